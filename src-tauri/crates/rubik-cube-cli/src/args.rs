@@ -0,0 +1,90 @@
+//! A small parser for `--flag`, `--option value` and positional arguments
+
+use std::str::FromStr;
+
+use crate::CliError;
+
+/// The arguments after the command name, consumed option by option
+pub struct Args {
+    rest: Vec<String>,
+}
+
+impl Args {
+    pub fn new(args: Vec<String>) -> Args {
+        Args { rest: args }
+    }
+
+    /// Whether `--name` was given
+    pub fn flag(&mut self, name: &str) -> bool {
+        let before = self.rest.len();
+        self.rest.retain(|arg| arg != name);
+        self.rest.len() != before
+    }
+
+    /// The value after `--name`, parsed; `None` when the option is absent
+    pub fn value<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, CliError>
+    where
+        T::Err: std::fmt::Display,
+    {
+        let Some(index) = self.rest.iter().position(|arg| arg == name) else {
+            return Ok(None);
+        };
+        if index + 1 == self.rest.len() {
+            return Err(CliError::Usage(format!("{} needs a value", name)));
+        }
+        let value = self.rest.remove(index + 1);
+        self.rest.remove(index);
+        value
+            .parse()
+            .map(Some)
+            .map_err(|e| CliError::Usage(format!("invalid value for {}: {}", name, e)))
+    }
+
+    /// Checks that only an optional input path is left and returns it
+    pub fn finish(self) -> Result<Option<String>, CliError> {
+        if let Some(option) = self
+            .rest
+            .iter()
+            .find(|arg| arg.starts_with("--") || (arg.starts_with('-') && arg.len() > 1))
+        {
+            return Err(CliError::Usage(format!("unknown option {}", option)));
+        }
+        let mut rest = self.rest.into_iter();
+        let path = rest.next();
+        if let Some(extra) = rest.next() {
+            return Err(CliError::Usage(format!("unexpected argument {}", extra)));
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Args {
+        Args::new(line.split_whitespace().map(String::from).collect())
+    }
+
+    #[test]
+    fn takes_options_in_any_order() {
+        let mut args = args("state.txt --stages --method two-phase");
+        assert_eq!(
+            args.value::<String>("--method").unwrap().unwrap(),
+            "two-phase"
+        );
+        assert!(args.flag("--stages"));
+        assert!(!args.flag("--plain"));
+        assert_eq!(args.finish().unwrap().unwrap(), "state.txt");
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(args("--length").value::<u32>("--length").is_err());
+        assert!(args("--length x").value::<u32>("--length").is_err());
+        assert!(args("--verbose").finish().is_err());
+        assert!(args("a b").finish().is_err());
+        // `-` 表示标准输入
+        assert_eq!(args("-").finish().unwrap().unwrap(), "-");
+    }
+}
@@ -0,0 +1,238 @@
+//! The subcommands. Results go to stdout, one item per line, so scripts can
+//! read them; the seed and errors go to stderr.
+
+use std::io::IsTerminal;
+
+use rubik_cube_core::cube::{
+    facelet::FaceletLetters, input::Suggestion, notation::Algorithm, Cube,
+};
+use rubik_cube_shuffler::{CubeShuffler, SCRAMBLE_LENGTH};
+use rubik_cube_solver::solver::{
+    execute_color_neutral, execute_with_method, SolveMethod, SolveTarget,
+};
+
+use crate::{
+    args::Args,
+    net,
+    state::{parse_state, read_input, to_json},
+    CliError,
+};
+
+const TARGETS: [(&str, SolveTarget); 11] = [
+    ("bottom-cross", SolveTarget::BottomCross),
+    ("bottom-corner", SolveTarget::BottomCorner),
+    ("middle-edge", SolveTarget::MiddleEdge),
+    ("top-cross", SolveTarget::TopCross),
+    ("top-face", SolveTarget::TopFace),
+    ("top-corner", SolveTarget::TopCorner),
+    ("top-edge", SolveTarget::TopEdge),
+    ("cross", SolveTarget::Cross),
+    ("f2l", SolveTarget::F2l),
+    ("oll", SolveTarget::Oll),
+    ("pll", SolveTarget::Pll),
+];
+
+fn parse_target(name: &str) -> Result<SolveTarget, CliError> {
+    TARGETS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, target)| target)
+        .ok_or_else(|| CliError::Usage(format!("unknown stage {}", name)))
+}
+
+fn target_name(target: SolveTarget) -> &'static str {
+    TARGETS.iter().find(|(_, t)| *t == target).unwrap().0
+}
+
+fn parse_method(name: Option<&str>) -> Result<SolveMethod, CliError> {
+    match name {
+        None | Some("layer") => Ok(SolveMethod::LayerByLayer),
+        Some("two-phase") => Ok(SolveMethod::two_phase()),
+        Some("cfop") => Ok(SolveMethod::Cfop),
+        Some(other) => Err(CliError::Usage(format!(
+            "unknown method {}, expected layer, two-phase or cfop",
+            other
+        ))),
+    }
+}
+
+/// ANSI colours only on a terminal, and never with `--plain` or `NO_COLOR`
+fn colored(plain: bool) -> bool {
+    !plain && std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+}
+
+fn read_state(args: Args) -> Result<Cube, CliError> {
+    parse_state(&read_input(args.finish()?.as_deref())?)
+}
+
+pub fn scramble(mut args: Args) -> Result<(), CliError> {
+    let length: Option<u32> = args.value("--length")?;
+    let random_state = args.flag("--random-state");
+    let subset: Option<String> = args.value("--subset")?;
+    let seed: Option<u64> = args.value("--seed")?;
+    let print_state = args.flag("--state");
+    let show = args.flag("--show");
+    let plain = args.flag("--plain");
+    if let Some(extra) = args.finish()? {
+        return Err(CliError::Usage(format!("unexpected argument {}", extra)));
+    }
+    let kinds = [length.is_some(), random_state, subset.is_some()];
+    if kinds.iter().filter(|&&kind| kind).count() > 1 {
+        return Err(CliError::Usage(
+            "--length, --random-state and --subset exclude each other".to_string(),
+        ));
+    }
+    let subset = subset.as_deref().map(parse_target).transpose()?;
+
+    let mut cube = Cube::new();
+    let mut shuffler = match seed {
+        Some(seed) => CubeShuffler::with_seed(&mut cube, seed),
+        None => CubeShuffler::new(&mut cube),
+    };
+    let scramble = if random_state {
+        shuffler.random_state_scramble()
+    } else if let Some(solved) = subset {
+        shuffler.subset_scramble(solved)
+    } else {
+        shuffler.scramble(length.unwrap_or(SCRAMBLE_LENGTH))
+    };
+    eprintln!("seed: {}", shuffler.seed().unwrap());
+
+    println!("{}", scramble);
+    if print_state {
+        println!("{}", cube.to_facelets(FaceletLetters::Faces));
+    }
+    if show {
+        print!("{}", net::render(&cube, colored(plain)));
+    }
+    Ok(())
+}
+
+pub fn solve(mut args: Args) -> Result<(), CliError> {
+    let method = parse_method(args.value::<String>("--method")?.as_deref())?;
+    let stages = args.flag("--stages");
+    let neutral = args.flag("--neutral");
+    let mut cube = read_state(args)?;
+    let target = match method {
+        SolveMethod::LayerByLayer => SolveTarget::TopEdge,
+        _ => SolveTarget::Pll,
+    };
+    let solution = if neutral {
+        execute_color_neutral(&mut cube, target, method)
+    } else {
+        execute_with_method(&mut cube, target, method)
+    }
+    .map_err(|e| CliError::Failed(e.to_string()))?;
+
+    if !stages {
+        println!("{}", solution.moves());
+        return Ok(());
+    }
+    for stage in &solution.stages {
+        match &stage.label {
+            Some(label) => println!(
+                "{}: {} // {}",
+                target_name(stage.target),
+                stage.moves,
+                label
+            ),
+            None => println!("{}: {}", target_name(stage.target), stage.moves),
+        }
+    }
+    Ok(())
+}
+
+pub fn verify(mut args: Args) -> Result<(), CliError> {
+    let solution: Option<Algorithm> = args.value("--solution")?;
+    let mut cube = read_state(args)?;
+    if let Err(error) = cube.validate() {
+        let mut message = format!("invalid state: {}", error);
+        for suggestion in cube.suggest_fixes() {
+            if !matches!(suggestion, Suggestion::Invalid(_)) {
+                message.push_str(&format!("\n  {}", suggestion));
+            }
+        }
+        return Err(CliError::Failed(message));
+    }
+
+    match solution {
+        Some(solution) => {
+            if let Err(error) = cube.try_apply_algorithm(&solution) {
+                return Err(CliError::Usage(format!(
+                    "invalid value for --solution: {}",
+                    error
+                )));
+            }
+            if !cube.is_solved() {
+                return Err(CliError::Failed(
+                    "the moves do not solve the cube".to_string(),
+                ));
+            }
+            println!("solved");
+        }
+        None => println!("valid"),
+    }
+    Ok(())
+}
+
+pub fn show(mut args: Args) -> Result<(), CliError> {
+    let moves: Option<Algorithm> = args.value("--moves")?;
+    let format: Option<String> = args.value("--format")?;
+    let plain = args.flag("--plain");
+    let mut cube = read_state(args)?;
+    if let Some(moves) = moves {
+        if let Err(error) = cube.try_apply_algorithm(&moves) {
+            return Err(CliError::Usage(format!(
+                "invalid value for --moves: {}",
+                error
+            )));
+        }
+    }
+    match format.as_deref() {
+        None | Some("net") => print!("{}", net::render(&cube, colored(plain))),
+        Some("facelets") => println!("{}", cube.to_facelets(FaceletLetters::Faces)),
+        Some("colors") => println!("{}", cube.to_facelets(FaceletLetters::Colors)),
+        Some("json") => println!("{}", to_json(&cube)),
+        Some(other) => {
+            return Err(CliError::Usage(format!(
+                "unknown format {}, expected net, facelets, colors or json",
+                other
+            )))
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_names_round_trip() {
+        for value in 0..=10 {
+            let target = SolveTarget::try_from(value).unwrap();
+            assert_eq!(parse_target(target_name(target)).unwrap(), target);
+        }
+        assert!(parse_target("f2").is_err());
+        assert!(parse_method(Some("layers")).is_err());
+    }
+
+    #[test]
+    fn moves_for_bigger_cubes_are_usage_errors() {
+        let path = std::env::temp_dir().join("rubik-cli-bigger-moves.txt");
+        std::fs::write(&path, Cube::new().to_facelets(FaceletLetters::Faces)).unwrap();
+        let args = |option: &str, moves: &str| {
+            let path = path.to_str().unwrap().to_string();
+            Args::new(vec![option.to_string(), moves.to_string(), path])
+        };
+        let shown = show(args("--moves", "5R"));
+        let verified = verify(args("--solution", "4Rw"));
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(shown, Err(CliError::Usage(_))), "{:?}", shown);
+        assert!(
+            matches!(verified, Err(CliError::Usage(_))),
+            "{:?}",
+            verified
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! `rubik`: scrambles, solves, checks and prints cubes without the app.
+//! States are read from a file, or stdin when none is given.
+
+mod args;
+mod commands;
+mod net;
+mod state;
+
+use std::process::ExitCode;
+
+use args::Args;
+
+const USAGE: &str = "\
+usage: rubik <command> [options] [FILE]
+
+commands:
+  scramble [--length N | --random-state | --subset STAGE] [--seed S] [--state] [--show]
+      print a scramble; --state also prints the scrambled state, --show its net
+  solve [--method layer|two-phase|cfop] [--stages] [--neutral] [FILE]
+      print the moves solving the state, one stage per line with --stages;
+      --neutral starts on the bottom colour giving the fewest moves
+  verify [--solution MOVES] [FILE]
+      check that the state is possible, and that MOVES solve it
+  show [--moves MOVES] [--format net|facelets|colors|json] [--plain] [FILE]
+      print the state, after MOVES if given
+
+States are the app's JSON colour array or a 54-letter facelet string in
+Kociemba's face order U R F D L B, with face letters (URFDLB) or colour
+letters (YWBGOR). FILE `-` or no FILE reads stdin. Stages: bottom-cross,
+bottom-corner, middle-edge, top-cross, top-face, top-corner, top-edge,
+cross, f2l, oll, pll.
+";
+
+#[derive(Debug)]
+pub enum CliError {
+    /// Bad command line, exits with 2 after the usage
+    Usage(String),
+    /// The command ran but failed, exits with 1
+    Failed(String),
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(command) = args.next() else {
+        eprint!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+    let args = Args::new(args.collect());
+    let result = match command.as_str() {
+        "scramble" => commands::scramble(args),
+        "solve" => commands::solve(args),
+        "verify" => commands::verify(args),
+        "show" => commands::show(args),
+        "help" | "-h" | "--help" => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        _ => Err(CliError::Usage(format!("unknown command {}", command))),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Usage(message)) => {
+            eprintln!("rubik: {}\n\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+        Err(CliError::Failed(message)) => {
+            eprintln!("rubik: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! The unfolded cube as text: Up on top, Left Front Right Back in the
+//! middle row and Down below, each face as seen from outside the cube
+
+use rubik_cube_core::cube::{color::Color, face::Face, Cube};
+
+/// 256-colour ANSI background for each colour
+fn ansi_background(color: Color) -> u8 {
+    match color {
+        Color::Yellow => 226,
+        Color::White => 255,
+        Color::Blue => 27,
+        Color::Green => 34,
+        Color::Orange => 208,
+        Color::Red => 196,
+    }
+}
+
+fn sticker(color: Color, colored: bool) -> String {
+    if colored {
+        format!("\x1b[48;5;{}m  \x1b[0m", ansi_background(color))
+    } else {
+        format!("{} ", color.letter())
+    }
+}
+
+/// Every sticker takes two columns; `colored` paints them with ANSI
+/// backgrounds, otherwise they are shown as colour letters.
+pub fn render(cube: &Cube, colored: bool) -> String {
+    let bands: [&[Option<Face>]; 3] = [
+        &[None, Some(Face::Up)],
+        &[
+            Some(Face::Left),
+            Some(Face::Front),
+            Some(Face::Right),
+            Some(Face::Back),
+        ],
+        &[None, Some(Face::Down)],
+    ];
+    let mut net = String::new();
+    for band in bands {
+        for row in 0..3 {
+            let mut line = String::new();
+            for face in band {
+                match face {
+                    Some(face) => {
+                        for color in cube.get_face_state(face.ordinal())[row] {
+                            line.push_str(&sticker(color, colored));
+                        }
+                    }
+                    None => line.push_str("      "),
+                }
+            }
+            net.push_str(line.trim_end());
+            net.push('\n');
+        }
+    }
+    net
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lays_out_the_faces_around_front() {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&"R".parse().unwrap());
+        let expected = "      Y Y B\n      Y Y B\n      Y Y B\n\
+                        O O O B B W R R R Y G G\n\
+                        O O O B B W R R R Y G G\n\
+                        O O O B B W R R R Y G G\n      \
+                        W W G\n      W W G\n      W W G\n";
+        assert_eq!(render(&cube, false), expected);
+    }
+}
@@ -0,0 +1,83 @@
+//! Cube states as text: the app's JSON colour array (`[[[u8; 3]; 3]; 6]`)
+//! or a 54-letter facelet string in Kociemba's U R F D L B order, with face
+//! or colour letters.
+
+use std::io::Read;
+
+use rubik_cube_core::cube::{color::Color, Cube};
+use rubik_cube_solver::utils::color_state_to_u8;
+
+use crate::CliError;
+
+/// Reads the input file, or stdin for `None` and `-`
+pub fn read_input(path: Option<&str>) -> Result<String, CliError> {
+    match path {
+        None | Some("-") => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| CliError::Failed(format!("cannot read stdin: {}", e)))?;
+            Ok(input)
+        }
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|e| CliError::Failed(format!("cannot read {}: {}", path, e))),
+    }
+}
+
+/// Parses either format. The state is not validated, so impossible cubes
+/// can still be shown and checked.
+pub fn parse_state(input: &str) -> Result<Cube, CliError> {
+    let input = input.trim();
+    let mut state = [[[Color::White; 3]; 3]; 6];
+    if input.starts_with('[') {
+        let values: [[[u8; 3]; 3]; 6] = serde_json::from_str(input)
+            .map_err(|e| CliError::Failed(format!("invalid JSON state: {}", e)))?;
+        for (face, rows) in values.iter().enumerate() {
+            for (row, colors) in rows.iter().enumerate() {
+                for (col, &value) in colors.iter().enumerate() {
+                    state[face][row][col] = Color::try_from(value)
+                        .map_err(|e| CliError::Failed(format!("invalid JSON state: {}", e)))?;
+                }
+            }
+        }
+        return Ok(Cube::from_state(state));
+    }
+
+    Cube::from_facelets(input)
+        .map_err(|e| CliError::Failed(format!("invalid facelet string: {}", e)))
+}
+
+pub fn to_json(cube: &Cube) -> String {
+    serde_json::to_string(&color_state_to_u8(cube)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use rubik_cube_core::cube::facelet::FaceletLetters;
+
+    use super::*;
+
+    fn scrambled() -> Cube {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&"R U2 F' L D B2".parse().unwrap());
+        cube
+    }
+
+    #[test]
+    fn all_formats_round_trip() {
+        let cube = scrambled();
+        for letters in [FaceletLetters::Faces, FaceletLetters::Colors] {
+            assert_eq!(parse_state(&cube.to_facelets(letters)).unwrap(), cube);
+        }
+        assert_eq!(parse_state(&to_json(&cube)).unwrap(), cube);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(parse_state("YYY").is_err());
+        assert!(parse_state(&"X".repeat(54)).is_err());
+        assert!(parse_state("[[[0, 1, 2]]]").is_err());
+        let json = to_json(&Cube::new()).replacen('0', "6", 1);
+        assert!(parse_state(&json).is_err());
+    }
+}
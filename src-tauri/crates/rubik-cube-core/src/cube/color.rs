@@ -1,3 +1,4 @@
+use super::error::CubeError;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -34,4 +35,39 @@ impl Color {
             _ => panic!("Invalid color value: {}", value),
         }
     }
+
+    /// The colour's initial, as in colour-letter facelet strings
+    pub fn letter(&self) -> char {
+        match self {
+            Color::Yellow => 'Y',
+            Color::White => 'W',
+            Color::Blue => 'B',
+            Color::Green => 'G',
+            Color::Orange => 'O',
+            Color::Red => 'R',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Color> {
+        match letter.to_ascii_uppercase() {
+            'Y' => Some(Color::Yellow),
+            'W' => Some(Color::White),
+            'B' => Some(Color::Blue),
+            'G' => Some(Color::Green),
+            'O' => Some(Color::Orange),
+            'R' => Some(Color::Red),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<u8> for Color {
+    type Error = CubeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0..=5 => Ok(Color::from_u8(value)),
+            _ => Err(CubeError::InvalidColorValue(value)),
+        }
+    }
 }
@@ -0,0 +1,341 @@
+//! Cubie-level representation: corner and edge slots, the facelets they
+//! occupy, and `CubieCube`, the permutation/orientation view of a cube
+
+use super::color::Color;
+use super::error::CubeError;
+use super::face::Face;
+use super::Cube;
+
+/// Corner slots, named by the faces they touch.
+/// The first face is always Up or Down, the rest follow clockwise.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Corner {
+    URF,
+    UFL,
+    ULB,
+    UBR,
+    DFR,
+    DLF,
+    DBL,
+    DRB,
+}
+
+/// Edge slots, named by the faces they touch.
+/// The first face is the reference for the edge orientation.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Edge {
+    UR,
+    UF,
+    UL,
+    UB,
+    DR,
+    DF,
+    DL,
+    DB,
+    FR,
+    FL,
+    BL,
+    BR,
+}
+
+/// A sticker position: face, row, column
+pub type Facelet = (Face, usize, usize);
+
+const CORNER_FACELETS: [[Facelet; 3]; 8] = [
+    [(Face::Up, 2, 2), (Face::Right, 0, 0), (Face::Front, 0, 2)],
+    [(Face::Up, 2, 0), (Face::Front, 0, 0), (Face::Left, 0, 2)],
+    [(Face::Up, 0, 0), (Face::Left, 0, 0), (Face::Back, 0, 2)],
+    [(Face::Up, 0, 2), (Face::Back, 0, 0), (Face::Right, 0, 2)],
+    [(Face::Down, 0, 2), (Face::Front, 2, 2), (Face::Right, 2, 0)],
+    [(Face::Down, 0, 0), (Face::Left, 2, 2), (Face::Front, 2, 0)],
+    [(Face::Down, 2, 0), (Face::Back, 2, 2), (Face::Left, 2, 0)],
+    [(Face::Down, 2, 2), (Face::Right, 2, 2), (Face::Back, 2, 0)],
+];
+
+const EDGE_FACELETS: [[Facelet; 2]; 12] = [
+    [(Face::Up, 1, 2), (Face::Right, 0, 1)],
+    [(Face::Up, 2, 1), (Face::Front, 0, 1)],
+    [(Face::Up, 1, 0), (Face::Left, 0, 1)],
+    [(Face::Up, 0, 1), (Face::Back, 0, 1)],
+    [(Face::Down, 1, 2), (Face::Right, 2, 1)],
+    [(Face::Down, 0, 1), (Face::Front, 2, 1)],
+    [(Face::Down, 1, 0), (Face::Left, 2, 1)],
+    [(Face::Down, 2, 1), (Face::Back, 2, 1)],
+    [(Face::Front, 1, 2), (Face::Right, 1, 0)],
+    [(Face::Front, 1, 0), (Face::Left, 1, 2)],
+    [(Face::Back, 1, 2), (Face::Left, 1, 0)],
+    [(Face::Back, 1, 0), (Face::Right, 1, 2)],
+];
+
+impl Corner {
+    pub const ALL: [Corner; 8] = [
+        Corner::URF,
+        Corner::UFL,
+        Corner::ULB,
+        Corner::UBR,
+        Corner::DFR,
+        Corner::DLF,
+        Corner::DBL,
+        Corner::DRB,
+    ];
+
+    pub fn from_index(index: usize) -> Corner {
+        Self::ALL[index]
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn facelets(&self) -> [Facelet; 3] {
+        CORNER_FACELETS[self.index()]
+    }
+
+    pub fn faces(&self) -> [Face; 3] {
+        self.facelets().map(|(face, _, _)| face)
+    }
+}
+
+impl Edge {
+    pub const ALL: [Edge; 12] = [
+        Edge::UR,
+        Edge::UF,
+        Edge::UL,
+        Edge::UB,
+        Edge::DR,
+        Edge::DF,
+        Edge::DL,
+        Edge::DB,
+        Edge::FR,
+        Edge::FL,
+        Edge::BL,
+        Edge::BR,
+    ];
+
+    pub fn from_index(index: usize) -> Edge {
+        Self::ALL[index]
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn facelets(&self) -> [Facelet; 2] {
+        EDGE_FACELETS[self.index()]
+    }
+
+    pub fn faces(&self) -> [Face; 2] {
+        self.facelets().map(|(face, _, _)| face)
+    }
+}
+
+/// A cube described by which cubie sits in each slot and how it is turned.
+///
+/// `cp[i]` is the corner in slot `i` and `co[i]` its clockwise twist (0..3),
+/// `ep[i]` is the edge in slot `i` and `eo[i]` its flip (0..2).
+/// Pieces are located relative to the centres, so whole-cube orientation is
+/// not part of this representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CubieCube {
+    pub cp: [Corner; 8],
+    pub co: [u8; 8],
+    pub ep: [Edge; 12],
+    pub eo: [u8; 12],
+}
+
+impl Default for CubieCube {
+    fn default() -> Self {
+        Self::solved()
+    }
+}
+
+impl CubieCube {
+    pub fn solved() -> CubieCube {
+        CubieCube {
+            cp: Corner::ALL,
+            co: [0; 8],
+            ep: Edge::ALL,
+            eo: [0; 12],
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::solved()
+    }
+
+    /// The state reached by applying `other` after `self`.
+    pub fn multiply(&self, other: &CubieCube) -> CubieCube {
+        let mut result = CubieCube::solved();
+        for i in 0..8 {
+            let from = other.cp[i].index();
+            result.cp[i] = self.cp[from];
+            result.co[i] = (self.co[from] + other.co[i]) % 3;
+        }
+        for i in 0..12 {
+            let from = other.ep[i].index();
+            result.ep[i] = self.ep[from];
+            result.eo[i] = (self.eo[from] + other.eo[i]) % 2;
+        }
+        result
+    }
+
+    pub fn inverse(&self) -> CubieCube {
+        let mut result = CubieCube::solved();
+        for i in 0..8 {
+            let to = self.cp[i].index();
+            result.cp[to] = Corner::from_index(i);
+            result.co[to] = (3 - self.co[i]) % 3;
+        }
+        for i in 0..12 {
+            let to = self.ep[i].index();
+            result.ep[to] = Edge::from_index(i);
+            result.eo[to] = self.eo[i];
+        }
+        result
+    }
+
+    pub fn corner_parity(&self) -> u8 {
+        permutation_parity(&self.cp.map(|c| c.index()))
+    }
+
+    pub fn edge_parity(&self) -> u8 {
+        permutation_parity(&self.ep.map(|e| e.index()))
+    }
+
+    /// Checks the invariants every reachable state satisfies.
+    pub fn verify(&self) -> Result<(), CubeError> {
+        let mut corner_seen: [Option<Corner>; 8] = [None; 8];
+        for position in Corner::ALL {
+            let cubie = self.cp[position.index()];
+            if let Some(other) = corner_seen[cubie.index()] {
+                return Err(CubeError::DuplicateCorner {
+                    corner: cubie,
+                    positions: [other, position],
+                });
+            }
+            corner_seen[cubie.index()] = Some(position);
+        }
+        let mut edge_seen: [Option<Edge>; 12] = [None; 12];
+        for position in Edge::ALL {
+            let cubie = self.ep[position.index()];
+            if let Some(other) = edge_seen[cubie.index()] {
+                return Err(CubeError::DuplicateEdge {
+                    edge: cubie,
+                    positions: [other, position],
+                });
+            }
+            edge_seen[cubie.index()] = Some(position);
+        }
+
+        let twist: u32 = self.co.iter().map(|&t| t as u32).sum();
+        if !twist.is_multiple_of(3) {
+            return Err(CubeError::CornerTwist {
+                sum: (twist % 3) as u8,
+            });
+        }
+        let flip: u32 = self.eo.iter().map(|&f| f as u32).sum();
+        if !flip.is_multiple_of(2) {
+            return Err(CubeError::EdgeFlip {
+                sum: (flip % 2) as u8,
+            });
+        }
+        if self.corner_parity() != self.edge_parity() {
+            return Err(CubeError::Parity);
+        }
+        Ok(())
+    }
+
+    /// Paints the facelets of this state in the default colour scheme.
+    pub fn to_cube(&self) -> Cube {
+        let mut cube = Cube::new();
+        for position in Corner::ALL {
+            let cubie = self.cp[position.index()];
+            let twist = self.co[position.index()] as usize;
+            let colors: [Color; 3] = cubie.faces().map(|f| f.color());
+            for (k, (face, row, col)) in position.facelets().into_iter().enumerate() {
+                cube.set_block_color(face.ordinal(), row, col, colors[(k + 3 - twist) % 3]);
+            }
+        }
+        for position in Edge::ALL {
+            let cubie = self.ep[position.index()];
+            let flip = self.eo[position.index()] as usize;
+            let colors: [Color; 2] = cubie.faces().map(|f| f.color());
+            for (k, (face, row, col)) in position.facelets().into_iter().enumerate() {
+                cube.set_block_color(face.ordinal(), row, col, colors[(k + flip) % 2]);
+            }
+        }
+        cube
+    }
+}
+
+impl TryFrom<&Cube> for CubieCube {
+    type Error = CubeError;
+
+    fn try_from(cube: &Cube) -> Result<Self, Self::Error> {
+        cube.identify_cubies()
+    }
+}
+
+impl From<&CubieCube> for Cube {
+    fn from(cubie_cube: &CubieCube) -> Self {
+        cubie_cube.to_cube()
+    }
+}
+
+/// 0 for an even permutation, 1 for an odd one
+pub(crate) fn permutation_parity(perm: &[usize]) -> u8 {
+    let mut inversions = 0;
+    for i in 0..perm.len() {
+        for j in i + 1..perm.len() {
+            if perm[i] > perm[j] {
+                inversions += 1;
+            }
+        }
+    }
+    (inversions % 2) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+
+    use super::*;
+
+    fn random_cubie_cube(rng: &mut impl Rng) -> CubieCube {
+        let mut cubie_cube = CubieCube::solved();
+        cubie_cube.cp.shuffle(rng);
+        cubie_cube.ep.shuffle(rng);
+        for i in 0..8 {
+            cubie_cube.co[i] = rng.gen_range(0..3);
+        }
+        for i in 0..12 {
+            cubie_cube.eo[i] = rng.gen_range(0..2);
+        }
+        cubie_cube
+    }
+
+    #[test]
+    fn round_trips_through_facelets() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let cubie_cube = random_cubie_cube(&mut rng);
+            let cube = cubie_cube.to_cube();
+            assert_eq!(CubieCube::try_from(&cube), Ok(cubie_cube));
+        }
+    }
+
+    #[test]
+    fn multiply_by_inverse_is_identity() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let cubie_cube = random_cubie_cube(&mut rng);
+            assert!(cubie_cube.multiply(&cubie_cube.inverse()).is_solved());
+            assert!(cubie_cube.inverse().multiply(&cubie_cube).is_solved());
+        }
+    }
+}
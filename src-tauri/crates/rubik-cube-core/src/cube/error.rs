@@ -0,0 +1,108 @@
+//! Errors reported for impossible cube states
+
+use std::fmt;
+
+use super::color::Color;
+use super::cubie::{Corner, Edge};
+use super::notation::Move;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CubeError {
+    /// A raw colour value outside `0..=5`
+    InvalidColorValue(u8),
+    /// A colour does not appear exactly 9 times
+    ColorCount { color: Color, count: usize },
+    /// Two faces share the same centre colour
+    DuplicateCenter { color: Color },
+    /// The stickers in a corner slot do not form a real corner
+    InvalidCorner {
+        position: Corner,
+        colors: [Color; 3],
+    },
+    /// The stickers in an edge slot do not form a real edge
+    InvalidEdge { position: Edge, colors: [Color; 2] },
+    /// The same corner cubie appears in two slots
+    DuplicateCorner {
+        corner: Corner,
+        positions: [Corner; 2],
+    },
+    /// The same edge cubie appears in two slots
+    DuplicateEdge { edge: Edge, positions: [Edge; 2] },
+    /// The corner twists do not add up to a multiple of 3
+    CornerTwist { sum: u8 },
+    /// The edge flips do not add up to a multiple of 2
+    EdgeFlip { sum: u8 },
+    /// Corner and edge permutations have different parity
+    Parity,
+    /// A move that needs a bigger cube, see [`Move::fits`]
+    MoveDoesNotFit { mv: Move, size: usize },
+    /// A cube of another size than the one the caller works with
+    WrongSize { size: usize, expected: usize },
+}
+
+impl fmt::Display for CubeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CubeError::InvalidColorValue(value) => write!(f, "invalid color value: {}", value),
+            CubeError::ColorCount { color, count } => {
+                write!(f, "color {:?} appears {} times, expected 9", color, count)
+            }
+            CubeError::DuplicateCenter { color } => {
+                write!(f, "color {:?} is on more than one center", color)
+            }
+            CubeError::InvalidCorner { position, colors } => {
+                write!(
+                    f,
+                    "corner {:?} has impossible colors {:?}",
+                    position, colors
+                )
+            }
+            CubeError::InvalidEdge { position, colors } => {
+                write!(f, "edge {:?} has impossible colors {:?}", position, colors)
+            }
+            CubeError::DuplicateCorner { corner, positions } => write!(
+                f,
+                "corner {:?} appears at both {:?} and {:?}",
+                corner, positions[0], positions[1]
+            ),
+            CubeError::DuplicateEdge { edge, positions } => write!(
+                f,
+                "edge {:?} appears at both {:?} and {:?}",
+                edge, positions[0], positions[1]
+            ),
+            CubeError::CornerTwist { sum } => {
+                write!(f, "corner twist sum is {} (mod 3), expected 0", sum)
+            }
+            CubeError::EdgeFlip { sum } => {
+                write!(f, "edge flip sum is {} (mod 2), expected 0", sum)
+            }
+            CubeError::Parity => write!(f, "corner and edge permutation parity differ"),
+            CubeError::MoveDoesNotFit { mv, size } => {
+                write!(f, "{} does not fit a {}x{} cube", mv, size, size)
+            }
+            CubeError::WrongSize { size, expected } => write!(
+                f,
+                "expected a {}x{} cube, got {}x{}",
+                expected, expected, size, size
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CubeError {}
+
+/// A numeric code, as sent by the app's frontend, that names no known value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownCode {
+    /// What the code was meant to name, e.g. `"stage"`
+    pub kind: &'static str,
+    pub value: u8,
+}
+
+impl fmt::Display for UnknownCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown {} {}", self.kind, self.value)
+    }
+}
+
+impl std::error::Error for UnknownCode {}
@@ -1,54 +1,38 @@
 use super::color::Color;
+use super::error::UnknownCode;
+use super::scheme::ColorScheme;
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 #[repr(u8)]
 enum FaceOrientation {
-    Up(Color),
-    Down(Color),
-    Front(Color),
-    Back(Color),
-    Left(Color),
-    Right(Color),
+    Up,
+    Down,
+    Front,
+    Back,
+    Left,
+    Right,
 }
 impl From<u8> for FaceOrientation {
     fn from(value: u8) -> Self {
         match value {
-            0 => FaceOrientation::Up(Color::Yellow),
-            1 => FaceOrientation::Down(Color::White),
-            2 => FaceOrientation::Front(Color::Blue),
-            3 => FaceOrientation::Back(Color::Green),
-            4 => FaceOrientation::Left(Color::Orange),
-            5 => FaceOrientation::Right(Color::Red),
+            0 => FaceOrientation::Up,
+            1 => FaceOrientation::Down,
+            2 => FaceOrientation::Front,
+            3 => FaceOrientation::Back,
+            4 => FaceOrientation::Left,
+            5 => FaceOrientation::Right,
             _ => panic!("Invalid face orientation value: {}", value),
         }
     }
 }
 impl FaceOrientation {
     pub fn ordinal(&self) -> usize {
-        match self {
-            FaceOrientation::Up(_) => 0,
-            FaceOrientation::Down(_) => 1,
-            FaceOrientation::Front(_) => 2,
-            FaceOrientation::Back(_) => 3,
-            FaceOrientation::Left(_) => 4,
-            FaceOrientation::Right(_) => 5,
-        }
+        *self as usize
     }
 
     pub fn values() -> Vec<Self> {
         (0..6).map(|i| Self::from(i as u8)).collect()
     }
-
-    pub fn color(&self) -> Color {
-        match self {
-            FaceOrientation::Up(c) => *c,
-            FaceOrientation::Down(c) => *c,
-            FaceOrientation::Front(c) => *c,
-            FaceOrientation::Back(c) => *c,
-            FaceOrientation::Left(c) => *c,
-            FaceOrientation::Right(c) => *c,
-        }
-    }
 }
 
 #[derive(PartialEq, Debug, Clone, Copy)]
@@ -57,7 +41,7 @@ pub enum TwistDirection {
     CounterClockwise,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 pub enum Face {
     Up,
     Down,
@@ -70,12 +54,12 @@ pub enum Face {
 impl From<&Face> for FaceOrientation {
     fn from(face: &Face) -> Self {
         match face {
-            Face::Up => FaceOrientation::Up(Color::Yellow),
-            Face::Down => FaceOrientation::Down(Color::White),
-            Face::Front => FaceOrientation::Front(Color::Blue),
-            Face::Right => FaceOrientation::Right(Color::Red),
-            Face::Back => FaceOrientation::Back(Color::Green),
-            Face::Left => FaceOrientation::Left(Color::Orange),
+            Face::Up => FaceOrientation::Up,
+            Face::Down => FaceOrientation::Down,
+            Face::Front => FaceOrientation::Front,
+            Face::Right => FaceOrientation::Right,
+            Face::Back => FaceOrientation::Back,
+            Face::Left => FaceOrientation::Left,
         }
     }
 }
@@ -83,20 +67,21 @@ impl From<&Face> for FaceOrientation {
 impl From<&FaceOrientation> for Face {
     fn from(orientation: &FaceOrientation) -> Self {
         match orientation {
-            FaceOrientation::Up(_) => Face::Up,
-            FaceOrientation::Down(_) => Face::Down,
-            FaceOrientation::Front(_) => Face::Front,
-            FaceOrientation::Back(_) => Face::Back,
-            FaceOrientation::Left(_) => Face::Left,
-            FaceOrientation::Right(_) => Face::Right,
+            FaceOrientation::Up => Face::Up,
+            FaceOrientation::Down => Face::Down,
+            FaceOrientation::Front => Face::Front,
+            FaceOrientation::Back => Face::Back,
+            FaceOrientation::Left => Face::Left,
+            FaceOrientation::Right => Face::Right,
         }
     }
 }
 
 impl Face {
+    /// The face's colour in the default scheme. Solvers read the colours
+    /// off the centres instead, see [`ColorScheme`].
     pub fn color(&self) -> Color {
-        let orientation: FaceOrientation = self.into();
-        orientation.color()
+        ColorScheme::default().color(*self)
     }
 
     pub fn ordinal(&self) -> usize {
@@ -109,8 +94,18 @@ impl Face {
     }
 }
 
-impl From<u8> for Face {
-    fn from(value: u8) -> Self {
-        (&FaceOrientation::from(value)).into()
+/// 0 Up, 1 Down, 2 Front, 3 Back, 4 Left, 5 Right
+impl TryFrom<u8> for Face {
+    type Error = UnknownCode;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if value < 6 {
+            Ok((&FaceOrientation::from(value)).into())
+        } else {
+            Err(UnknownCode {
+                kind: "face",
+                value,
+            })
+        }
     }
 }
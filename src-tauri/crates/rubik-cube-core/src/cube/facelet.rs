@@ -0,0 +1,241 @@
+//! The 54-character facelet strings used by Kociemba's solver and most
+//! other cube programs, e.g. `UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB`
+//! for the solved cube.
+//!
+//! The string lists the faces in the order U R F D L B, each row by row as
+//! seen from outside: U with B on top, D with F on top, the side faces with
+//! U on top. These are the rows and columns of [`Cube::state`], only the
+//! face order differs from its U D F B L R.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::color::Color;
+use super::face::Face;
+use super::notation::{face_letter, letter_face};
+use super::scheme::ColorScheme;
+use super::Cube;
+
+/// The faces in facelet-string order
+pub const FACELET_ORDER: [Face; 6] = [
+    Face::Up,
+    Face::Right,
+    Face::Front,
+    Face::Down,
+    Face::Left,
+    Face::Back,
+];
+
+/// What the letters of a facelet string stand for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaceletLetters {
+    /// The face whose centre has the sticker's colour (`URFDLB`), the
+    /// format Kociemba's solver reads
+    Faces,
+    /// The sticker's colour (`YWBGOR`), see [`Color::letter`]
+    Colors,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FaceletError {
+    /// The string does not have 54 letters, whitespace aside
+    Length(usize),
+    /// A letter that is neither a face nor a colour
+    InvalidLetter { letter: char, position: usize },
+    /// Face letters only used by faces (`U D F L`) together with colour
+    /// letters only used by colours (`Y W G O`)
+    MixedLetters,
+}
+
+impl fmt::Display for FaceletError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FaceletError::Length(length) => {
+                write!(f, "facelet string has {} letters, expected 54", length)
+            }
+            FaceletError::InvalidLetter { letter, position } => {
+                write!(f, "invalid letter '{}' at position {}", letter, position)
+            }
+            FaceletError::MixedLetters => {
+                write!(f, "facelet string mixes face and colour letters")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FaceletError {}
+
+impl Cube {
+    /// Parses a facelet string of either kind of letters, ignoring
+    /// whitespace and case. Face letters stand for the colour of that face
+    /// in the default scheme. The state is not validated, see
+    /// [`Cube::validate`].
+    pub fn from_facelets(facelets: &str) -> Result<Cube, FaceletError> {
+        Cube::from_facelets_with_scheme(facelets, ColorScheme::default())
+    }
+
+    /// Like [`Cube::from_facelets`], with face letters painted in `scheme`
+    pub fn from_facelets_with_scheme(
+        facelets: &str,
+        scheme: ColorScheme,
+    ) -> Result<Cube, FaceletError> {
+        let letters: Vec<char> = facelets
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if letters.len() != 54 {
+            return Err(FaceletError::Length(letters.len()));
+        }
+        // B 和 R 既是面也是颜色，要靠其他字母判断是哪一种
+        let faces = letters.iter().any(|c| "UDFL".contains(*c));
+        let colors = letters.iter().any(|c| "YWGO".contains(*c));
+        let kind = match (faces, colors) {
+            (true, true) => return Err(FaceletError::MixedLetters),
+            (_, false) => FaceletLetters::Faces,
+            (false, true) => FaceletLetters::Colors,
+        };
+
+        let mut state = [[[Color::White; 3]; 3]; 6];
+        for (position, &letter) in letters.iter().enumerate() {
+            let color = match kind {
+                FaceletLetters::Faces => letter_face(letter).map(|face| scheme.color(face)),
+                FaceletLetters::Colors => Color::from_letter(letter),
+            }
+            .ok_or(FaceletError::InvalidLetter { letter, position })?;
+            let face = FACELET_ORDER[position / 9];
+            state[face.ordinal()][position / 3 % 3][position % 3] = color;
+        }
+        Ok(Cube::from_state(state))
+    }
+
+    /// Formats the cube as a facelet string. With [`FaceletLetters::Faces`]
+    /// the letters follow the current centres, so a rotated cube still
+    /// reads `U` on its upper centre.
+    pub fn to_facelets(&self, letters: FaceletLetters) -> String {
+        let letter = |color: Color| match letters {
+            FaceletLetters::Colors => color.letter(),
+            FaceletLetters::Faces => {
+                let face = Face::values()
+                    .into_iter()
+                    .find(|face| self.state[face.ordinal()][1][1] == color)
+                    .unwrap_or_else(|| Face::try_from(color as u8).unwrap());
+                face_letter(face)
+            }
+        };
+        FACELET_ORDER
+            .iter()
+            .flat_map(|face| self.state[face.ordinal()].iter().flatten())
+            .map(|&color| letter(color))
+            .collect()
+    }
+}
+
+impl FromStr for Cube {
+    type Err = FaceletError;
+
+    fn from_str(s: &str) -> Result<Cube, FaceletError> {
+        Cube::from_facelets(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLVED: &str = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+    #[test]
+    fn formats_the_solved_cube() {
+        let cube = Cube::new();
+        assert_eq!(cube.to_facelets(FaceletLetters::Faces), SOLVED);
+        assert_eq!(
+            cube.to_facelets(FaceletLetters::Colors),
+            "YYYYYYYYYRRRRRRRRRBBBBBBBBBWWWWWWWWWOOOOOOOOOGGGGGGGGG"
+        );
+        assert_eq!(SOLVED.parse::<Cube>().unwrap(), cube);
+    }
+
+    #[test]
+    fn paints_face_letters_in_any_scheme() {
+        let japanese = Cube::with_scheme(ColorScheme::JAPANESE);
+        assert_eq!(
+            Cube::from_facelets_with_scheme(SOLVED, ColorScheme::JAPANESE).unwrap(),
+            japanese
+        );
+        assert_eq!(japanese.to_facelets(FaceletLetters::Faces), SOLVED);
+    }
+
+    #[test]
+    fn matches_kociemba_for_single_turns() {
+        // 与 Kociemba 的 facelet 编号（U1..U9 R1..R9 ...）对照
+        let cases = [
+            (
+                "R",
+                "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB",
+            ),
+            (
+                "U",
+                "UUUUUUUUUBBBRRRRRRRRRFFFFFFDDDDDDDDDFFFLLLLLLLLLBBBBBB",
+            ),
+            (
+                "F",
+                "UUUUUULLLURRURRURRFFFFFFFFFRRRDDDDDDLLDLLDLLDBBBBBBBBB",
+            ),
+        ];
+        for (moves, facelets) in cases {
+            let mut cube = Cube::new();
+            cube.apply_algorithm(&moves.parse().unwrap());
+            assert_eq!(
+                cube.to_facelets(FaceletLetters::Faces),
+                facelets,
+                "{}",
+                moves
+            );
+            assert_eq!(Cube::from_facelets(facelets).unwrap(), cube);
+        }
+    }
+
+    #[test]
+    fn round_trips_both_letter_kinds() {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&"R U2 F' L D2 B R2 U'".parse().unwrap());
+        for letters in [FaceletLetters::Faces, FaceletLetters::Colors] {
+            let facelets = cube.to_facelets(letters);
+            assert_eq!(Cube::from_facelets(&facelets).unwrap(), cube);
+            let spaced: String = facelets
+                .to_lowercase()
+                .chars()
+                .enumerate()
+                .flat_map(|(i, c)| if i % 9 == 8 { vec![c, '\n'] } else { vec![c] })
+                .collect();
+            assert_eq!(Cube::from_facelets(&spaced).unwrap(), cube);
+        }
+    }
+
+    #[test]
+    fn face_letters_follow_the_centres() {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&"y".parse().unwrap());
+        assert_eq!(cube.to_facelets(FaceletLetters::Faces), SOLVED);
+        assert_ne!(
+            cube.to_facelets(FaceletLetters::Colors),
+            Cube::new().to_facelets(FaceletLetters::Colors)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_strings() {
+        assert_eq!(Cube::from_facelets("UUU"), Err(FaceletError::Length(3)));
+        let mixed = SOLVED.replacen('U', "Y", 1);
+        assert_eq!(Cube::from_facelets(&mixed), Err(FaceletError::MixedLetters));
+        let invalid = SOLVED.replacen('F', "X", 1);
+        assert_eq!(
+            Cube::from_facelets(&invalid),
+            Err(FaceletError::InvalidLetter {
+                letter: 'X',
+                position: 18
+            })
+        );
+    }
+}
@@ -0,0 +1,608 @@
+//! Sticker layouts entered by hand: missing stickers are filled in when only
+//! one real cube fits the rest, and impossible layouts come with suggestions
+//! for what was most likely entered wrong.
+
+use std::fmt;
+
+use super::color::Color;
+use super::cubie::{Corner, CubieCube, Edge, Facelet};
+use super::error::CubeError;
+use super::face::Face;
+use super::notation::face_letter;
+use super::Cube;
+
+/// Stickers by face, row and column; `None` for a sticker not entered yet
+pub type PartialState = [[[Option<Color>; 3]; 3]; 6];
+
+/// Completions tried before giving up on telling whether one is unique
+const SEARCH_LIMIT: usize = 200_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Suggestion {
+    /// The sticker probably has another colour
+    Recolor { facelet: Facelet, color: Color },
+    /// Exchanging the two stickers makes the cube valid
+    SwapStickers { facelets: [Facelet; 2] },
+    /// Turning the corner in its slot fixes the twist
+    TwistCorner { position: Corner, clockwise: bool },
+    /// Turning the edge over in its slot fixes the flip
+    FlipEdge { position: Edge },
+    /// Permutation parity is off: two pieces were exchanged, e.g. when the
+    /// cube was taken apart and put back together
+    SwapPieces,
+    /// Missing stickers that more than one real cube would fit
+    Ambiguous { facelets: Vec<Facelet> },
+    /// Stickers that no cubie left over can have, or no cubie at all
+    Contradiction { facelets: Vec<Facelet> },
+    /// The problem itself, when no single fix was found
+    Invalid(CubeError),
+}
+
+impl Suggestion {
+    /// The stickers the suggestion is about, for highlighting
+    pub fn facelets(&self) -> Vec<Facelet> {
+        match self {
+            Suggestion::Recolor { facelet, .. } => vec![*facelet],
+            Suggestion::SwapStickers { facelets } => facelets.to_vec(),
+            Suggestion::TwistCorner { position, .. } => position.facelets().to_vec(),
+            Suggestion::FlipEdge { position } => position.facelets().to_vec(),
+            Suggestion::Ambiguous { facelets } | Suggestion::Contradiction { facelets } => {
+                facelets.clone()
+            }
+            Suggestion::SwapPieces | Suggestion::Invalid(_) => vec![],
+        }
+    }
+}
+
+fn facelet_name((face, row, col): Facelet) -> String {
+    format!("{}[{}][{}]", face_letter(face), row, col)
+}
+
+fn facelet_names(facelets: &[Facelet]) -> String {
+    facelets
+        .iter()
+        .map(|&facelet| facelet_name(facelet))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Suggestion::Recolor { facelet, color } => write!(
+                f,
+                "sticker {} is probably {:?}",
+                facelet_name(*facelet),
+                color
+            ),
+            Suggestion::SwapStickers { facelets } => write!(
+                f,
+                "two stickers swapped at {} and {}",
+                facelet_name(facelets[0]),
+                facelet_name(facelets[1])
+            ),
+            Suggestion::TwistCorner {
+                position,
+                clockwise,
+            } => write!(
+                f,
+                "corner {:?} appears twisted — rotate it {}",
+                position,
+                if *clockwise {
+                    "clockwise"
+                } else {
+                    "counter-clockwise"
+                }
+            ),
+            Suggestion::FlipEdge { position } => {
+                write!(f, "edge {:?} appears flipped — flip it over", position)
+            }
+            Suggestion::SwapPieces => write!(
+                f,
+                "two pieces appear exchanged — swap two edges or two corners"
+            ),
+            Suggestion::Ambiguous { facelets } => write!(
+                f,
+                "stickers {} cannot be worked out yet — enter more colours",
+                facelet_names(facelets)
+            ),
+            Suggestion::Contradiction { facelets } if facelets.is_empty() => {
+                write!(f, "the entered stickers cannot all be right")
+            }
+            Suggestion::Contradiction { facelets } => write!(
+                f,
+                "stickers {} do not fit any remaining piece",
+                facelet_names(facelets)
+            ),
+            Suggestion::Invalid(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Every non-centre sticker
+fn piece_facelets() -> impl Iterator<Item = Facelet> {
+    Corner::ALL
+        .into_iter()
+        .flat_map(|corner| corner.facelets())
+        .chain(Edge::ALL.into_iter().flat_map(|edge| edge.facelets()))
+}
+
+/// A cubie index and its twist or flip
+type Candidate = (usize, u8);
+
+/// Backtracking over the slots, corners first, collecting at most two
+/// completions
+struct Search {
+    corners: Vec<Vec<Candidate>>,
+    edges: Vec<Vec<Candidate>>,
+    cube: CubieCube,
+    corner_used: [bool; 8],
+    edge_used: [bool; 12],
+    found: Vec<CubieCube>,
+    nodes: usize,
+}
+
+impl Search {
+    fn done(&self) -> bool {
+        self.found.len() >= 2 || self.nodes > SEARCH_LIMIT
+    }
+
+    fn run(&mut self, slot: usize) {
+        self.nodes += 1;
+        if self.done() {
+            return;
+        }
+        if slot == 8 && !self.cube.co.iter().sum::<u8>().is_multiple_of(3) {
+            return;
+        }
+        if slot == 20 {
+            if self.cube.eo.iter().sum::<u8>().is_multiple_of(2)
+                && self.cube.corner_parity() == self.cube.edge_parity()
+            {
+                self.found.push(self.cube);
+            }
+            return;
+        }
+        if slot < 8 {
+            for (cubie, twist) in self.corners[slot].clone() {
+                if self.corner_used[cubie] {
+                    continue;
+                }
+                self.corner_used[cubie] = true;
+                self.cube.cp[slot] = Corner::from_index(cubie);
+                self.cube.co[slot] = twist;
+                self.run(slot + 1);
+                self.corner_used[cubie] = false;
+            }
+        } else {
+            let slot_edge = slot - 8;
+            for (cubie, flip) in self.edges[slot_edge].clone() {
+                if self.edge_used[cubie] {
+                    continue;
+                }
+                self.edge_used[cubie] = true;
+                self.cube.ep[slot_edge] = Edge::from_index(cubie);
+                self.cube.eo[slot_edge] = flip;
+                self.run(slot + 1);
+                self.edge_used[cubie] = false;
+            }
+        }
+    }
+}
+
+/// The sticker colours of `cubies`, with the centres coloured `centers`
+fn paint(cubies: &CubieCube, centers: [Color; 6]) -> [[[Color; 3]; 3]; 6] {
+    let mut state = [[[Color::White; 3]; 3]; 6];
+    for face in Face::values() {
+        state[face.ordinal()][1][1] = centers[face.ordinal()];
+    }
+    for slot in Corner::ALL {
+        let cubie = cubies.cp[slot.index()].faces();
+        let twist = cubies.co[slot.index()] as usize;
+        for (k, (face, row, col)) in slot.facelets().into_iter().enumerate() {
+            state[face.ordinal()][row][col] = centers[cubie[(k + 3 - twist) % 3].ordinal()];
+        }
+    }
+    for slot in Edge::ALL {
+        let cubie = slot_cubie_faces(cubies, slot);
+        for (k, (face, row, col)) in slot.facelets().into_iter().enumerate() {
+            state[face.ordinal()][row][col] = centers[cubie[k].ordinal()];
+        }
+    }
+    state
+}
+
+/// The home faces of the edge in `slot`, in the order of the slot's stickers
+fn slot_cubie_faces(cubies: &CubieCube, slot: Edge) -> [Face; 2] {
+    let [a, b] = cubies.ep[slot.index()].faces();
+    if cubies.eo[slot.index()] == 0 {
+        [a, b]
+    } else {
+        [b, a]
+    }
+}
+
+impl Cube {
+    /// Builds a cube from stickers entered by hand. Missing stickers are
+    /// filled in when exactly one real cube fits the others; otherwise the
+    /// error lists what to enter or correct.
+    pub fn from_partial(partial: &PartialState) -> Result<Cube, Vec<Suggestion>> {
+        let known = |(face, row, col): Facelet| partial[face.ordinal()][row][col];
+        let missing: Vec<Facelet> = Face::values()
+            .into_iter()
+            .flat_map(|face| (0..3).flat_map(move |row| (0..3).map(move |col| (face, row, col))))
+            .filter(|&facelet| known(facelet).is_none())
+            .collect();
+
+        if missing.is_empty() {
+            let cube =
+                Cube::from_state(partial.map(|face| face.map(|row| row.map(Option::unwrap))));
+            return match cube.validate() {
+                Ok(()) => Ok(cube),
+                Err(_) => Err(cube.suggest_fixes()),
+            };
+        }
+
+        let mut counts = [0usize; 6];
+        for color in partial.iter().flatten().flatten().flatten() {
+            counts[*color as usize] += 1;
+        }
+        if let Some(value) = counts.iter().position(|&count| count > 9) {
+            return Err(vec![Suggestion::Invalid(CubeError::ColorCount {
+                color: Color::from_u8(value as u8),
+                count: counts[value],
+            })]);
+        }
+
+        // 中心块：颜色不能重复，只缺一个时可以补上
+        let mut centers: [Option<Color>; 6] = [None; 6];
+        for face in Face::values() {
+            let Some(color) = known((face, 1, 1)) else {
+                continue;
+            };
+            if centers.contains(&Some(color)) {
+                return Err(vec![Suggestion::Invalid(CubeError::DuplicateCenter {
+                    color,
+                })]);
+            }
+            centers[face.ordinal()] = Some(color);
+        }
+        let missing_centers: Vec<Facelet> = Face::values()
+            .into_iter()
+            .filter(|face| centers[face.ordinal()].is_none())
+            .map(|face| (face, 1, 1))
+            .collect();
+        match missing_centers.as_slice() {
+            [] => {}
+            [(face, _, _)] => {
+                let unused = (0..6)
+                    .map(Color::from_u8)
+                    .find(|color| !centers.contains(&Some(*color)))
+                    .unwrap();
+                centers[face.ordinal()] = Some(unused);
+            }
+            _ => {
+                return Err(vec![Suggestion::Ambiguous {
+                    facelets: missing_centers,
+                }])
+            }
+        }
+        let centers = centers.map(Option::unwrap);
+        let fits = |facelet: Facelet, face: Face| {
+            known(facelet).is_none_or(|color| color == centers[face.ordinal()])
+        };
+
+        let corners: Vec<Vec<Candidate>> = Corner::ALL
+            .iter()
+            .map(|slot| {
+                let facelets = slot.facelets();
+                let mut candidates = vec![];
+                for cubie in Corner::ALL {
+                    let faces = cubie.faces();
+                    for twist in 0..3 {
+                        if (0..3).all(|k| fits(facelets[k], faces[(k + 3 - twist) % 3])) {
+                            candidates.push((cubie.index(), twist as u8));
+                        }
+                    }
+                }
+                candidates
+            })
+            .collect();
+        let edges: Vec<Vec<Candidate>> = Edge::ALL
+            .iter()
+            .map(|slot| {
+                let facelets = slot.facelets();
+                let mut candidates = vec![];
+                for cubie in Edge::ALL {
+                    let faces = cubie.faces();
+                    for flip in 0..2 {
+                        if (0..2).all(|k| fits(facelets[k], faces[(k + flip) % 2])) {
+                            candidates.push((cubie.index(), flip as u8));
+                        }
+                    }
+                }
+                candidates
+            })
+            .collect();
+
+        let empty_slots: Vec<Vec<Facelet>> = Corner::ALL
+            .iter()
+            .filter(|slot| corners[slot.index()].is_empty())
+            .map(|slot| slot.facelets().to_vec())
+            .chain(
+                Edge::ALL
+                    .iter()
+                    .filter(|slot| edges[slot.index()].is_empty())
+                    .map(|slot| slot.facelets().to_vec()),
+            )
+            .collect();
+        if !empty_slots.is_empty() {
+            return Err(empty_slots
+                .into_iter()
+                .map(|facelets| Suggestion::Contradiction { facelets })
+                .collect());
+        }
+
+        let mut search = Search {
+            corners,
+            edges,
+            cube: CubieCube::solved(),
+            corner_used: [false; 8],
+            edge_used: [false; 12],
+            found: vec![],
+            nodes: 0,
+        };
+        search.run(0);
+
+        match search.found.as_slice() {
+            [only] if search.nodes <= SEARCH_LIMIT => Ok(Cube::from_state(paint(only, centers))),
+            [] if search.nodes <= SEARCH_LIMIT => {
+                Err(vec![Suggestion::Contradiction { facelets: vec![] }])
+            }
+            [first, second] => {
+                let (a, b) = (paint(first, centers), paint(second, centers));
+                let facelets = missing
+                    .into_iter()
+                    .filter(|&(face, row, col)| {
+                        a[face.ordinal()][row][col] != b[face.ordinal()][row][col]
+                    })
+                    .collect();
+                Err(vec![Suggestion::Ambiguous { facelets }])
+            }
+            _ => Err(vec![Suggestion::Ambiguous { facelets: missing }]),
+        }
+    }
+
+    /// Likely fixes for an invalid cube, empty if the cube is valid. When
+    /// several are listed, any one of them is enough.
+    pub fn suggest_fixes(&self) -> Vec<Suggestion> {
+        let error = match self.validate() {
+            Ok(()) => return vec![],
+            Err(error) => error,
+        };
+        let suggestions = match &error {
+            CubeError::ColorCount { .. } => self.recolors(),
+            CubeError::CornerTwist { sum } => {
+                let cubies = self.identify_cubies().unwrap();
+                Corner::ALL
+                    .into_iter()
+                    .filter(|slot| cubies.co[slot.index()] != 0)
+                    .map(|position| Suggestion::TwistCorner {
+                        position,
+                        clockwise: *sum == 2,
+                    })
+                    .collect()
+            }
+            CubeError::EdgeFlip { .. } => {
+                let cubies = self.identify_cubies().unwrap();
+                Edge::ALL
+                    .into_iter()
+                    .filter(|slot| cubies.eo[slot.index()] != 0)
+                    .map(|position| Suggestion::FlipEdge { position })
+                    .collect()
+            }
+            CubeError::Parity => vec![Suggestion::SwapPieces],
+            CubeError::InvalidCorner { .. }
+            | CubeError::InvalidEdge { .. }
+            | CubeError::DuplicateCorner { .. }
+            | CubeError::DuplicateEdge { .. } => self.sticker_swaps(),
+            CubeError::InvalidColorValue(_)
+            | CubeError::DuplicateCenter { .. }
+            | CubeError::MoveDoesNotFit { .. }
+            | CubeError::WrongSize { .. } => vec![],
+        };
+        if suggestions.is_empty() {
+            vec![Suggestion::Invalid(error)]
+        } else {
+            suggestions
+        }
+    }
+
+    /// Single stickers of an over-used colour that, given a missing colour,
+    /// make the cube valid
+    fn recolors(&self) -> Vec<Suggestion> {
+        let mut counts = [0usize; 6];
+        for color in self.state.iter().flatten().flatten() {
+            counts[*color as usize] += 1;
+        }
+        let mut suggestions = vec![];
+        for facelet in piece_facelets() {
+            let (face, row, col) = facelet;
+            let current = self.state[face.ordinal()][row][col];
+            if counts[current as usize] <= 9 {
+                continue;
+            }
+            for value in (0..6).filter(|&value| counts[value] < 9) {
+                let color = Color::from_u8(value as u8);
+                let mut cube = self.clone();
+                cube.state[face.ordinal()][row][col] = color;
+                if cube.validate().is_ok() {
+                    suggestions.push(Suggestion::Recolor { facelet, color });
+                }
+            }
+        }
+        suggestions
+    }
+
+    /// Pairs of stickers that, exchanged, make the cube valid
+    fn sticker_swaps(&self) -> Vec<Suggestion> {
+        let facelets: Vec<Facelet> = piece_facelets().collect();
+        let color = |(face, row, col): Facelet| self.state[face.ordinal()][row][col];
+        let mut suggestions = vec![];
+        for (i, &a) in facelets.iter().enumerate() {
+            for &b in &facelets[i + 1..] {
+                if color(a) == color(b) {
+                    continue;
+                }
+                let mut cube = self.clone();
+                cube.state[a.0.ordinal()][a.1][a.2] = color(b);
+                cube.state[b.0.ordinal()][b.1][b.2] = color(a);
+                if cube.validate().is_ok() {
+                    suggestions.push(Suggestion::SwapStickers { facelets: [a, b] });
+                }
+            }
+        }
+        suggestions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrambled() -> Cube {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&"R U F' L2 D B R' U2".parse().unwrap());
+        cube
+    }
+
+    fn partial(cube: &Cube, missing: &[Facelet]) -> PartialState {
+        let mut state = cube.state.map(|face| face.map(|row| row.map(Some)));
+        for &(face, row, col) in missing {
+            state[face.ordinal()][row][col] = None;
+        }
+        state
+    }
+
+    fn set(cube: &mut Cube, (face, row, col): Facelet, color: Color) {
+        cube.state[face.ordinal()][row][col] = color;
+    }
+
+    fn get(cube: &Cube, (face, row, col): Facelet) -> Color {
+        cube.state[face.ordinal()][row][col]
+    }
+
+    #[test]
+    fn fills_in_missing_stickers() {
+        let cube = scrambled();
+        let mut missing = vec![(Face::Front, 1, 1)];
+        missing.extend(Corner::URF.facelets());
+        missing.extend(Edge::DB.facelets());
+        missing.push(Corner::DLF.facelets()[1]);
+        missing.push(Edge::FR.facelets()[0]);
+        assert_eq!(Cube::from_partial(&partial(&cube, &missing)), Ok(cube));
+    }
+
+    #[test]
+    fn reports_stickers_that_cannot_be_deduced() {
+        let cube = scrambled();
+        let mut missing = Corner::URF.facelets().to_vec();
+        missing.extend(Corner::UFL.facelets());
+        let Err(suggestions) = Cube::from_partial(&partial(&cube, &missing)) else {
+            panic!("two missing corners are not unique");
+        };
+        let [Suggestion::Ambiguous { facelets }] = suggestions.as_slice() else {
+            panic!("{:?}", suggestions);
+        };
+        assert!(facelets.iter().all(|facelet| missing.contains(facelet)));
+        // 只填了中心块
+        let mut centers_only: PartialState = [[[None; 3]; 3]; 6];
+        for face in Face::values() {
+            centers_only[face.ordinal()][1][1] = Some(face.color());
+        }
+        assert!(matches!(
+            Cube::from_partial(&centers_only).err().unwrap().as_slice(),
+            [Suggestion::Ambiguous { .. }]
+        ));
+    }
+
+    #[test]
+    fn reports_stickers_that_fit_no_piece() {
+        // UF 的前面贴纸和 DF 的底面贴纸互换：颜色数量不变，但两个棱块都不存在
+        let mut cube = Cube::new();
+        let a = Edge::UF.facelets()[1];
+        let b = Edge::DF.facelets()[0];
+        set(&mut cube, a, Color::White);
+        set(&mut cube, b, Color::Blue);
+        let result = Cube::from_partial(&partial(&cube, &[Edge::UR.facelets()[0]]));
+        let suggestions = result.err().unwrap();
+        assert_eq!(suggestions.len(), 2);
+        assert!(suggestions
+            .iter()
+            .all(|suggestion| matches!(suggestion, Suggestion::Contradiction { .. })));
+        assert!(suggestions[0].facelets().contains(&a));
+    }
+
+    #[test]
+    fn suggests_twisting_a_corner() {
+        let mut cube = Cube::new();
+        let facelets = Corner::URF.facelets();
+        let colors = facelets.map(|facelet| get(&cube, facelet));
+        for k in 0..3 {
+            set(&mut cube, facelets[(k + 1) % 3], colors[k]);
+        }
+        let suggestions = cube.suggest_fixes();
+        assert!(suggestions.contains(&Suggestion::TwistCorner {
+            position: Corner::URF,
+            clockwise: false,
+        }));
+        assert_eq!(Cube::from_partial(&partial(&cube, &[])), Err(suggestions));
+    }
+
+    #[test]
+    fn suggests_flipping_an_edge() {
+        let mut cube = Cube::new();
+        let [a, b] = Edge::FL.facelets();
+        let (color_a, color_b) = (get(&cube, a), get(&cube, b));
+        set(&mut cube, a, color_b);
+        set(&mut cube, b, color_a);
+        assert!(cube
+            .suggest_fixes()
+            .contains(&Suggestion::FlipEdge { position: Edge::FL }));
+    }
+
+    #[test]
+    fn suggests_swapped_stickers() {
+        let mut cube = Cube::new();
+        let a = Edge::UF.facelets()[1];
+        let b = Edge::UR.facelets()[0];
+        set(&mut cube, a, Color::Yellow);
+        set(&mut cube, b, Color::Blue);
+        assert!(cube
+            .suggest_fixes()
+            .contains(&Suggestion::SwapStickers { facelets: [b, a] }));
+    }
+
+    #[test]
+    fn suggests_recoloring_a_sticker() {
+        let mut cube = scrambled();
+        let facelet = Edge::DL.facelets()[1];
+        let color = get(&cube, facelet);
+        let wrong = Color::from_u8((color as u8 + 1) % 6);
+        set(&mut cube, facelet, wrong);
+        let suggestions = cube.suggest_fixes();
+        assert!(suggestions.contains(&Suggestion::Recolor { facelet, color }));
+        assert!(suggestions[0].to_string().starts_with("sticker "));
+    }
+
+    #[test]
+    fn suggests_swapping_pieces_for_parity() {
+        let mut cube = Cube::new();
+        for (a, b) in Edge::UF.facelets().into_iter().zip(Edge::UR.facelets()) {
+            let (color_a, color_b) = (get(&cube, a), get(&cube, b));
+            set(&mut cube, a, color_b);
+            set(&mut cube, b, color_a);
+        }
+        assert_eq!(cube.suggest_fixes(), vec![Suggestion::SwapPieces]);
+    }
+}
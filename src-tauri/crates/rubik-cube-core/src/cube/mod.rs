@@ -1,31 +1,46 @@
 //! Cube module
 
 use color::Color;
-use face::Face;
+use error::CubeError;
+use scheme::ColorScheme;
 
 pub mod color;
+pub mod cubie;
+pub mod error;
 pub mod face;
+pub mod facelet;
+pub mod input;
+mod moves;
+pub mod notation;
+pub mod nxn;
+pub mod scheme;
+mod validate;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Cube {
     pub state: [[[Color; 3]; 3]; 6],
 }
 
 impl Cube {
+    /// A solved cube in the default scheme, see [`Cube::with_scheme`]
     pub fn new() -> Cube {
-        let mut state = [[[Color::White; 3]; 3]; 6];
+        Cube::with_scheme(ColorScheme::default())
+    }
 
-        for face in Face::values() {
-            for i in 0..3 {
-                for j in 0..3 {
-                    state[face.ordinal()][i][j] = face.color();
-                }
-            }
+    /// A solved cube painted with `scheme`
+    pub fn with_scheme(scheme: ColorScheme) -> Cube {
+        let mut state = [[[Color::White; 3]; 3]; 6];
+        for (face, color) in scheme.colors().into_iter().enumerate() {
+            state[face] = [[color; 3]; 3];
         }
-
         Cube { state }
     }
 
+    /// The scheme the centres show, whichever way the cube is held
+    pub fn scheme(&self) -> Result<ColorScheme, CubeError> {
+        ColorScheme::new(std::array::from_fn(|face| self.state[face][1][1]))
+    }
+
     pub fn from_state(state: [[[Color; 3]; 3]; 6]) -> Cube {
         Cube { state }
     }
@@ -0,0 +1,322 @@
+//! Table-driven move application
+//!
+//! Every move is a permutation of the 54 facelets. The tables are derived
+//! once from the cube geometry: each sticker gets integer 3D coordinates,
+//! and a move rotates the stickers of the layers it turns by 90 degrees
+//! about its axis.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::error::CubeError;
+use super::face::Face;
+use super::notation::{Algorithm, Axis, Move, MoveKind, Slice, Turn};
+use super::Cube;
+
+const FACELETS: usize = 54;
+
+type Point = [i32; 3];
+
+/// Coordinates of a sticker for a cube with `size` layers.
+/// Cubie coordinates run from `-(size - 1)` to `size - 1` in steps of 2 and the
+/// sticker sits one unit outside its cubie along the face normal.
+pub(crate) fn sticker_point(size: usize, face: Face, row: usize, col: usize) -> Point {
+    let max = size as i32 - 1;
+    let r = 2 * row as i32 - max;
+    let c = 2 * col as i32 - max;
+    let outer = max + 1;
+    match face {
+        Face::Up => [c, outer, r],
+        Face::Down => [c, -outer, -r],
+        Face::Front => [c, -r, outer],
+        Face::Back => [-c, -r, -outer],
+        Face::Left => [-outer, -r, c],
+        Face::Right => [outer, -r, -c],
+    }
+}
+
+/// Rotates a point by +90 degrees (right-hand rule) about `axis`.
+pub(crate) fn rotate_point(point: Point, axis: Axis) -> Point {
+    let [x, y, z] = point;
+    match axis {
+        Axis::X => [x, -z, y],
+        Axis::Y => [z, y, -x],
+        Axis::Z => [-y, x, z],
+    }
+}
+
+pub(crate) fn axis_index(axis: Axis) -> usize {
+    match axis {
+        Axis::X => 0,
+        Axis::Y => 1,
+        Axis::Z => 2,
+    }
+}
+
+/// Axis of a face, whether it lies on the positive side of that axis, and
+/// how many +90 degree rotations make a clockwise turn of it.
+pub(crate) fn face_axis(face: Face) -> (Axis, bool, u32) {
+    match face {
+        Face::Right => (Axis::X, true, 3),
+        Face::Left => (Axis::X, false, 1),
+        Face::Up => (Axis::Y, true, 3),
+        Face::Down => (Axis::Y, false, 1),
+        Face::Front => (Axis::Z, true, 3),
+        Face::Back => (Axis::Z, false, 1),
+    }
+}
+
+/// The same move written the shortest way: `1R` is `R`, `2Rw` is `Rw`
+pub(crate) fn canonical(kind: MoveKind) -> MoveKind {
+    match kind {
+        MoveKind::Layer(face, 1) | MoveKind::WideLayers(face, 0..=1) => MoveKind::Face(face),
+        MoveKind::WideLayers(face, 2) => MoveKind::Wide(face),
+        kind => kind,
+    }
+}
+
+/// The axis a move turns about, the cubie coordinates of the layers it moves
+/// on a cube with `size` layers (`-(size - 1)` to `size - 1` in steps of 2
+/// along the axis) and its clockwise turn in +90 degree rotations.
+pub(crate) fn move_geometry(size: usize, kind: MoveKind) -> (Axis, Vec<i32>, u32) {
+    let max = size as i32 - 1;
+    let from_face = |face: Face, depths: std::ops::RangeInclusive<u8>| {
+        let (axis, positive, quarter) = face_axis(face);
+        let layers = depths
+            .map(|depth| {
+                let layer = max - 2 * (depth as i32 - 1);
+                if positive {
+                    layer
+                } else {
+                    -layer
+                }
+            })
+            .collect();
+        (axis, layers, quarter)
+    };
+    match kind {
+        MoveKind::Face(face) => from_face(face, 1..=1),
+        MoveKind::Wide(face) => from_face(face, 1..=2),
+        MoveKind::Layer(face, depth) => from_face(face, depth..=depth),
+        MoveKind::WideLayers(face, depth) => from_face(face, 1..=depth),
+        MoveKind::Slice(Slice::M) => (Axis::X, vec![0], 1),
+        MoveKind::Slice(Slice::E) => (Axis::Y, vec![0], 1),
+        MoveKind::Slice(Slice::S) => (Axis::Z, vec![0], 3),
+        MoveKind::Rotation(axis) => (axis, (-max..=max).step_by(2).collect(), 3),
+    }
+}
+
+/// Where every sticker of a cube with `size` layers ends up after the
+/// move; stickers are numbered face by face, then row by row.
+pub(crate) fn permutation(size: usize, kind: MoveKind, turn: Turn) -> Vec<usize> {
+    let (axis, layers, quarter) = move_geometry(size, kind);
+    let rotations = quarter * turn.quarter_turns() % 4;
+    let max = size as i32 - 1;
+
+    let mut points = vec![];
+    for face in Face::values() {
+        for row in 0..size {
+            for col in 0..size {
+                points.push(sticker_point(size, face, row, col));
+            }
+        }
+    }
+    let index: HashMap<Point, usize> = points.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            // 贴纸在法向上比所在块多出 1，截断后得到块坐标
+            let layer = point[axis_index(axis)].clamp(-max, max);
+            if !layers.contains(&layer) {
+                return i;
+            }
+            let mut moved = *point;
+            for _ in 0..rotations {
+                moved = rotate_point(moved, axis);
+            }
+            index[&moved]
+        })
+        .collect()
+}
+
+/// `table[i]` is where the sticker at facelet `i` ends up after the move.
+fn build_table(kind: MoveKind, turn: Turn) -> [usize; FACELETS] {
+    permutation(3, kind, turn).try_into().unwrap()
+}
+
+const KINDS: usize = 36;
+
+/// Only for moves that fit a 3x3 cube, in canonical form
+fn kind_index(kind: MoveKind) -> usize {
+    match kind {
+        MoveKind::Face(face) => face.ordinal(),
+        MoveKind::Wide(face) => 6 + face.ordinal(),
+        MoveKind::Slice(slice) => 12 + slice as usize,
+        MoveKind::Rotation(axis) => 15 + axis_index(axis),
+        MoveKind::Layer(face, depth) => 18 + 6 * (depth as usize - 2) + face.ordinal(),
+        MoveKind::WideLayers(face, _) => 30 + face.ordinal(),
+    }
+}
+
+/// Every move kind on a cube with `size` layers, in canonical form
+pub(crate) fn all_kinds(size: usize) -> Vec<MoveKind> {
+    let mut kinds = vec![];
+    for face in Face::values() {
+        kinds.push(MoveKind::Face(face));
+    }
+    for face in Face::values() {
+        kinds.push(MoveKind::Wide(face));
+    }
+    if size % 2 == 1 {
+        for slice in [Slice::M, Slice::E, Slice::S] {
+            kinds.push(MoveKind::Slice(slice));
+        }
+    }
+    for axis in [Axis::X, Axis::Y, Axis::Z] {
+        kinds.push(MoveKind::Rotation(axis));
+    }
+    for depth in 2..=size as u8 {
+        for face in Face::values() {
+            kinds.push(MoveKind::Layer(face, depth));
+        }
+    }
+    for depth in 3..=size as u8 {
+        for face in Face::values() {
+            kinds.push(MoveKind::WideLayers(face, depth));
+        }
+    }
+    kinds
+}
+
+fn tables() -> &'static Vec<[usize; FACELETS]> {
+    static TABLES: OnceLock<Vec<[usize; FACELETS]>> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut tables = vec![[0; FACELETS]; KINDS * 3];
+        for kind in all_kinds(3) {
+            for turn in [Turn::Clockwise, Turn::Double, Turn::CounterClockwise] {
+                tables[kind_index(kind) * 3 + turn.quarter_turns() as usize - 1] =
+                    build_table(kind, turn);
+            }
+        }
+        tables
+    })
+}
+
+impl Cube {
+    /// # Panics
+    ///
+    /// If the move needs a bigger cube, see [`Move::fits`].
+    pub fn apply_move(&mut self, mv: Move) {
+        assert!(mv.fits(3), "{} does not fit a 3x3 cube", mv);
+        let kind = kind_index(canonical(mv.kind));
+        let table = &tables()[kind * 3 + mv.turn.quarter_turns() as usize - 1];
+        let old = self.state;
+        for face in 0..6 {
+            for row in 0..3 {
+                for col in 0..3 {
+                    let to = table[face * 9 + row * 3 + col];
+                    self.state[to / 9][to % 9 / 3][to % 3] = old[face][row][col];
+                }
+            }
+        }
+    }
+
+    pub fn apply_algorithm(&mut self, algorithm: &Algorithm) {
+        for mv in algorithm.moves() {
+            self.apply_move(mv);
+        }
+    }
+
+    /// Like [`Cube::apply_algorithm`], but leaves the cube untouched and
+    /// returns an error if any move needs a bigger cube.
+    pub fn try_apply_algorithm(&mut self, algorithm: &Algorithm) -> Result<(), CubeError> {
+        let moves = algorithm.moves();
+        if let Some(&mv) = moves.iter().find(|mv| !mv.fits(3)) {
+            return Err(CubeError::MoveDoesNotFit { mv, size: 3 });
+        }
+        for mv in moves {
+            self.apply_move(mv);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after(alg: &str) -> Cube {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&alg.parse().unwrap());
+        cube
+    }
+
+    #[test]
+    fn every_move_has_order_four() {
+        for kind in all_kinds(3) {
+            let mut cube = after("R U F' L2 D B'");
+            let before = cube.state;
+            for _ in 0..4 {
+                cube.apply_move(Move::new(kind, Turn::Clockwise));
+            }
+            assert_eq!(cube.state, before, "{}", kind);
+        }
+    }
+
+    #[test]
+    fn sexy_move_has_order_six() {
+        assert!(after("(R U R' U')6").is_solved());
+        assert!(!after("(R U R' U')3").is_solved());
+    }
+
+    #[test]
+    fn try_apply_rejects_moves_for_bigger_cubes() {
+        let mut cube = Cube::new();
+        let err = cube.try_apply_algorithm(&"R U 4Rw".parse().unwrap());
+        assert_eq!(err.unwrap_err().to_string(), "4Rw does not fit a 3x3 cube");
+        assert!(cube.is_solved());
+        assert!(cube.try_apply_algorithm(&"R U 2R".parse().unwrap()).is_ok());
+        assert_eq!(cube, after("R U M'"));
+    }
+
+    #[test]
+    fn wide_slice_and_rotation_identities() {
+        assert_eq!(after("Rw").state, after("R M'").state);
+        assert_eq!(after("Lw").state, after("L M").state);
+        assert_eq!(after("Uw").state, after("U E'").state);
+        assert_eq!(after("Dw").state, after("D E").state);
+        assert_eq!(after("Fw").state, after("F S").state);
+        assert_eq!(after("Bw").state, after("B S'").state);
+        assert_eq!(after("x").state, after("R M' L'").state);
+        assert_eq!(after("y").state, after("U E' D'").state);
+        assert_eq!(after("z").state, after("F S B'").state);
+    }
+
+    #[test]
+    fn layer_indexed_moves_on_a_3x3() {
+        assert_eq!(after("2R").state, after("M'").state);
+        assert_eq!(after("2U").state, after("E'").state);
+        assert_eq!(after("2F").state, after("S").state);
+        assert_eq!(after("3R").state, after("L'").state);
+        assert_eq!(after("3Rw").state, after("x").state);
+        assert_eq!(after("1R 2Rw").state, after("R Rw").state);
+    }
+
+    #[test]
+    fn rotations_relabel_faces() {
+        // x 把 F 转到 U 的位置，因此 x U x' 等价于 F
+        assert_eq!(after("x U x'").state, after("F").state);
+        assert_eq!(after("y F y'").state, after("R").state);
+        assert_eq!(after("z U z'").state, after("L").state);
+        assert_eq!(after("x R x'").state, after("R").state);
+    }
+
+    #[test]
+    fn m_slice_pll_is_an_edge_cycle() {
+        let cube = after("M2 U M U2 M' U M2");
+        assert!(!cube.is_solved());
+        assert!(after("(M2 U M U2 M' U M2)3").is_solved());
+    }
+}
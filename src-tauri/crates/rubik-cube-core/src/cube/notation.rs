@@ -0,0 +1,881 @@
+//! Standard (Singmaster / WCA) move notation
+//!
+//! Supports outer turns (`R`, `R'`, `R2`), wide turns (`Rw`, `r`), slice
+//! turns (`M`, `E`, `S`), layer-indexed turns for bigger cubes (`2R`,
+//! `3Rw`), cube rotations (`x`, `y`, `z`), groups with repetition
+//! (`(R U)3`), commutators (`[A, B]`) and conjugates (`[A: B]`).
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::error::UnknownCode;
+use super::face::{Face, TwistDirection};
+
+/// Amount of a turn, seen from the face being turned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Clockwise,
+    Double,
+    CounterClockwise,
+}
+
+impl Turn {
+    /// Builds a turn from a number of clockwise quarter turns, `None` if it is a full turn.
+    pub fn from_quarter_turns(quarter_turns: u32) -> Option<Turn> {
+        match quarter_turns % 4 {
+            1 => Some(Turn::Clockwise),
+            2 => Some(Turn::Double),
+            3 => Some(Turn::CounterClockwise),
+            _ => None,
+        }
+    }
+
+    pub fn quarter_turns(&self) -> u32 {
+        match self {
+            Turn::Clockwise => 1,
+            Turn::Double => 2,
+            Turn::CounterClockwise => 3,
+        }
+    }
+
+    pub fn inverse(&self) -> Turn {
+        match self {
+            Turn::Clockwise => Turn::CounterClockwise,
+            Turn::Double => Turn::Double,
+            Turn::CounterClockwise => Turn::Clockwise,
+        }
+    }
+
+    fn suffix(&self) -> &'static str {
+        match self {
+            Turn::Clockwise => "",
+            Turn::Double => "2",
+            Turn::CounterClockwise => "'",
+        }
+    }
+}
+
+impl From<TwistDirection> for Turn {
+    fn from(direction: TwistDirection) -> Self {
+        match direction {
+            TwistDirection::Clockwise => Turn::Clockwise,
+            TwistDirection::CounterClockwise => Turn::CounterClockwise,
+        }
+    }
+}
+
+/// Middle layers: M follows L, E follows D, S follows F
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slice {
+    M,
+    E,
+    S,
+}
+
+/// Whole-cube rotation axes: x follows R, y follows U, z follows F
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    /// A single outer layer
+    Face(Face),
+    /// An outer layer together with the adjacent middle layer
+    Wide(Face),
+    Slice(Slice),
+    Rotation(Axis),
+    /// The n-th layer counted from a face, alone (`2R`); n is at least 2
+    Layer(Face, u8),
+    /// The first n layers from a face (`3Rw`); n is at least 3, two
+    /// layers are [`MoveKind::Wide`]
+    WideLayers(Face, u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub kind: MoveKind,
+    pub turn: Turn,
+}
+
+impl Move {
+    pub fn new(kind: MoveKind, turn: Turn) -> Move {
+        Move { kind, turn }
+    }
+
+    /// A quarter turn of an outer face, as produced by `CubeScrambler::scramble`.
+    pub fn face(face: Face, direction: TwistDirection) -> Move {
+        Move::new(MoveKind::Face(face), direction.into())
+    }
+
+    pub fn inverse(&self) -> Move {
+        Move::new(self.kind, self.turn.inverse())
+    }
+
+    /// Whether the move exists on a cube with `size` layers: slice turns
+    /// need a middle layer and layer-indexed turns enough layers.
+    pub fn fits(&self, size: usize) -> bool {
+        match self.kind {
+            MoveKind::Face(_) | MoveKind::Wide(_) | MoveKind::Rotation(_) => true,
+            MoveKind::Slice(_) => size % 2 == 1,
+            MoveKind::Layer(_, depth) | MoveKind::WideLayers(_, depth) => {
+                depth >= 1 && depth as usize <= size
+            }
+        }
+    }
+
+    /// How many moves this counts as in `metric`. Rotations are free.
+    pub fn cost(&self, metric: Metric) -> u32 {
+        let turns = if self.turn == Turn::Double { 2 } else { 1 };
+        match (self.kind, metric) {
+            (MoveKind::Rotation(_), _) => 0,
+            (MoveKind::Slice(_) | MoveKind::Layer(..), Metric::Htm) => 2,
+            (MoveKind::Slice(_) | MoveKind::Layer(..), Metric::Qtm) => 2 * turns,
+            (_, Metric::Qtm) => turns,
+            _ => 1,
+        }
+    }
+}
+
+/// Ways of counting moves
+/// - Htm: half turn metric, any turn of an outer layer is one move
+/// - Qtm: quarter turn metric, half turns count twice
+/// - Stm: slice turn metric, any turn of any layer is one move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    Htm,
+    Qtm,
+    Stm,
+}
+
+impl TryFrom<u8> for Metric {
+    type Error = UnknownCode;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Metric::Htm),
+            1 => Ok(Metric::Qtm),
+            2 => Ok(Metric::Stm),
+            _ => Err(UnknownCode {
+                kind: "metric",
+                value,
+            }),
+        }
+    }
+}
+
+pub(crate) fn face_letter(face: Face) -> char {
+    match face {
+        Face::Up => 'U',
+        Face::Down => 'D',
+        Face::Front => 'F',
+        Face::Back => 'B',
+        Face::Left => 'L',
+        Face::Right => 'R',
+    }
+}
+
+pub(crate) fn letter_face(letter: char) -> Option<Face> {
+    match letter.to_ascii_uppercase() {
+        'U' => Some(Face::Up),
+        'D' => Some(Face::Down),
+        'F' => Some(Face::Front),
+        'B' => Some(Face::Back),
+        'L' => Some(Face::Left),
+        'R' => Some(Face::Right),
+        _ => None,
+    }
+}
+
+impl fmt::Display for MoveKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveKind::Face(face) => write!(f, "{}", face_letter(*face)),
+            MoveKind::Wide(face) => write!(f, "{}w", face_letter(*face)),
+            MoveKind::Slice(slice) => write!(f, "{:?}", slice),
+            MoveKind::Rotation(Axis::X) => write!(f, "x"),
+            MoveKind::Rotation(Axis::Y) => write!(f, "y"),
+            MoveKind::Rotation(Axis::Z) => write!(f, "z"),
+            MoveKind::Layer(face, depth) => write!(f, "{}{}", depth, face_letter(*face)),
+            MoveKind::WideLayers(face, depth) => write!(f, "{}{}w", depth, face_letter(*face)),
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.kind, self.turn.suffix())
+    }
+}
+
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        parser.skip_whitespace();
+        let mv = parser.parse_move()?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(mv),
+            Some(c) => Err(parser.error(ParseErrorKind::UnexpectedChar(c))),
+        }
+    }
+}
+
+/// One element of an algorithm, keeping the structure it was written with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlgNode {
+    Move(Move),
+    /// `(A)`, `(A)n`, `(A)'` or `(A)n'`
+    Group {
+        body: Vec<AlgNode>,
+        repeat: u32,
+        inverted: bool,
+    },
+    /// `[A, B]` = A B A' B'
+    Commutator(Vec<AlgNode>, Vec<AlgNode>),
+    /// `[A: B]` = A B A'
+    Conjugate(Vec<AlgNode>, Vec<AlgNode>),
+}
+
+impl AlgNode {
+    fn expand_into(&self, moves: &mut Vec<Move>) {
+        match self {
+            AlgNode::Move(mv) => moves.push(*mv),
+            AlgNode::Group {
+                body,
+                repeat,
+                inverted,
+            } => {
+                let mut once = vec![];
+                expand_nodes(body, &mut once);
+                if *inverted {
+                    once = invert_moves(&once);
+                }
+                for _ in 0..*repeat {
+                    moves.extend_from_slice(&once);
+                }
+            }
+            AlgNode::Commutator(a, b) => {
+                let mut a_moves = vec![];
+                let mut b_moves = vec![];
+                expand_nodes(a, &mut a_moves);
+                expand_nodes(b, &mut b_moves);
+                moves.extend_from_slice(&a_moves);
+                moves.extend_from_slice(&b_moves);
+                moves.extend(invert_moves(&a_moves));
+                moves.extend(invert_moves(&b_moves));
+            }
+            AlgNode::Conjugate(a, b) => {
+                let mut a_moves = vec![];
+                expand_nodes(a, &mut a_moves);
+                moves.extend_from_slice(&a_moves);
+                expand_nodes(b, moves);
+                moves.extend(invert_moves(&a_moves));
+            }
+        }
+    }
+
+    /// Number of moves once expanded, without expanding.
+    fn expanded_len(&self) -> usize {
+        match self {
+            AlgNode::Move(_) => 1,
+            AlgNode::Group { body, repeat, .. } => {
+                expanded_len(body).saturating_mul(*repeat as usize)
+            }
+            AlgNode::Commutator(a, b) => expanded_len(a)
+                .saturating_add(expanded_len(b))
+                .saturating_mul(2),
+            AlgNode::Conjugate(a, b) => expanded_len(a)
+                .saturating_mul(2)
+                .saturating_add(expanded_len(b)),
+        }
+    }
+
+    fn inverse(&self) -> AlgNode {
+        match self {
+            AlgNode::Move(mv) => AlgNode::Move(mv.inverse()),
+            AlgNode::Group {
+                body,
+                repeat,
+                inverted,
+            } => AlgNode::Group {
+                body: body.clone(),
+                repeat: *repeat,
+                inverted: !inverted,
+            },
+            // [A, B]' = [B, A]
+            AlgNode::Commutator(a, b) => AlgNode::Commutator(b.clone(), a.clone()),
+            // [A: B]' = [A: B']
+            AlgNode::Conjugate(a, b) => AlgNode::Conjugate(a.clone(), invert_nodes(b)),
+        }
+    }
+}
+
+fn expand_nodes(nodes: &[AlgNode], moves: &mut Vec<Move>) {
+    for node in nodes {
+        node.expand_into(moves);
+    }
+}
+
+fn expanded_len(nodes: &[AlgNode]) -> usize {
+    nodes
+        .iter()
+        .fold(0, |len, node| len.saturating_add(node.expanded_len()))
+}
+
+fn invert_moves(moves: &[Move]) -> Vec<Move> {
+    moves.iter().rev().map(|m| m.inverse()).collect()
+}
+
+fn invert_nodes(nodes: &[AlgNode]) -> Vec<AlgNode> {
+    nodes.iter().rev().map(|n| n.inverse()).collect()
+}
+
+struct NodesDisplay<'a>(&'a [AlgNode]);
+
+impl fmt::Display for NodesDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, node) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", node)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for AlgNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlgNode::Move(mv) => write!(f, "{}", mv),
+            AlgNode::Group {
+                body,
+                repeat,
+                inverted,
+            } => {
+                match body.as_slice() {
+                    [bracket @ (AlgNode::Commutator(..) | AlgNode::Conjugate(..))] => {
+                        write!(f, "{}", bracket)?
+                    }
+                    _ => write!(f, "({})", NodesDisplay(body))?,
+                }
+                if *repeat != 1 {
+                    write!(f, "{}", repeat)?;
+                }
+                if *inverted {
+                    write!(f, "'")?;
+                }
+                Ok(())
+            }
+            AlgNode::Commutator(a, b) => {
+                write!(f, "[{}, {}]", NodesDisplay(a), NodesDisplay(b))
+            }
+            AlgNode::Conjugate(a, b) => {
+                write!(f, "[{}: {}]", NodesDisplay(a), NodesDisplay(b))
+            }
+        }
+    }
+}
+
+/// A sequence of moves, possibly written with groups, commutators and conjugates
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Algorithm {
+    nodes: Vec<AlgNode>,
+}
+
+impl Algorithm {
+    pub fn new() -> Algorithm {
+        Algorithm { nodes: vec![] }
+    }
+
+    pub fn from_nodes(nodes: Vec<AlgNode>) -> Algorithm {
+        Algorithm { nodes }
+    }
+
+    pub fn nodes(&self) -> &[AlgNode] {
+        &self.nodes
+    }
+
+    /// The plain move sequence, with every group and bracket expanded.
+    pub fn moves(&self) -> Vec<Move> {
+        let mut moves = vec![];
+        expand_nodes(&self.nodes, &mut moves);
+        moves
+    }
+
+    /// Number of moves once expanded.
+    pub fn len(&self) -> usize {
+        self.moves().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Length in `metric`, see [`Move::cost`].
+    pub fn length(&self, metric: Metric) -> u32 {
+        self.moves().iter().map(|mv| mv.cost(metric)).sum()
+    }
+
+    pub fn push(&mut self, mv: Move) {
+        self.nodes.push(AlgNode::Move(mv));
+    }
+
+    pub fn extend(&mut self, other: Algorithm) {
+        self.nodes.extend(other.nodes);
+    }
+
+    pub fn inverse(&self) -> Algorithm {
+        Algorithm {
+            nodes: invert_nodes(&self.nodes),
+        }
+    }
+}
+
+impl From<Vec<Move>> for Algorithm {
+    fn from(moves: Vec<Move>) -> Self {
+        Algorithm {
+            nodes: moves.into_iter().map(AlgNode::Move).collect(),
+        }
+    }
+}
+
+impl FromIterator<Move> for Algorithm {
+    fn from_iter<I: IntoIterator<Item = Move>>(iter: I) -> Self {
+        Algorithm::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", NodesDisplay(&self.nodes))
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let nodes = parser.parse_sequence()?;
+        match parser.peek() {
+            None => Ok(Algorithm { nodes }),
+            Some(c) => Err(parser.error(ParseErrorKind::UnexpectedChar(c))),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Algorithm {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Algorithm {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Move {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Move {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedChar(char),
+    UnexpectedEnd,
+    /// An opening `(` or `[` at the given position was never closed
+    Unclosed(usize),
+    /// A `[` bracket without `,` or `:`
+    MissingSeparator,
+    /// A turn amount that is a multiple of four, e.g. `R4`
+    NoTurn,
+    /// A layer prefix of 0, e.g. `0R`
+    NoLayer,
+    /// A group repeated more than [`MAX_REPEAT`] times, e.g. `(R U)1000`
+    RepeatTooLarge(u32),
+    /// The algorithm would expand to more than [`MAX_MOVES`] moves
+    TooLong,
+}
+
+/// Largest repeat count accepted after a group or bracket
+pub const MAX_REPEAT: u32 = 99;
+
+/// Largest number of moves a parsed algorithm may expand to
+pub const MAX_MOVES: usize = 10_000;
+
+/// A parse failure and the character position where it happened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnexpectedChar(c) => {
+                write!(
+                    f,
+                    "unexpected character '{}' at position {}",
+                    c, self.position
+                )
+            }
+            ParseErrorKind::UnexpectedEnd => {
+                write!(f, "unexpected end of input at position {}", self.position)
+            }
+            ParseErrorKind::Unclosed(open) => write!(
+                f,
+                "bracket opened at position {} is not closed at position {}",
+                open, self.position
+            ),
+            ParseErrorKind::MissingSeparator => write!(
+                f,
+                "expected ',' or ':' inside brackets at position {}",
+                self.position
+            ),
+            ParseErrorKind::NoTurn => {
+                write!(f, "move at position {} turns a full circle", self.position)
+            }
+            ParseErrorKind::NoLayer => {
+                write!(f, "move at position {} turns layer 0", self.position)
+            }
+            ParseErrorKind::RepeatTooLarge(count) => write!(
+                f,
+                "repeat count {} at position {} is larger than {}",
+                count, self.position, MAX_REPEAT
+            ),
+            ParseErrorKind::TooLong => write!(
+                f,
+                "algorithm expands to more than {} moves at position {}",
+                MAX_MOVES, self.position
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(s: &str) -> Parser {
+        Parser {
+            chars: s.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            position: self.pos,
+            kind,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parses items until the end of input or a closing/separator character.
+    fn parse_sequence(&mut self) -> Result<Vec<AlgNode>, ParseError> {
+        let mut nodes = vec![];
+        let mut len: usize = 0;
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+            let node = match self.peek() {
+                None | Some(')') | Some(']') | Some(',') | Some(':') => return Ok(nodes),
+                Some('(') => self.parse_group()?,
+                Some('[') => self.parse_bracket()?,
+                Some(_) => AlgNode::Move(self.parse_move()?),
+            };
+            // 嵌套的重复会成倍放大，超过上限就不再展开
+            len = len.saturating_add(node.expanded_len());
+            if len > MAX_MOVES {
+                return Err(ParseError {
+                    position: start,
+                    kind: ParseErrorKind::TooLong,
+                });
+            }
+            nodes.push(node);
+        }
+    }
+
+    fn expect_close(&mut self, close: char, open: usize) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(c) if c == close => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+            None => Err(self.error(ParseErrorKind::Unclosed(open))),
+        }
+    }
+
+    fn parse_group(&mut self) -> Result<AlgNode, ParseError> {
+        let open = self.pos;
+        self.pos += 1;
+        let body = self.parse_sequence()?;
+        self.expect_close(')', open)?;
+        let (repeat, inverted) = self.parse_repeat()?;
+        Ok(AlgNode::Group {
+            body,
+            repeat: repeat.unwrap_or(1),
+            inverted,
+        })
+    }
+
+    fn parse_bracket(&mut self) -> Result<AlgNode, ParseError> {
+        let open = self.pos;
+        self.pos += 1;
+        let a = self.parse_sequence()?;
+        let separator = match self.peek() {
+            Some(c @ (',' | ':')) => c,
+            Some(_) => return Err(self.error(ParseErrorKind::MissingSeparator)),
+            None => return Err(self.error(ParseErrorKind::Unclosed(open))),
+        };
+        self.pos += 1;
+        let b = self.parse_sequence()?;
+        self.expect_close(']', open)?;
+        let node = if separator == ',' {
+            AlgNode::Commutator(a, b)
+        } else {
+            AlgNode::Conjugate(a, b)
+        };
+        let (repeat, inverted) = self.parse_repeat()?;
+        if repeat.is_none() && !inverted {
+            return Ok(node);
+        }
+        Ok(AlgNode::Group {
+            body: vec![node],
+            repeat: repeat.unwrap_or(1),
+            inverted,
+        })
+    }
+
+    fn parse_move(&mut self) -> Result<Move, ParseError> {
+        let start = self.pos;
+        let mut depth: Option<u32> = None;
+        while let Some(digit) = self.peek().and_then(|c| c.to_digit(10)) {
+            depth = Some(depth.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+            self.pos += 1;
+        }
+        let Some(letter) = self.peek() else {
+            return Err(self.error(ParseErrorKind::UnexpectedEnd));
+        };
+        if let Some(depth) = depth {
+            let face = letter_face(letter)
+                .ok_or_else(|| self.error(ParseErrorKind::UnexpectedChar(letter)))?;
+            let wide = letter.is_lowercase() || self.chars.get(self.pos + 1) == Some(&'w');
+            if wide && letter.is_uppercase() {
+                self.pos += 1;
+            }
+            let depth = u8::try_from(depth).unwrap_or(u8::MAX);
+            let kind = match (depth, wide) {
+                (0, _) => {
+                    return Err(ParseError {
+                        position: start,
+                        kind: ParseErrorKind::NoLayer,
+                    })
+                }
+                (1, _) => MoveKind::Face(face),
+                (2, true) => MoveKind::Wide(face),
+                (_, true) => MoveKind::WideLayers(face, depth),
+                (_, false) => MoveKind::Layer(face, depth),
+            };
+            return self.parse_turn(start, kind);
+        }
+        let kind = match letter {
+            'U' | 'D' | 'F' | 'B' | 'L' | 'R' => {
+                let face = letter_face(letter).unwrap();
+                if self.chars.get(self.pos + 1) == Some(&'w') {
+                    self.pos += 1;
+                    MoveKind::Wide(face)
+                } else {
+                    MoveKind::Face(face)
+                }
+            }
+            'u' | 'd' | 'f' | 'b' | 'l' | 'r' => MoveKind::Wide(letter_face(letter).unwrap()),
+            'M' => MoveKind::Slice(Slice::M),
+            'E' => MoveKind::Slice(Slice::E),
+            'S' => MoveKind::Slice(Slice::S),
+            'x' => MoveKind::Rotation(Axis::X),
+            'y' => MoveKind::Rotation(Axis::Y),
+            'z' => MoveKind::Rotation(Axis::Z),
+            c => return Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+        };
+        self.parse_turn(start, kind)
+    }
+
+    /// Reads the turn amount after the letter of a move starting at `start`.
+    fn parse_turn(&mut self, start: usize, kind: MoveKind) -> Result<Move, ParseError> {
+        self.pos += 1;
+        let (amount, inverted) = self.parse_suffix();
+        let amount = amount.unwrap_or(1) % 4;
+        let quarter_turns = if inverted { 4 - amount } else { amount };
+        match Turn::from_quarter_turns(quarter_turns) {
+            Some(turn) => Ok(Move::new(kind, turn)),
+            None => Err(ParseError {
+                position: start,
+                kind: ParseErrorKind::NoTurn,
+            }),
+        }
+    }
+
+    /// Reads the suffix of a group, rejecting counts above [`MAX_REPEAT`].
+    fn parse_repeat(&mut self) -> Result<(Option<u32>, bool), ParseError> {
+        let start = self.pos;
+        let (repeat, inverted) = self.parse_suffix();
+        match repeat {
+            Some(count) if count > MAX_REPEAT => Err(ParseError {
+                position: start,
+                kind: ParseErrorKind::RepeatTooLarge(count),
+            }),
+            _ => Ok((repeat, inverted)),
+        }
+    }
+
+    /// Reads an optional count followed by an optional prime.
+    fn parse_suffix(&mut self) -> (Option<u32>, bool) {
+        let mut count: Option<u32> = None;
+        while let Some(digit) = self.peek().and_then(|c| c.to_digit(10)) {
+            count = Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+            self.pos += 1;
+        }
+        let inverted = matches!(self.peek(), Some('\'') | Some('’'));
+        if inverted {
+            self.pos += 1;
+        }
+        (count, inverted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(s: &str) -> Vec<Move> {
+        s.parse::<Algorithm>().unwrap().moves()
+    }
+
+    #[test]
+    fn parses_and_prints_basic_moves() {
+        let alg: Algorithm = "R U2 R' Rw x M' r2 U3".parse().unwrap();
+        assert_eq!(alg.to_string(), "R U2 R' Rw x M' Rw2 U'");
+        assert_eq!(alg.len(), 8);
+    }
+
+    #[test]
+    fn parses_layer_indexed_moves() {
+        let alg: Algorithm = "2R 3Rw' 3r2 1R 2Rw 2L'".parse().unwrap();
+        assert_eq!(alg.to_string(), "2R 3Rw' 3Rw2 R Rw 2L'");
+        assert_eq!(alg.moves()[0].kind, MoveKind::Layer(Face::Right, 2));
+        assert_eq!(alg.length(Metric::Htm), 8);
+        assert_eq!(alg.length(Metric::Stm), 6);
+
+        let err = "R 0R".parse::<Algorithm>().unwrap_err();
+        assert_eq!(err.position, 2);
+        assert_eq!(err.kind, ParseErrorKind::NoLayer);
+        let err = "2x".parse::<Algorithm>().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('x'));
+    }
+
+    #[test]
+    fn keeps_structure_when_printing() {
+        let text = "[R, U] [F: R U R' U'] (R U R' U')3 [R U: L]2'";
+        assert_eq!(text.parse::<Algorithm>().unwrap().to_string(), text);
+    }
+
+    #[test]
+    fn expands_commutators_and_conjugates() {
+        assert_eq!(moves("[R, U]"), moves("R U R' U'"));
+        assert_eq!(moves("[F: R U]"), moves("F R U F'"));
+        assert_eq!(moves("(R U)2'"), moves("U' R' U' R'"));
+        assert_eq!(moves("[R, U]'"), moves("U R U' R'"));
+    }
+
+    #[test]
+    fn inverse_matches_expanded_inverse() {
+        let alg: Algorithm = "[R U: [F, D2]] (L B')2 M".parse().unwrap();
+        let expected: Vec<Move> = alg.moves().iter().rev().map(|m| m.inverse()).collect();
+        assert_eq!(alg.inverse().moves(), expected);
+    }
+
+    #[test]
+    fn counts_moves_in_every_metric() {
+        let alg: Algorithm = "R U2 M' r2 x E2".parse().unwrap();
+        assert_eq!(alg.length(Metric::Htm), 7);
+        assert_eq!(alg.length(Metric::Qtm), 11);
+        assert_eq!(alg.length(Metric::Stm), 5);
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        let err = "R U Q".parse::<Algorithm>().unwrap_err();
+        assert_eq!(err.position, 4);
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('Q'));
+
+        let err = "R [U, F".parse::<Algorithm>().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::Unclosed(2));
+
+        let err = "[R U]".parse::<Algorithm>().unwrap_err();
+        assert_eq!(err.position, 4);
+        assert_eq!(err.kind, ParseErrorKind::MissingSeparator);
+
+        let err = "R U4".parse::<Algorithm>().unwrap_err();
+        assert_eq!(err.position, 2);
+        assert_eq!(err.kind, ParseErrorKind::NoTurn);
+    }
+
+    #[test]
+    fn limits_repeated_groups() {
+        assert_eq!(moves("(R U)99").len(), 198);
+
+        let err = "((R U)100000)100000".parse::<Algorithm>().unwrap_err();
+        assert_eq!(err.position, 6);
+        assert_eq!(err.kind, ParseErrorKind::RepeatTooLarge(100000));
+        let err = "R [R, U]1000".parse::<Algorithm>().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::RepeatTooLarge(1000));
+
+        // 每层都不超过上限，嵌套起来仍然太长
+        let err = "R ((R U R' U')50)99".parse::<Algorithm>().unwrap_err();
+        assert_eq!(err.position, 2);
+        assert_eq!(err.kind, ParseErrorKind::TooLong);
+        let long = vec!["(R U R' U')99"; 30].join(" ");
+        assert_eq!(
+            long.parse::<Algorithm>().unwrap_err().kind,
+            ParseErrorKind::TooLong
+        );
+    }
+}
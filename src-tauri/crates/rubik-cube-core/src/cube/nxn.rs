@@ -0,0 +1,304 @@
+//! Cubes with 2 to 7 layers
+//!
+//! [`NxnCube`] keeps the stickers the way [`Cube`] does, face by face and
+//! row by row, only with `size` rows and columns. Moves are permutations
+//! built from the same geometry as the 3x3 tables, once per size. The 3x3
+//! solvers keep working on [`Cube`]; convert with `From` and
+//! [`NxnCube::to_cube`].
+//!
+//! Solvers that work on pieces rather than colours number the stickers with
+//! [`sticker_index`] and move them with [`move_permutation`].
+
+use std::sync::OnceLock;
+
+use super::color::Color;
+use super::error::CubeError;
+use super::face::Face;
+use super::moves::{all_kinds, canonical, permutation, sticker_point};
+use super::notation::{Algorithm, Move, MoveKind, Turn};
+use super::scheme::ColorScheme;
+use super::Cube;
+
+pub const MIN_SIZE: usize = 2;
+pub const MAX_SIZE: usize = 7;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NxnCube {
+    size: usize,
+    /// `state[face][row][col]`, faces in [`Face`] order
+    state: Vec<Vec<Vec<Color>>>,
+}
+
+/// Permutations of every move kind for one size, indexed like
+/// [`all_kinds`] times the three turns
+struct SizeTables {
+    kinds: Vec<MoveKind>,
+    tables: Vec<Vec<usize>>,
+}
+
+fn tables(size: usize) -> &'static SizeTables {
+    static TABLES: [OnceLock<SizeTables>; MAX_SIZE + 1] = [const { OnceLock::new() }; MAX_SIZE + 1];
+    TABLES[size].get_or_init(|| {
+        let kinds = all_kinds(size);
+        let tables = kinds
+            .iter()
+            .flat_map(|&kind| {
+                [Turn::Clockwise, Turn::Double, Turn::CounterClockwise]
+                    .map(|turn| permutation(size, kind, turn))
+            })
+            .collect();
+        SizeTables { kinds, tables }
+    })
+}
+
+/// Stickers are numbered face by face in [`Face`] order, then row by row
+pub fn sticker_index(size: usize, face: Face, row: usize, col: usize) -> usize {
+    (face.ordinal() * size + row) * size + col
+}
+
+/// Where every sticker ends up after `mv`: the sticker numbered `i` moves
+/// to `move_permutation(size, mv)[i]`
+///
+/// # Panics
+///
+/// If the move does not fit this size, see [`Move::fits`].
+pub fn move_permutation(size: usize, mv: Move) -> &'static [usize] {
+    assert!(
+        mv.fits(size),
+        "{} does not fit a {}x{} cube",
+        mv,
+        size,
+        size
+    );
+    let tables = tables(size);
+    let kind = canonical(mv.kind);
+    let index = tables.kinds.iter().position(|&k| k == kind).unwrap();
+    &tables.tables[index * 3 + mv.turn.quarter_turns() as usize - 1]
+}
+
+/// The piece a sticker belongs to, as the coordinates of its cubie: from
+/// `-(size - 1)` to `size - 1` in steps of 2 on each axis. The stickers of
+/// one piece share them.
+pub fn sticker_cubie(size: usize, index: usize) -> [i32; 3] {
+    let face = Face::values()[index / (size * size)];
+    let max = size as i32 - 1;
+    sticker_point(size, face, index / size % size, index % size).map(|c| c.clamp(-max, max))
+}
+
+impl NxnCube {
+    /// A solved cube with `size` layers in the default scheme
+    ///
+    /// # Panics
+    ///
+    /// If `size` is outside `MIN_SIZE..=MAX_SIZE`.
+    pub fn new(size: usize) -> NxnCube {
+        NxnCube::with_scheme(size, ColorScheme::default())
+    }
+
+    /// A solved cube painted with `scheme`
+    ///
+    /// # Panics
+    ///
+    /// If `size` is outside `MIN_SIZE..=MAX_SIZE`.
+    pub fn with_scheme(size: usize, scheme: ColorScheme) -> NxnCube {
+        assert!(
+            (MIN_SIZE..=MAX_SIZE).contains(&size),
+            "Invalid cube size: {}",
+            size
+        );
+        NxnCube {
+            size,
+            state: scheme
+                .colors()
+                .into_iter()
+                .map(|color| vec![vec![color; size]; size])
+                .collect(),
+        }
+    }
+
+    /// The scheme the middle centres show on odd sizes. Even sizes have no
+    /// fixed centres and read it from the corners, see
+    /// [`ColorScheme::from_corners`].
+    pub fn scheme(&self) -> Result<ColorScheme, CubeError> {
+        if self.size.is_multiple_of(2) {
+            return ColorScheme::from_corners(self);
+        }
+        let middle = self.size / 2;
+        ColorScheme::new(std::array::from_fn(|face| self.state[face][middle][middle]))
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn state(&self) -> &[Vec<Vec<Color>>] {
+        &self.state
+    }
+
+    pub fn get_block_color(&self, face: usize, row: usize, col: usize) -> Color {
+        self.state[face][row][col]
+    }
+
+    pub fn set_block_color(&mut self, face: usize, row: usize, col: usize, color: Color) {
+        self.state[face][row][col] = color;
+    }
+
+    /// Every face has a single colour. Even cubes have no fixed centres,
+    /// so any orientation counts.
+    pub fn is_solved(&self) -> bool {
+        self.state
+            .iter()
+            .all(|face| face.iter().flatten().all(|&color| color == face[0][0]))
+    }
+
+    /// # Panics
+    ///
+    /// If the move does not fit this size, see [`Move::fits`].
+    pub fn apply_move(&mut self, mv: Move) {
+        let table = move_permutation(self.size, mv);
+        let n = self.size;
+        let old = self.state.clone();
+        for (face, rows) in old.iter().enumerate() {
+            for (row, colors) in rows.iter().enumerate() {
+                for (col, &color) in colors.iter().enumerate() {
+                    let to = table[(face * n + row) * n + col];
+                    self.state[to / (n * n)][to / n % n][to % n] = color;
+                }
+            }
+        }
+    }
+
+    pub fn apply_algorithm(&mut self, algorithm: &Algorithm) {
+        for mv in algorithm.moves() {
+            self.apply_move(mv);
+        }
+    }
+
+    /// Like [`NxnCube::apply_algorithm`], but leaves the cube untouched and
+    /// returns an error if any move needs a bigger cube.
+    pub fn try_apply_algorithm(&mut self, algorithm: &Algorithm) -> Result<(), CubeError> {
+        let moves = algorithm.moves();
+        let size = self.size;
+        if let Some(&mv) = moves.iter().find(|mv| !mv.fits(size)) {
+            return Err(CubeError::MoveDoesNotFit { mv, size });
+        }
+        for mv in moves {
+            self.apply_move(mv);
+        }
+        Ok(())
+    }
+
+    /// The same state as a [`Cube`], `None` unless this is a 3x3
+    pub fn to_cube(&self) -> Option<Cube> {
+        if self.size != 3 {
+            return None;
+        }
+        let mut state = [[[Color::White; 3]; 3]; 6];
+        for (face, rows) in self.state.iter().enumerate() {
+            for (row, colors) in rows.iter().enumerate() {
+                state[face][row].copy_from_slice(colors);
+            }
+        }
+        Some(Cube::from_state(state))
+    }
+}
+
+impl From<&Cube> for NxnCube {
+    fn from(cube: &Cube) -> Self {
+        NxnCube {
+            size: 3,
+            state: cube
+                .state
+                .iter()
+                .map(|face| face.iter().map(|row| row.to_vec()).collect())
+                .collect(),
+        }
+    }
+}
+
+impl From<Cube> for NxnCube {
+    fn from(cube: Cube) -> Self {
+        NxnCube::from(&cube)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after(size: usize, alg: &str) -> NxnCube {
+        let mut cube = NxnCube::new(size);
+        cube.apply_algorithm(&alg.parse().unwrap());
+        cube
+    }
+
+    #[test]
+    fn matches_the_3x3_tables() {
+        let alg: Algorithm = "R U2 F' Rw M 2U 3Fw' x L2 S".parse().unwrap();
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&alg);
+        let nxn = after(3, &alg.to_string());
+        assert_eq!(nxn.to_cube().unwrap(), cube);
+        assert_eq!(NxnCube::from(&cube), nxn);
+    }
+
+    #[test]
+    fn every_move_has_order_four() {
+        for size in MIN_SIZE..=MAX_SIZE {
+            for kind in all_kinds(size) {
+                let mut cube = after(size, "R U 2F' Lw D");
+                let before = cube.clone();
+                for _ in 0..4 {
+                    cube.apply_move(Move::new(kind, Turn::Clockwise));
+                }
+                assert_eq!(cube, before, "{} on {}x{}", kind, size, size);
+            }
+        }
+    }
+
+    #[test]
+    fn wide_turns_are_their_layers() {
+        assert_eq!(after(4, "Rw"), after(4, "R 2R"));
+        assert_eq!(after(5, "3Uw'"), after(5, "U' 2U' 3U'"));
+        assert_eq!(after(6, "x"), after(6, "6Rw"));
+        assert_eq!(after(7, "M'"), after(7, "4R"));
+        assert_eq!(after(4, "4R"), after(4, "L'"));
+    }
+
+    #[test]
+    fn solved_in_any_orientation() {
+        assert!(after(4, "x y2").is_solved());
+        assert!(after(2, "R U R' U'").to_cube().is_none());
+        assert!(!after(5, "2R").is_solved());
+        assert!(after(5, "(R U R' U')6").is_solved());
+    }
+
+    #[test]
+    fn stickers_of_a_piece_share_their_cubie() {
+        let n = 4;
+        let cubie = |face, row, col| sticker_cubie(n, sticker_index(n, face, row, col));
+        // UF 棱上靠左的一块，以及 URF 角块
+        assert_eq!(cubie(Face::Up, 3, 1), cubie(Face::Front, 0, 1));
+        assert_ne!(cubie(Face::Up, 3, 1), cubie(Face::Front, 0, 2));
+        assert_eq!(cubie(Face::Up, 3, 3), cubie(Face::Right, 0, 0));
+        assert_eq!(cubie(Face::Front, 0, 3), cubie(Face::Right, 0, 0));
+
+        let table = move_permutation(n, "2R".parse().unwrap());
+        let from = sticker_index(n, Face::Front, 1, 2);
+        assert_eq!(table[from], sticker_index(n, Face::Up, 1, 2));
+    }
+
+    #[test]
+    fn moves_check_the_size() {
+        assert!(!"M".parse::<Move>().unwrap().fits(4));
+        assert!(!"5R".parse::<Move>().unwrap().fits(4));
+        assert!("3Rw".parse::<Move>().unwrap().fits(3));
+
+        let mut cube = NxnCube::new(4);
+        let err = cube.try_apply_algorithm(&"R 2R M".parse().unwrap());
+        assert!(matches!(err, Err(CubeError::MoveDoesNotFit { size: 4, .. })));
+        assert!(cube.is_solved());
+        assert!(cube.try_apply_algorithm(&"R 2R".parse().unwrap()).is_ok());
+        assert_eq!(cube, after(4, "R 2R"));
+    }
+}
@@ -0,0 +1,160 @@
+//! Colour schemes: which colour each face shows when the cube is solved
+//!
+//! Nothing in the cube model depends on the scheme. Solvers take the face
+//! colours from the cube itself, see [`Cube::scheme`](super::Cube::scheme)
+//! and [`NxnCube::scheme`]; the scheme only matters when painting a solved
+//! cube.
+
+use super::color::Color;
+use super::cubie::Corner;
+use super::error::{CubeError, UnknownCode};
+use super::face::Face;
+use super::nxn::NxnCube;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorScheme {
+    /// Colours in [`Face`] order
+    colors: [Color; 6],
+}
+
+impl ColorScheme {
+    /// Yellow opposite white, blue opposite green, white on the bottom
+    pub const WESTERN: ColorScheme = ColorScheme {
+        colors: [
+            Color::Yellow,
+            Color::White,
+            Color::Blue,
+            Color::Green,
+            Color::Orange,
+            Color::Red,
+        ],
+    };
+
+    /// The Western scheme with blue and yellow swapped
+    pub const JAPANESE: ColorScheme = ColorScheme {
+        colors: [
+            Color::Blue,
+            Color::White,
+            Color::Yellow,
+            Color::Green,
+            Color::Orange,
+            Color::Red,
+        ],
+    };
+
+    /// `colors` in [`Face`] order, each colour on exactly one face.
+    pub fn new(colors: [Color; 6]) -> Result<ColorScheme, CubeError> {
+        for (i, color) in colors.iter().enumerate() {
+            if colors[..i].contains(color) {
+                return Err(CubeError::DuplicateCenter { color: *color });
+            }
+        }
+        Ok(ColorScheme { colors })
+    }
+
+    pub fn color(&self, face: Face) -> Color {
+        self.colors[face.ordinal()]
+    }
+
+    pub fn face(&self, color: Color) -> Face {
+        let ordinal = self.colors.iter().position(|&c| c == color).unwrap();
+        Face::try_from(ordinal as u8).unwrap()
+    }
+
+    pub fn colors(&self) -> [Color; 6] {
+        self.colors
+    }
+
+    /// Works the scheme out from the corners alone, for cubes without fixed
+    /// centres: the DBL corner gives the bottom, back and left colours, and
+    /// the opposite colour is the one never sharing a corner with it.
+    pub fn from_corners(cube: &NxnCube) -> Result<ColorScheme, CubeError> {
+        let last = cube.size() - 1;
+        // 3x3 的角块贴纸坐标 0、2 对应 N 阶的 0、N-1
+        let corners = Corner::ALL.map(|corner| {
+            corner.facelets().map(|(face, row, col)| {
+                cube.get_block_color(face.ordinal(), row / 2 * last, col / 2 * last)
+            })
+        });
+        let anchor = corners[Corner::DBL.index()];
+        let invalid = CubeError::InvalidCorner {
+            position: Corner::DBL,
+            colors: anchor,
+        };
+        let opposite = |color: Color| {
+            let mut apart = (0..6).map(Color::from_u8).filter(|&other| {
+                other != color
+                    && !corners
+                        .iter()
+                        .any(|colors| colors.contains(&color) && colors.contains(&other))
+            });
+            match (apart.next(), apart.next()) {
+                (Some(other), None) => Some(other),
+                _ => None,
+            }
+        };
+
+        let mut colors = [Color::White; 6];
+        for (color, face) in anchor.into_iter().zip(Corner::DBL.faces()) {
+            colors[face.ordinal()] = color;
+            colors[face.ordinal() ^ 1] = opposite(color).ok_or_else(|| invalid.clone())?;
+        }
+        ColorScheme::new(colors).map_err(|_| invalid)
+    }
+}
+
+/// 0 Western, 1 Japanese
+impl TryFrom<u8> for ColorScheme {
+    type Error = UnknownCode;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ColorScheme::WESTERN),
+            1 => Ok(ColorScheme::JAPANESE),
+            _ => Err(UnknownCode {
+                kind: "colour scheme",
+                value,
+            }),
+        }
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme::WESTERN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Cube;
+    use super::*;
+
+    #[test]
+    fn presets_match_their_solved_cubes() {
+        assert_eq!(Cube::new().scheme(), Ok(ColorScheme::WESTERN));
+        let japanese = Cube::with_scheme(ColorScheme::JAPANESE);
+        assert!(japanese.is_solved());
+        assert!(japanese.validate().is_ok());
+        assert_eq!(japanese.scheme(), Ok(ColorScheme::JAPANESE));
+        assert_eq!(ColorScheme::JAPANESE.face(Color::Yellow), Face::Front);
+        assert!(ColorScheme::new([Color::White; 6]).is_err());
+        assert_eq!(ColorScheme::try_from(1), Ok(ColorScheme::JAPANESE));
+        assert!(ColorScheme::try_from(2).is_err());
+    }
+
+    #[test]
+    fn reads_the_scheme_of_any_size() {
+        for size in 2..=5 {
+            let mut cube = NxnCube::with_scheme(size, ColorScheme::JAPANESE);
+            assert_eq!(cube.scheme(), Ok(ColorScheme::JAPANESE));
+            // 转动不动 DBL 角块，偶数阶仍从角块读出同样的配色
+            cube.apply_algorithm(&"R U F' R2 U'".parse().unwrap());
+            assert_eq!(cube.scheme(), Ok(ColorScheme::JAPANESE));
+        }
+        let mut rotated = Cube::with_scheme(ColorScheme::JAPANESE);
+        rotated.apply_algorithm(&"x".parse().unwrap());
+        assert_ne!(rotated.scheme(), Ok(ColorScheme::JAPANESE));
+        assert!(rotated.is_solved());
+    }
+}
@@ -0,0 +1,181 @@
+//! Facelet-state validation
+
+use super::color::Color;
+use super::cubie::{Corner, CubieCube, Edge};
+use super::error::CubeError;
+use super::face::Face;
+use super::Cube;
+
+impl Cube {
+    /// Builds a cube from raw colours, rejecting states no real cube can reach.
+    pub fn try_from_state(state: [[[Color; 3]; 3]; 6]) -> Result<Cube, CubeError> {
+        let cube = Cube::from_state(state);
+        cube.validate()?;
+        Ok(cube)
+    }
+
+    /// Checks colour counts, cubie identity, corner twist, edge flip and parity.
+    pub fn validate(&self) -> Result<(), CubeError> {
+        self.identify_cubies()?.verify()
+    }
+
+    /// Works out which cubie sits in every slot, using the centres as reference.
+    pub(crate) fn identify_cubies(&self) -> Result<CubieCube, CubeError> {
+        let mut counts = [0usize; 6];
+        for face in self.state.iter() {
+            for row in face.iter() {
+                for color in row.iter() {
+                    counts[*color as usize] += 1;
+                }
+            }
+        }
+        for (value, count) in counts.iter().enumerate() {
+            if *count != 9 {
+                return Err(CubeError::ColorCount {
+                    color: Color::from_u8(value as u8),
+                    count: *count,
+                });
+            }
+        }
+
+        // 中心块决定每种颜色属于哪个面
+        let mut face_of_color: [Option<Face>; 6] = [None; 6];
+        for face in Face::values() {
+            let color = self.state[face.ordinal()][1][1];
+            if face_of_color[color as usize].is_some() {
+                return Err(CubeError::DuplicateCenter { color });
+            }
+            face_of_color[color as usize] = Some(face);
+        }
+        let face_of = |color: Color| face_of_color[color as usize].unwrap();
+
+        let mut cubie_cube = CubieCube::solved();
+        let mut corner_seen: [Option<Corner>; 8] = [None; 8];
+        for position in Corner::ALL {
+            let colors = position
+                .facelets()
+                .map(|(face, row, col)| self.state[face.ordinal()][row][col]);
+            let faces = colors.map(face_of);
+            let twist = faces
+                .iter()
+                .position(|f| *f == Face::Up || *f == Face::Down);
+            let cubie = twist.and_then(|t| {
+                let rotated = [faces[t], faces[(t + 1) % 3], faces[(t + 2) % 3]];
+                Corner::ALL.into_iter().find(|c| c.faces() == rotated)
+            });
+            let (Some(twist), Some(cubie)) = (twist, cubie) else {
+                return Err(CubeError::InvalidCorner { position, colors });
+            };
+            if let Some(other) = corner_seen[cubie.index()] {
+                return Err(CubeError::DuplicateCorner {
+                    corner: cubie,
+                    positions: [other, position],
+                });
+            }
+            corner_seen[cubie.index()] = Some(position);
+            cubie_cube.cp[position.index()] = cubie;
+            cubie_cube.co[position.index()] = twist as u8;
+        }
+
+        let mut edge_seen: [Option<Edge>; 12] = [None; 12];
+        for position in Edge::ALL {
+            let colors = position
+                .facelets()
+                .map(|(face, row, col)| self.state[face.ordinal()][row][col]);
+            let faces = colors.map(face_of);
+            let found = Edge::ALL.into_iter().find_map(|e| {
+                let home = e.faces();
+                if home == faces {
+                    Some((e, 0))
+                } else if home == [faces[1], faces[0]] {
+                    Some((e, 1))
+                } else {
+                    None
+                }
+            });
+            let Some((cubie, flip)) = found else {
+                return Err(CubeError::InvalidEdge { position, colors });
+            };
+            if let Some(other) = edge_seen[cubie.index()] {
+                return Err(CubeError::DuplicateEdge {
+                    edge: cubie,
+                    positions: [other, position],
+                });
+            }
+            edge_seen[cubie.index()] = Some(position);
+            cubie_cube.ep[position.index()] = cubie;
+            cubie_cube.eo[position.index()] = flip;
+        }
+
+        Ok(cubie_cube)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap(cube: &mut Cube, a: (Face, usize, usize), b: (Face, usize, usize)) {
+        let color_a = cube.get_block_color(a.0.ordinal(), a.1, a.2);
+        let color_b = cube.get_block_color(b.0.ordinal(), b.1, b.2);
+        cube.set_block_color(a.0.ordinal(), a.1, a.2, color_b);
+        cube.set_block_color(b.0.ordinal(), b.1, b.2, color_a);
+    }
+
+    #[test]
+    fn solved_cube_is_valid() {
+        assert_eq!(Cube::new().validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_wrong_color_count() {
+        let mut cube = Cube::new();
+        cube.set_block_color(Face::Up.ordinal(), 0, 0, Color::White);
+        assert!(matches!(cube.validate(), Err(CubeError::ColorCount { .. })));
+    }
+
+    #[test]
+    fn rejects_flipped_edge() {
+        let mut cube = Cube::new();
+        let [a, b] = Edge::UF.facelets();
+        swap(&mut cube, a, b);
+        assert_eq!(cube.validate(), Err(CubeError::EdgeFlip { sum: 1 }));
+    }
+
+    #[test]
+    fn rejects_twisted_corner() {
+        let mut cube = Cube::new();
+        let [a, b, c] = Corner::URF.facelets();
+        swap(&mut cube, a, b);
+        swap(&mut cube, a, c);
+        assert!(matches!(
+            cube.validate(),
+            Err(CubeError::CornerTwist { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_single_edge_swap() {
+        let mut cube = Cube::new();
+        let [uf_u, uf_f] = Edge::UF.facelets();
+        let [ur_u, ur_r] = Edge::UR.facelets();
+        swap(&mut cube, uf_u, ur_u);
+        swap(&mut cube, uf_f, ur_r);
+        assert_eq!(cube.validate(), Err(CubeError::Parity));
+    }
+
+    #[test]
+    fn rejects_impossible_corner() {
+        let mut cube = Cube::new();
+        let [_, urf_r, _] = Corner::URF.facelets();
+        let [_, uf_f] = Edge::UF.facelets();
+        swap(&mut cube, urf_r, uf_f);
+        assert!(matches!(
+            cube.validate(),
+            Err(CubeError::InvalidCorner {
+                position: Corner::URF,
+                ..
+            })
+        ));
+    }
+}
@@ -1,2 +1,6 @@
+mod nxn;
+mod random_state;
 mod shuffler;
+pub use nxn::*;
+pub use random_state::*;
 pub use shuffler::*;
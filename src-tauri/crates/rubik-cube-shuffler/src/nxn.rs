@@ -0,0 +1,127 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use rubik_cube_core::cube::{
+    face::Face,
+    notation::{Algorithm, Move, MoveKind, Turn},
+    nxn::NxnCube,
+};
+
+use crate::{FacePicker, SCRAMBLE_LENGTH};
+
+/// Random-move scramble length for each size, as in competitions
+pub fn scramble_length(size: usize) -> u32 {
+    match size {
+        2 => 11,
+        3 => SCRAMBLE_LENGTH,
+        4 => 40,
+        5 => 60,
+        6 => 80,
+        _ => 100,
+    }
+}
+
+/// Random-move scrambles for cubes of any size, seeded like
+/// [`CubeShuffler`](crate::CubeShuffler).
+pub struct NxnShuffler<'a, R = StdRng> {
+    cube: &'a mut NxnCube,
+    rng: R,
+    seed: Option<u64>,
+}
+
+impl<'a> NxnShuffler<'a, StdRng> {
+    /// Shuffles with a fresh random seed, see [`NxnShuffler::seed`].
+    pub fn new(cube: &'a mut NxnCube) -> Self {
+        let seed = rand::thread_rng().gen_range(0..1 << 53);
+        Self::with_seed(cube, seed)
+    }
+
+    pub fn with_seed(cube: &'a mut NxnCube, seed: u64) -> Self {
+        NxnShuffler {
+            cube,
+            rng: StdRng::seed_from_u64(seed),
+            seed: Some(seed),
+        }
+    }
+}
+
+impl<'a, R: Rng> NxnShuffler<'a, R> {
+    pub fn with_rng(cube: &'a mut NxnCube, rng: R) -> Self {
+        NxnShuffler {
+            cube,
+            rng,
+            seed: None,
+        }
+    }
+
+    /// The seed the moves are generated from, `None` for a caller's `Rng`
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Random-move scramble with the faces picked like the 3x3 one. The 2x2
+    /// only turns R, U and F, since the other faces repeat them; bigger
+    /// cubes also turn wide blocks of up to half the layers (`Rw`, `3Rw`).
+    pub fn scramble(&mut self, length: u32) -> Algorithm {
+        let size = self.cube.size();
+        let faces = if size == 2 {
+            vec![Face::Up, Face::Front, Face::Right]
+        } else {
+            Face::values()
+        };
+        let depth = (size / 2).max(1);
+
+        let mut moves = Algorithm::new();
+        let mut picker = FacePicker::default();
+        for _ in 0..length {
+            let face = picker.next(&mut self.rng, &faces);
+            let kind = match self.rng.gen_range(1..=depth) {
+                1 => MoveKind::Face(face),
+                2 => MoveKind::Wide(face),
+                layers => MoveKind::WideLayers(face, layers as u8),
+            };
+            let turn = Turn::from_quarter_turns(self.rng.gen_range(1..4)).unwrap();
+            let mv = Move::new(kind, turn);
+            self.cube.apply_move(mv);
+            moves.push(mv);
+        }
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_scramble() {
+        for size in 2..=7 {
+            let mut first = NxnCube::new(size);
+            let moves = NxnShuffler::with_seed(&mut first, 42).scramble(scramble_length(size));
+            let mut second = NxnCube::new(size);
+            second.apply_algorithm(&moves);
+            assert_eq!(first, second);
+            assert!(!first.is_solved());
+            assert_eq!(moves.len(), scramble_length(size) as usize);
+        }
+    }
+
+    #[test]
+    fn turns_fit_the_size() {
+        let mut cube = NxnCube::new(2);
+        let moves = NxnShuffler::with_seed(&mut cube, 7).scramble(30);
+        assert!(moves.moves().iter().all(|mv| matches!(
+            mv.kind,
+            MoveKind::Face(Face::Up | Face::Front | Face::Right)
+        )));
+
+        let mut cube = NxnCube::new(7);
+        let moves = NxnShuffler::with_seed(&mut cube, 7).scramble(100);
+        assert!(moves.moves().iter().all(|mv| mv.fits(7)));
+        assert!(
+            moves
+                .moves()
+                .iter()
+                .any(|mv| matches!(mv.kind, MoveKind::WideLayers(_, 3)))
+        );
+    }
+}
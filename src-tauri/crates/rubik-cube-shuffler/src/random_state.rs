@@ -0,0 +1,160 @@
+use rand::{Rng, seq::SliceRandom};
+
+use rubik_cube_core::cube::cubie::{Corner, CubieCube, Edge};
+use rubik_cube_solver::solver::SolveTarget;
+
+/// A uniformly random state among all 4.3×10^19 reachable ones.
+pub fn random_state<R: Rng + ?Sized>(rng: &mut R) -> CubieCube {
+    let mut cube = CubieCube::solved();
+    cube.cp.shuffle(rng);
+    cube.ep.shuffle(rng);
+    // 奇偶性不一致时交换两个棱块；这是奇偶两类排列之间的一一对应，不影响均匀性
+    if cube.corner_parity() != cube.edge_parity() {
+        cube.ep.swap(10, 11);
+    }
+
+    for i in 0..7 {
+        cube.co[i] = rng.gen_range(0..3);
+    }
+    cube.co[7] = (3 - cube.co[..7].iter().sum::<u8>() % 3) % 3;
+    for i in 0..11 {
+        cube.eo[i] = rng.gen_range(0..2);
+    }
+    cube.eo[11] = cube.eo[..11].iter().sum::<u8>() % 2;
+    cube
+}
+
+/// The cubies a stage leaves solved, on top of the stages before it
+struct Restriction {
+    corners: Vec<Corner>,
+    edges: Vec<Edge>,
+    /// The other corners keep their U/D sticker on U/D
+    corners_oriented: bool,
+    /// The other edges are not flipped
+    edges_oriented: bool,
+}
+
+fn restriction(solved: SolveTarget) -> Restriction {
+    use Corner::*;
+    use Edge::*;
+    let cross = vec![DR, DF, DL, DB];
+    let first_layer = vec![DFR, DLF, DBL, DRB];
+    let first_two_layers = [cross.clone(), vec![FR, FL, BL, BR]].concat();
+    let restriction = |corners: &[Corner], edges: &[Edge], co: bool, eo: bool| Restriction {
+        corners: corners.to_vec(),
+        edges: edges.to_vec(),
+        corners_oriented: co,
+        edges_oriented: eo,
+    };
+    match solved {
+        SolveTarget::BottomCross | SolveTarget::Cross => restriction(&[], &cross, false, false),
+        SolveTarget::BottomCorner => restriction(&first_layer, &cross, false, false),
+        SolveTarget::MiddleEdge | SolveTarget::F2l => {
+            restriction(&first_layer, &first_two_layers, false, false)
+        }
+        SolveTarget::TopCross => restriction(&first_layer, &first_two_layers, false, true),
+        SolveTarget::TopFace | SolveTarget::Oll => {
+            restriction(&first_layer, &first_two_layers, true, true)
+        }
+        SolveTarget::TopCorner => restriction(&Corner::ALL, &first_two_layers, true, true),
+        SolveTarget::TopEdge | SolveTarget::Pll => {
+            restriction(&Corner::ALL, &Edge::ALL, true, true)
+        }
+    }
+}
+
+/// Shuffles the cubies in the free slots and gives them random
+/// orientations, or none if `oriented`. Returns the free slots.
+fn scatter<T: Copy + PartialEq, R: Rng + ?Sized>(
+    rng: &mut R,
+    pieces: &mut [T],
+    orientations: &mut [u8],
+    fixed: &[T],
+    modulus: u8,
+    oriented: bool,
+) -> Vec<usize> {
+    let free: Vec<usize> = (0..pieces.len())
+        .filter(|&i| !fixed.contains(&pieces[i]))
+        .collect();
+    let mut shuffled: Vec<T> = free.iter().map(|&i| pieces[i]).collect();
+    shuffled.shuffle(rng);
+    for (&slot, piece) in free.iter().zip(shuffled) {
+        pieces[slot] = piece;
+    }
+    if let Some((&last, rest)) = free.split_last().filter(|_| !oriented) {
+        for &slot in rest {
+            orientations[slot] = rng.gen_range(0..modulus);
+        }
+        let sum: u8 = rest.iter().map(|&slot| orientations[slot]).sum::<u8>() % modulus;
+        orientations[last] = (modulus - sum) % modulus;
+    }
+    free
+}
+
+/// A uniformly random state among those where `solved` and every stage of
+/// its method before it are solved, e.g. a random last layer for
+/// [`SolveTarget::F2l`].
+pub fn random_state_after<R: Rng + ?Sized>(rng: &mut R, solved: SolveTarget) -> CubieCube {
+    let restriction = restriction(solved);
+    let mut cube = CubieCube::solved();
+    let free_corners = scatter(
+        rng,
+        &mut cube.cp,
+        &mut cube.co,
+        &restriction.corners,
+        3,
+        restriction.corners_oriented,
+    );
+    let free_edges = scatter(
+        rng,
+        &mut cube.ep,
+        &mut cube.eo,
+        &restriction.edges,
+        2,
+        restriction.edges_oriented,
+    );
+    if cube.corner_parity() != cube.edge_parity() {
+        if free_edges.len() >= 2 {
+            cube.ep.swap(free_edges[0], free_edges[1]);
+        } else {
+            cube.cp.swap(free_corners[0], free_corners[1]);
+        }
+    }
+    cube
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subset_states_reach_their_stage() {
+        let mut rng = rand::thread_rng();
+        for value in 0..=10 {
+            let solved = SolveTarget::try_from(value).unwrap();
+            for _ in 0..20 {
+                let state = random_state_after(&mut rng, solved);
+                assert_eq!(state.verify(), Ok(()));
+                assert!(solved.is_reached(&state.to_cube()), "{:?}", solved);
+            }
+        }
+    }
+
+    #[test]
+    fn last_layer_states_vary() {
+        let mut rng = rand::thread_rng();
+        let states: Vec<CubieCube> = (0..20)
+            .map(|_| random_state_after(&mut rng, SolveTarget::F2l))
+            .collect();
+        assert!(states.iter().any(|state| !state.is_solved()));
+        assert!(states.iter().any(|state| state != &states[0]));
+    }
+
+    #[test]
+    fn random_states_are_legal() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            assert_eq!(random_state(&mut rng).verify(), Ok(()));
+        }
+    }
+}
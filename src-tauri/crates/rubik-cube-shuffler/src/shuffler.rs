@@ -1,10 +1,43 @@
-use rand::{Rng, rngs::ThreadRng};
+use rand::{Rng, SeedableRng, rngs::StdRng};
 
+use rubik_cube_core::cube::cubie::CubieCube;
 use rubik_cube_core::cube::{
     Cube,
-    color::Color,
     face::{Face, TwistDirection},
+    notation::{Algorithm, Move, MoveKind, Turn},
 };
+use rubik_cube_solver::solver::{SolveTarget, TwoPhaseSolver};
+
+use crate::{random_state, random_state_after};
+
+/// Length of competition random-move scrambles; 20 is also common for practice.
+pub const SCRAMBLE_LENGTH: u32 = 25;
+
+/// Picks the faces of a random-move scramble: never the same face twice in
+/// a row and never three turns on one axis in a row (`L R L`).
+#[derive(Default)]
+pub(crate) struct FacePicker {
+    last: Option<Face>,
+    last_axis_twice: bool,
+}
+
+impl FacePicker {
+    pub(crate) fn next<R: Rng + ?Sized>(&mut self, rng: &mut R, faces: &[Face]) -> Face {
+        loop {
+            let index: i32 = rng.gen_range(0..faces.len() as i32);
+            let face = faces[index as usize];
+            // U/D、F/B、L/R 的 ordinal 两两相邻，除以 2 就是所在的轴
+            let same_axis = self
+                .last
+                .is_some_and(|l| l.ordinal() / 2 == face.ordinal() / 2);
+            if Some(face) != self.last && !(same_axis && self.last_axis_twice) {
+                self.last_axis_twice = same_axis;
+                self.last = Some(face);
+                return face;
+            }
+        }
+    }
+}
 
 pub struct CubeScrambler<'a> {
     cube: &'a mut Cube,
@@ -16,605 +49,62 @@ impl<'a> CubeScrambler<'a> {
     }
 
     pub fn scramble(&mut self, face: Face, direction: TwistDirection) {
-        // 保存当前面的状态
-        let mut current_face = [[Color::White; 3]; 3];
-        for i in 0..3 {
-            for j in 0..3 {
-                current_face[i][j] = self.cube.get_block_color(face.ordinal(), i, j);
-            }
-        }
-
-        // 旋转当前面
-        for i in 0..3 {
-            for j in 0..3 {
-                if direction == TwistDirection::Clockwise {
-                    self.cube
-                        .set_block_color(face.ordinal(), i, j, current_face[2 - j][i]);
-                } else {
-                    self.cube
-                        .set_block_color(face.ordinal(), i, j, current_face[j][2 - i]);
-                }
-            }
-        }
+        self.apply(Move::face(face, direction));
+    }
 
-        // 旋转相邻的边
-        self.rotate_adjacent_edges(face, direction);
+    /// Applies any move: outer, wide and slice turns of any amount, or a cube rotation.
+    pub fn apply(&mut self, mv: Move) {
+        self.cube.apply_move(mv);
     }
 
-    fn rotate_adjacent_edges(&mut self, face: Face, direction: TwistDirection) {
-        let mut temp = [Color::White; 3];
-
-        match face {
-            Face::Up => {
-                // 保存前面的边
-                for i in 0..3 {
-                    temp[i] = self.cube.get_block_color(
-                        Face::Front.ordinal(),
-                        0,
-                        i,
-                    );
-                }
-
-                if direction == TwistDirection::Clockwise {
-                    // 前 -> 右 -> 后 -> 左 -> 前
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Front.ordinal(),
-                            0,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Right.ordinal(),
-                                0,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Right.ordinal(),
-                            0,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Back.ordinal(),
-                                0,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Back.ordinal(),
-                            0,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Left.ordinal(),
-                                0,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Left.ordinal(),
-                            0,
-                            i,
-                            temp[i],
-                        );
-                    }
-                } else {
-                    // 前 <- 右 <- 后 <- 左 <- 前
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Front.ordinal(),
-                            0,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Left.ordinal(),
-                                0,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Left.ordinal(),
-                            0,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Back.ordinal(),
-                                0,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Back.ordinal(),
-                            0,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Right.ordinal(),
-                                0,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Right.ordinal(),
-                            0,
-                            i,
-                            temp[i],
-                        );
-                    }
-                }
-            }
-            Face::Down => {
-                // 保存前面的边
-                for i in 0..3 {
-                    temp[i] = self.cube.get_block_color(
-                        Face::Front.ordinal(),
-                        2,
-                        i,
-                    );
-                }
-
-                if direction == TwistDirection::Clockwise {
-                    // 前 <- 左 <- 后 <- 右 <- 前
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Front.ordinal(),
-                            2,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Left.ordinal(),
-                                2,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Left.ordinal(),
-                            2,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Back.ordinal(),
-                                2,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Back.ordinal(),
-                            2,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Right.ordinal(),
-                                2,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Right.ordinal(),
-                            2,
-                            i,
-                            temp[i],
-                        );
-                    }
-                } else {
-                    // 前 -> 左 -> 后 -> 右 -> 前
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Front.ordinal(),
-                            2,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Right.ordinal(),
-                                2,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Right.ordinal(),
-                            2,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Back.ordinal(),
-                                2,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Back.ordinal(),
-                            2,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Left.ordinal(),
-                                2,
-                                i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Left.ordinal(),
-                            2,
-                            i,
-                            temp[i],
-                        );
-                    }
-                }
-            }
-            Face::Front => {
-                // 保存上面的边
-                for i in 0..3 {
-                    temp[i] = self.cube.get_block_color(
-                        Face::Up.ordinal(),
-                        2,
-                        i,
-                    );
-                }
-
-                if direction == TwistDirection::Clockwise {
-                    // 上 -> 右 -> 下 -> 左 -> 上
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Up.ordinal(),
-                            2,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Left.ordinal(),
-                                2 - i,
-                                2,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Left.ordinal(),
-                            2 - i,
-                            2,
-                            self.cube.get_block_color(
-                                Face::Down.ordinal(),
-                                0,
-                                2 - i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Down.ordinal(),
-                            0,
-                            2 - i,
-                            self.cube.get_block_color(
-                                Face::Right.ordinal(),
-                                i,
-                                0,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Right.ordinal(),
-                            i,
-                            0,
-                            temp[i],
-                        );
-                    }
-                } else {
-                    // 上 <- 右 <- 下 <- 左 <- 上
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Up.ordinal(),
-                            2,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Right.ordinal(),
-                                i,
-                                0,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Right.ordinal(),
-                            i,
-                            0,
-                            self.cube.get_block_color(
-                                Face::Down.ordinal(),
-                                0,
-                                2 - i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Down.ordinal(),
-                            0,
-                            2 - i,
-                            self.cube.get_block_color(
-                                Face::Left.ordinal(),
-                                2 - i,
-                                2,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Left.ordinal(),
-                            2 - i,
-                            2,
-                            temp[i],
-                        );
-                    }
-                }
-            }
-            Face::Back => {
-                // 保存上面的边
-                for i in 0..3 {
-                    temp[i] = self.cube.get_block_color(
-                        Face::Up.ordinal(),
-                        0,
-                        i,
-                    );
-                }
-
-                if direction == TwistDirection::Clockwise {
-                    // 上 <- 右 <- 下 <- 左 <- 上
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Up.ordinal(),
-                            0,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Right.ordinal(),
-                                i,
-                                2,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Right.ordinal(),
-                            i,
-                            2,
-                            self.cube.get_block_color(
-                                Face::Down.ordinal(),
-                                2,
-                                2 - i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Down.ordinal(),
-                            2,
-                            2 - i,
-                            self.cube.get_block_color(
-                                Face::Left.ordinal(),
-                                2 - i,
-                                0,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Left.ordinal(),
-                            2 - i,
-                            0,
-                            temp[i],
-                        );
-                    }
-                } else {
-                    // 上 -> 右 -> 下 -> 左 -> 上
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Up.ordinal(),
-                            0,
-                            i,
-                            self.cube.get_block_color(
-                                Face::Left.ordinal(),
-                                2 - i,
-                                0,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Left.ordinal(),
-                            2 - i,
-                            0,
-                            self.cube.get_block_color(
-                                Face::Down.ordinal(),
-                                2,
-                                2 - i,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Down.ordinal(),
-                            2,
-                            2 - i,
-                            self.cube.get_block_color(
-                                Face::Right.ordinal(),
-                                i,
-                                2,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Right.ordinal(),
-                            i,
-                            2,
-                            temp[i],
-                        );
-                    }
-                }
-            }
-            Face::Left => {
-                // 保存上面的边
-                for i in 0..3 {
-                    temp[i] = self.cube.get_block_color(
-                        Face::Up.ordinal(),
-                        i,
-                        0,
-                    );
-                }
-
-                if direction == TwistDirection::Clockwise {
-                    // 上 -> 前 -> 下 -> 后 -> 上
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Up.ordinal(),
-                            i,
-                            0,
-                            self.cube.get_block_color(
-                                Face::Back.ordinal(),
-                                2 - i,
-                                2,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Back.ordinal(),
-                            2 - i,
-                            2,
-                            self.cube.get_block_color(
-                                Face::Down.ordinal(),
-                                i,
-                                0,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Down.ordinal(),
-                            i,
-                            0,
-                            self.cube.get_block_color(
-                                Face::Front.ordinal(),
-                                i,
-                                0,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Front.ordinal(),
-                            i,
-                            0,
-                            temp[i],
-                        );
-                    }
-                } else {
-                    // 上 <- 前 <- 下 <- 后 <- 上
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Up.ordinal(),
-                            i,
-                            0,
-                            self.cube.get_block_color(
-                                Face::Front.ordinal(),
-                                i,
-                                0,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Front.ordinal(),
-                            i,
-                            0,
-                            self.cube.get_block_color(
-                                Face::Down.ordinal(),
-                                i,
-                                0,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Down.ordinal(),
-                            i,
-                            0,
-                            self.cube.get_block_color(
-                                Face::Back.ordinal(),
-                                2 - i,
-                                2,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Back.ordinal(),
-                            2 - i,
-                            2,
-                            temp[i],
-                        );
-                    }
-                }
-            }
-            Face::Right => {
-                // 保存上面的边
-                for i in 0..3 {
-                    temp[i] = self.cube.get_block_color(
-                        Face::Up.ordinal(),
-                        i,
-                        2,
-                    );
-                }
-
-                if direction == TwistDirection::Clockwise {
-                    // 上 <- 前 <- 下 <- 后 <- 上
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Up.ordinal(),
-                            i,
-                            2,
-                            self.cube.get_block_color(
-                                Face::Front.ordinal(),
-                                i,
-                                2,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Front.ordinal(),
-                            i,
-                            2,
-                            self.cube.get_block_color(
-                                Face::Down.ordinal(),
-                                i,
-                                2,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Down.ordinal(),
-                            i,
-                            2,
-                            self.cube.get_block_color(
-                                Face::Back.ordinal(),
-                                2 - i,
-                                0,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Back.ordinal(),
-                            2 - i,
-                            0,
-                            temp[i],
-                        );
-                    }
-                } else {
-                    // 上 -> 前 -> 下 -> 后 -> 上
-                    for i in 0..3 {
-                        self.cube.set_block_color(
-                            Face::Up.ordinal(),
-                            i,
-                            2,
-                            self.cube.get_block_color(
-                                Face::Back.ordinal(),
-                                2 - i,
-                                0,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Back.ordinal(),
-                            2 - i,
-                            0,
-                            self.cube.get_block_color(
-                                Face::Down.ordinal(),
-                                i,
-                                2,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Down.ordinal(),
-                            i,
-                            2,
-                            self.cube.get_block_color(
-                                Face::Front.ordinal(),
-                                i,
-                                2,
-                            ),
-                        );
-                        self.cube.set_block_color(
-                            Face::Front.ordinal(),
-                            i,
-                            2,
-                            temp[i],
-                        );
-                    }
-                }
-            }
-        }
+    pub fn apply_algorithm(&mut self, algorithm: &Algorithm) {
+        self.cube.apply_algorithm(algorithm);
     }
 }
 
-pub struct CubeShuffler<'a> {
+/// Random quarter turns. A shuffler built with [`CubeShuffler::new`] or
+/// [`CubeShuffler::with_seed`] remembers its seed, so the same scramble can
+/// be generated again (with the same version of `rand`).
+pub struct CubeShuffler<'a, R = StdRng> {
     scrambler: CubeScrambler<'a>,
-    rng: Option<ThreadRng>,
+    rng: R,
+    seed: Option<u64>,
 }
 
-impl<'a> CubeShuffler<'a> {
+impl<'a> CubeShuffler<'a, StdRng> {
+    /// Shuffles with a fresh random seed, see [`CubeShuffler::seed`].
     pub fn new(cube: &'a mut Cube) -> Self {
+        // 种子不超过 2^53，前端的 number 可以原样传回
+        let seed = rand::thread_rng().gen_range(0..1 << 53);
+        Self::with_seed(cube, seed)
+    }
+
+    pub fn with_seed(cube: &'a mut Cube, seed: u64) -> Self {
         CubeShuffler {
             scrambler: CubeScrambler::new(cube),
-            rng: Some(rand::thread_rng()),
+            rng: StdRng::seed_from_u64(seed),
+            seed: Some(seed),
         }
     }
+}
+
+impl<'a, R: Rng> CubeShuffler<'a, R> {
+    pub fn with_rng(cube: &'a mut Cube, rng: R) -> Self {
+        CubeShuffler {
+            scrambler: CubeScrambler::new(cube),
+            rng,
+            seed: None,
+        }
+    }
+
+    /// The seed the moves are generated from, `None` for a caller's `Rng`
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
 
-    pub fn shuffle(&mut self, times: u32) {
+    /// Applies `times` random quarter turns and returns them.
+    pub fn shuffle(&mut self, times: u32) -> Algorithm {
+        let mut moves = Algorithm::new();
         for _ in 0..times {
             // 随机选择一个面
             let face = self.random_face();
@@ -622,17 +112,60 @@ impl<'a> CubeShuffler<'a> {
             let twist_direction = self.random_twist_direction();
 
             self.scrambler.scramble(face, twist_direction);
+            moves.push(Move::face(face, twist_direction));
+        }
+        moves
+    }
+
+    /// Random-move scramble in the competition style: no face is turned
+    /// twice in a row, no axis three times in a row (`L R L`), and half
+    /// turns are as likely as quarter turns.
+    pub fn scramble(&mut self, length: u32) -> Algorithm {
+        let mut moves = Algorithm::new();
+        let mut picker = FacePicker::default();
+        let faces = Face::values();
+        for _ in 0..length {
+            let face = picker.next(&mut self.rng, &faces);
+            let turn = Turn::from_quarter_turns(self.rng.gen_range(1..4)).unwrap();
+            let mv = Move::new(MoveKind::Face(face), turn);
+            self.scrambler.apply(mv);
+            moves.push(mv);
         }
+        moves
+    }
+
+    /// Random-state scramble: draws a uniformly random state and returns a
+    /// short sequence (at most 22 moves) reaching it from solved, found by
+    /// the two-phase solver. Applied to a solved cube it gives that state.
+    pub fn random_state_scramble(&mut self) -> Algorithm {
+        let state = random_state(&mut self.rng);
+        self.scramble_to(&state)
+    }
+
+    /// Random-state scramble for practising the stages after `solved`: the
+    /// state is random except that `solved` and the stages of its method
+    /// before it are left solved, e.g. [`SolveTarget::F2l`] gives a random
+    /// OLL and PLL case and [`SolveTarget::Oll`] only a PLL one.
+    pub fn subset_scramble(&mut self, solved: SolveTarget) -> Algorithm {
+        let state = random_state_after(&mut self.rng, solved);
+        self.scramble_to(&state)
+    }
+
+    fn scramble_to(&mut self, state: &CubieCube) -> Algorithm {
+        let solution = TwoPhaseSolver::default().solve(state);
+        let scramble = Algorithm::from(solution).inverse();
+        self.scrambler.apply_algorithm(&scramble);
+        scramble
     }
 
     fn random_face(&mut self) -> Face {
-        let random_index = self.rng.as_mut().unwrap().gen_range(0..6);
+        let random_index = self.rng.gen_range(0..6);
 
-        Face::from(random_index as u8)
+        Face::try_from(random_index as u8).unwrap()
     }
 
     fn random_twist_direction(&mut self) -> TwistDirection {
-        let clockwise = self.rng.as_mut().unwrap().gen_bool(0.5);
+        let clockwise = self.rng.gen_bool(0.5);
         if clockwise {
             TwistDirection::Clockwise
         } else {
@@ -640,3 +173,95 @@ impl<'a> CubeShuffler<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_scramble() {
+        let mut first = Cube::new();
+        let moves = CubeShuffler::with_seed(&mut first, 42).shuffle(25);
+        let mut second = Cube::new();
+        let again = CubeShuffler::with_seed(&mut second, 42).shuffle(25);
+        assert_eq!(moves, again);
+        assert_eq!(first, second);
+        assert_eq!(moves.len(), 25);
+    }
+
+    #[test]
+    fn returns_the_applied_moves() {
+        let mut cube = Cube::new();
+        let mut shuffler = CubeShuffler::new(&mut cube);
+        let seed = shuffler.seed().unwrap();
+        let moves = shuffler.shuffle(20);
+        let mut replayed = Cube::new();
+        replayed.apply_algorithm(&moves);
+        assert_eq!(cube, replayed);
+
+        let mut from_seed = Cube::new();
+        CubeShuffler::with_seed(&mut from_seed, seed).shuffle(20);
+        assert_eq!(cube, from_seed);
+    }
+
+    #[test]
+    fn scrambles_have_no_redundant_moves() {
+        let mut cube = Cube::new();
+        let moves = CubeShuffler::with_seed(&mut cube, 7)
+            .scramble(SCRAMBLE_LENGTH)
+            .moves();
+        assert_eq!(moves.len(), SCRAMBLE_LENGTH as usize);
+
+        let axis = |mv: &Move| match mv.kind {
+            MoveKind::Face(face) => face.ordinal() / 2,
+            _ => panic!("Only face turns expected"),
+        };
+        for pair in moves.windows(2) {
+            assert_ne!(pair[0].kind, pair[1].kind);
+        }
+        for triple in moves.windows(3) {
+            assert!(
+                !(axis(&triple[0]) == axis(&triple[1]) && axis(&triple[1]) == axis(&triple[2]))
+            );
+        }
+
+        let mut replayed = Cube::new();
+        replayed.apply_algorithm(&Algorithm::from(moves));
+        assert_eq!(cube, replayed);
+    }
+
+    #[test]
+    fn random_state_scrambles_reach_the_drawn_state() {
+        let mut cube = Cube::new();
+        let scramble = CubeShuffler::with_seed(&mut cube, 2024).random_state_scramble();
+        assert!(scramble.len() <= 22);
+        let expected = random_state(&mut StdRng::seed_from_u64(2024));
+        assert_eq!(cube, expected.to_cube());
+
+        let mut again = Cube::new();
+        let replayed = CubeShuffler::with_seed(&mut again, 2024).random_state_scramble();
+        assert_eq!(scramble, replayed);
+    }
+
+    #[test]
+    fn subset_scrambles_leave_earlier_stages_solved() {
+        for solved in [
+            SolveTarget::BottomCross,
+            SolveTarget::MiddleEdge,
+            SolveTarget::F2l,
+            SolveTarget::Oll,
+        ] {
+            let mut cube = Cube::new();
+            CubeShuffler::with_seed(&mut cube, 7).subset_scramble(solved);
+            assert!(solved.is_reached(&cube), "{:?}", solved);
+        }
+    }
+
+    #[test]
+    fn accepts_any_rng() {
+        let mut cube = Cube::new();
+        let mut shuffler = CubeShuffler::with_rng(&mut cube, rand::thread_rng());
+        assert_eq!(shuffler.seed(), None);
+        assert_eq!(shuffler.shuffle(5).len(), 5);
+    }
+}
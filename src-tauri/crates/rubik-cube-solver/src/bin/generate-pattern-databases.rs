@@ -0,0 +1,26 @@
+//! Builds the optimal solver's pattern databases into the given directory
+//! (default `pattern-databases`), skipping any that already exist.
+
+use std::path::PathBuf;
+use std::time::Instant;
+
+use rubik_cube_solver::solver::OptimalSolver;
+
+fn main() {
+    let dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("pattern-databases"));
+    let start = Instant::now();
+    match OptimalSolver::from_dir(&dir) {
+        Ok(_) => println!(
+            "pattern databases ready in {:?} ({:?})",
+            dir,
+            start.elapsed()
+        ),
+        Err(e) => {
+            eprintln!("failed to build pattern databases in {:?}: {}", dir, e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::OnceLock;
+
+use rubik_cube_core::cube::{notation::Move, Cube};
+
+use super::{cross_solved, cubies, CROSS_EDGES};
+use crate::solver::face_turns::{move_cubies, to_move, N_MOVES};
+use crate::solver::{Pattern, PatternDatabase, SolveError, SolveTarget, Solver, SolverEnum};
+
+/// Optimal cross: the database holds the exact distance of every placement
+/// of the four cross edges, so walking downhill gives a shortest solution.
+pub struct CrossSolver {}
+
+fn database() -> &'static PatternDatabase {
+    static DATABASE: OnceLock<PatternDatabase> = OnceLock::new();
+    DATABASE.get_or_init(|| PatternDatabase::generate(Pattern::new(&[], &CROSS_EDGES)))
+}
+
+impl Solver for CrossSolver {
+    fn target(&self) -> SolveTarget {
+        SolveTarget::Cross
+    }
+
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
+        let database = database();
+        let mut cc = cubies(cube);
+        let mut steps = vec![];
+        while !cross_solved(&cc) {
+            let distance = database.distance(&cc);
+            let m = (0..N_MOVES)
+                .find(|&m| database.distance(&cc.multiply(&move_cubies()[m])) < distance)
+                .unwrap();
+            cc = cc.multiply(&move_cubies()[m]);
+            cube.apply_move(to_move(m));
+            steps.push(to_move(m));
+        }
+        Ok(steps)
+    }
+
+    fn is_target_solved(&self, cube: &Cube) -> bool {
+        cross_solved(&cubies(cube))
+    }
+
+    fn next_solver(&self) -> Option<SolverEnum> {
+        Some(SolverEnum::F2l(super::F2lSolver::default()))
+    }
+}
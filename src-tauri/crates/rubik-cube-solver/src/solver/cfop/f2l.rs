@@ -0,0 +1,243 @@
+use rubik_cube_core::cube::{notation::Move, Cube};
+
+use super::{after, auf, cross_solved, cubies, parse, rotate_y, slot_solved, SLOT_NAMES};
+use crate::solver::{SolveError, SolveTarget, Solver, SolverEnum};
+
+/// Shortest <R, U, F> solutions of the 41 cases of a pair for the FR slot,
+/// each with the corner and edge up to a U turn in the listed position.
+/// Other slots use the same algorithms rotated by y.
+const CASES: [&str; 41] = [
+    // URF 扭转 0，UR 翻转 0
+    "R U2 R' U' R U R'",
+    // URF 扭转 0，UR 翻转 1
+    "U2 F2 U2 F U F' U F2",
+    // URF 扭转 0，UF 翻转 0
+    "U2 R2 U2 R' U' R U' R2",
+    // URF 扭转 0，UF 翻转 1
+    "F' U2 F U F' U' F",
+    // URF 扭转 0，UL 翻转 0
+    "U2 R U R2 F R F'",
+    // URF 扭转 0，UL 翻转 1
+    "U' F' U2 F2 R' F' R",
+    // URF 扭转 0，UB 翻转 0
+    "U R U2 R2 F R F'",
+    // URF 扭转 0，UB 翻转 1
+    "U2 R U R' F' U' F",
+    // URF 扭转 0，FR 翻转 0
+    "R2 U R2 U R2 U2 R2",
+    // URF 扭转 0，FR 翻转 1
+    "R U' R' F' U2 F",
+    // URF 扭转 1，UR 翻转 0
+    "U2 R2 U R' U R U2 R2",
+    // URF 扭转 1，UR 翻转 1
+    "R U' R' U2 F' U' F",
+    // URF 扭转 1，UF 翻转 0
+    "R' U2 R2 U R2 U R",
+    // URF 扭转 1，UF 翻转 1
+    "U2 F' U2 F",
+    // URF 扭转 1，UL 翻转 0
+    "U F' U F U' R U R'",
+    // URF 扭转 1，UL 翻转 1
+    "U F' U' F U F' U2 F",
+    // URF 扭转 1，UB 翻转 0
+    "R U R'",
+    // URF 扭转 1，UB 翻转 1
+    "R2 U2 F R2 F' U2 R2",
+    // URF 扭转 1，FR 翻转 0
+    "U R U R' U2 R U R'",
+    // URF 扭转 1，FR 翻转 1
+    "U F' U' F U' R U R'",
+    // URF 扭转 2，UR 翻转 0
+    "U R U' R'",
+    // URF 扭转 2，UR 翻转 1
+    "F U2 F2 U' F2 U' F'",
+    // URF 扭转 2，UF 翻转 0
+    "F' U F U2 R U R'",
+    // URF 扭转 2，UF 翻转 1
+    "U F' U F U' F' U' F",
+    // URF 扭转 2，UL 翻转 0
+    "F2 U2 R' F2 R U2 F2",
+    // URF 扭转 2，UL 翻转 1
+    "F' U' F",
+    // URF 扭转 2，UB 翻转 0
+    "U R2 U2 F R' F' U2 R2",
+    // URF 扭转 2，UB 翻转 1
+    "U R U R' U2 F' U' F",
+    // URF 扭转 2，FR 翻转 0
+    "U F' U2 F U' F' U' F",
+    // URF 扭转 2，FR 翻转 1
+    "U2 R U R' U2 F' U2 F",
+    // DFR 扭转 0，UR 翻转 0
+    "U F' U2 F U2 R U R'",
+    // DFR 扭转 0，UR 翻转 1
+    "R U2 R' U2 F' U' F",
+    // DFR 扭转 0，FR 翻转 1
+    "R U' R U2 F R2 F' U2 R2",
+    // DFR 扭转 1，UR 翻转 0
+    "R U' R2 F R F'",
+    // DFR 扭转 1，UR 翻转 1
+    "U2 R' F R F2 U' F",
+    // DFR 扭转 1，FR 翻转 0
+    "R U2 R U2 F R F' U2 R2",
+    // DFR 扭转 1，FR 翻转 1
+    "R F U R U' R' F' U' R'",
+    // DFR 扭转 2，UR 翻转 0
+    "U2 F' U F R U R'",
+    // DFR 扭转 2，UR 翻转 1
+    "U R U2 R' F' U2 F",
+    // DFR 扭转 2，FR 翻转 0
+    "R U2 R U R' U R U2 R2",
+    // DFR 扭转 2，FR 翻转 1
+    "R U F R U R' U' F' R'",
+];
+
+/// Takes a pair out of a wrong slot so it can be solved from the U layer.
+const EXTRACT: &str = "R U R'";
+
+/// Pairs are solved one at a time, always picking the shortest insertion
+/// available among the unsolved slots.
+#[derive(Default)]
+pub struct F2lSolver {
+    labels: Vec<String>,
+}
+
+fn extraction(slot: usize) -> Vec<Move> {
+    parse(EXTRACT)
+        .into_iter()
+        .map(|mv| rotate_y(mv, slot))
+        .collect()
+}
+
+impl F2lSolver {
+    /// The shortest insertion among `slots`, with its case number and slot
+    fn best_insertion(&self, cube: &Cube, slots: &[usize]) -> Option<(Vec<Move>, usize, usize)> {
+        slots
+            .iter()
+            .filter_map(|&slot| self.insertion(cube, slot).map(|(m, case)| (m, case, slot)))
+            .min_by_key(|(moves, _, _)| moves.len())
+    }
+
+    /// The shortest way to solve `slot` directly, with its case number
+    fn insertion(&self, cube: &Cube, slot: usize) -> Option<(Vec<Move>, usize)> {
+        let mut best: Option<(Vec<Move>, usize)> = None;
+        for (case, alg) in CASES.iter().enumerate() {
+            let alg: Vec<Move> = parse(alg)
+                .into_iter()
+                .map(|mv| rotate_y(mv, slot))
+                .collect();
+            for quarter_turns in 0..4 {
+                let mut moves = auf(quarter_turns);
+                moves.extend(alg.iter().copied());
+                if best.as_ref().is_some_and(|(b, _)| b.len() <= moves.len()) {
+                    continue;
+                }
+                if keeps_progress(cube, &after(cube, &moves), slot) {
+                    best = Some((moves, case + 1));
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Whether `next` solves `slot` without breaking the cross or solved slots.
+fn keeps_progress(cube: &Cube, next: &Cube, slot: usize) -> bool {
+    let (before, after) = (cubies(cube), cubies(next));
+    cross_solved(&after)
+        && slot_solved(&after, slot)
+        && (0..4).all(|s| !slot_solved(&before, s) || slot_solved(&after, s))
+}
+
+impl Solver for F2lSolver {
+    fn target(&self) -> SolveTarget {
+        SolveTarget::F2l
+    }
+
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
+        let mut steps = vec![];
+        let mut count = 0;
+
+        while !self.is_target_solved(cube) {
+            if count > 8 {
+                return Err(SolveError::stuck(self.target(), cube, &steps));
+            }
+            count += 1;
+
+            let cc = cubies(cube);
+            let unsolved: Vec<usize> = (0..4).filter(|&s| !slot_solved(&cc, s)).collect();
+            let moves = match self.best_insertion(cube, &unsolved) {
+                Some((moves, case, slot)) => {
+                    self.labels.push(format!("{} #{}", SLOT_NAMES[slot], case));
+                    moves
+                }
+                None => {
+                    // 每对块都卡在别的槽里，取出能让下一次插入最短的那个槽
+                    unsolved
+                        .iter()
+                        .map(|&slot| extraction(slot))
+                        .min_by_key(|moves| {
+                            self.best_insertion(&after(cube, moves), &unsolved)
+                                .map_or(usize::MAX, |(m, _, _)| m.len())
+                        })
+                        .unwrap()
+                }
+            };
+            for &mv in &moves {
+                cube.apply_move(mv);
+            }
+            steps.extend(moves);
+        }
+
+        Ok(steps)
+    }
+
+    fn is_target_solved(&self, cube: &Cube) -> bool {
+        let cc = cubies(cube);
+        cross_solved(&cc) && (0..4).all(|slot| slot_solved(&cc, slot))
+    }
+
+    fn next_solver(&self) -> Option<SolverEnum> {
+        Some(SolverEnum::Oll(super::OllSolver::default()))
+    }
+
+    fn label(&self) -> Option<String> {
+        if self.labels.is_empty() {
+            return None;
+        }
+        Some(format!("F2L {}", self.labels.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_case_solves_the_front_right_slot() {
+        // 每个公式的逆在 FR 槽之外只动顶层，正好构造出对应情形
+        for (case, alg) in CASES.iter().enumerate() {
+            let inverse: Vec<Move> = parse(alg).iter().rev().map(|mv| mv.inverse()).collect();
+            let cube = after(&Cube::new(), &inverse);
+            let solver = F2lSolver::default();
+            let (moves, found) = solver.insertion(&cube, 0).unwrap();
+            assert!(moves.len() <= parse(alg).len(), "case {}", case + 1);
+            assert!(
+                solver.is_target_solved(&after(&cube, &moves)),
+                "case {} vs {}",
+                case + 1,
+                found
+            );
+        }
+    }
+
+    #[test]
+    fn solves_all_slots_after_the_cross() {
+        let mut cube = after(&Cube::new(), &parse("R U F' U2 L' B U' R2 B' L U2 F R'"));
+        let mut cross = super::super::CrossSolver {};
+        cross.solve_target(&mut cube).unwrap();
+        let mut solver = F2lSolver::default();
+        solver.solve_target(&mut cube).unwrap();
+        assert!(solver.is_target_solved(&cube));
+        assert!(solver.label().unwrap().starts_with("F2L "));
+    }
+}
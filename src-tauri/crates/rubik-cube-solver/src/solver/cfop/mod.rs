@@ -0,0 +1,141 @@
+//! CFOP: cross, first two layers, orientation and permutation of the last
+//! layer
+//!
+//! The cross is found by search; the other stages try the algorithms of
+//! their case table until one works, so recognition is by simulation rather
+//! than by sticker patterns. The cross is built on Down, the last layer is Up.
+
+mod cross;
+mod f2l;
+mod oll;
+mod pll;
+
+pub use cross::CrossSolver;
+pub use f2l::F2lSolver;
+pub use oll::OllSolver;
+pub use pll::PllSolver;
+
+use rubik_cube_core::cube::{
+    cubie::{Corner, CubieCube, Edge},
+    face::Face,
+    notation::{Algorithm, Move, MoveKind, Turn},
+    Cube,
+};
+
+/// The F2L slots in the order a y rotation visits them, starting at FR
+const SLOTS: [(Corner, Edge); 4] = [
+    (Corner::DFR, Edge::FR),
+    (Corner::DRB, Edge::BR),
+    (Corner::DBL, Edge::BL),
+    (Corner::DLF, Edge::FL),
+];
+
+const SLOT_NAMES: [&str; 4] = ["FR", "BR", "BL", "FL"];
+
+const CROSS_EDGES: [Edge; 4] = [Edge::DR, Edge::DF, Edge::DL, Edge::DB];
+
+fn cubies(cube: &Cube) -> CubieCube {
+    CubieCube::try_from(cube).expect("Invalid cube state")
+}
+
+fn corner_solved(cc: &CubieCube, corner: Corner) -> bool {
+    cc.cp[corner.index()] == corner && cc.co[corner.index()] == 0
+}
+
+fn edge_solved(cc: &CubieCube, edge: Edge) -> bool {
+    cc.ep[edge.index()] == edge && cc.eo[edge.index()] == 0
+}
+
+fn cross_solved(cc: &CubieCube) -> bool {
+    CROSS_EDGES.iter().all(|&e| edge_solved(cc, e))
+}
+
+fn slot_solved(cc: &CubieCube, slot: usize) -> bool {
+    let (corner, edge) = SLOTS[slot];
+    corner_solved(cc, corner) && edge_solved(cc, edge)
+}
+
+fn f2l_solved(cc: &CubieCube) -> bool {
+    cross_solved(cc) && (0..4).all(|slot| slot_solved(cc, slot))
+}
+
+/// With the first two layers solved, the Up face is a single colour.
+fn last_layer_oriented(cc: &CubieCube) -> bool {
+    f2l_solved(cc) && cc.co[..4].iter().all(|&t| t == 0) && cc.eo[..4].iter().all(|&f| f == 0)
+}
+
+fn parse(alg: &str) -> Vec<Move> {
+    alg.parse::<Algorithm>().unwrap().moves()
+}
+
+fn after(cube: &Cube, moves: &[Move]) -> Cube {
+    let mut cube = cube.clone();
+    for &mv in moves {
+        cube.apply_move(mv);
+    }
+    cube
+}
+
+/// `U`, `U2` or `U'`, nothing for `quarter_turns == 0`
+fn auf(quarter_turns: u32) -> Vec<Move> {
+    Turn::from_quarter_turns(quarter_turns)
+        .map(|turn| Move::new(MoveKind::Face(Face::Up), turn))
+        .into_iter()
+        .collect()
+}
+
+/// The move that does to the slot `k` steps along [`SLOTS`] what `mv` does
+/// to the FR slot, i.e. `mv` conjugated by `y^k`. Only face turns.
+fn rotate_y(mv: Move, k: usize) -> Move {
+    const AROUND: [Face; 4] = [Face::Front, Face::Right, Face::Back, Face::Left];
+    match mv.kind {
+        MoveKind::Face(face) => match AROUND.iter().position(|&f| f == face) {
+            Some(i) => Move::new(MoveKind::Face(AROUND[(i + k) % 4]), mv.turn),
+            None => mv,
+        },
+        _ => panic!("Only face turns can be rotated: {}", mv),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{execute_with_method, SolveMethod, SolveTarget};
+
+    #[test]
+    fn rotated_algorithms_act_on_the_next_slot() {
+        // R U R' 把 FR 槽的块取出，转一次 y 后应作用于 BR 槽
+        let extract = parse("R U R'");
+        for k in 0..4 {
+            let moves: Vec<Move> = extract.iter().map(|&mv| rotate_y(mv, k)).collect();
+            let cc = cubies(&after(&Cube::new(), &moves));
+            assert!(cross_solved(&cc));
+            for slot in 0..4 {
+                assert_eq!(slot_solved(&cc, slot), slot != k, "{} {}", k, slot);
+            }
+        }
+    }
+
+    #[test]
+    fn labels_every_stage() {
+        let mut cube = after(
+            &Cube::new(),
+            &parse("D2 F' L2 U B2 R' F D' L B2 U2 R2 F2 D L2 B R' U2 F'"),
+        );
+        let solution = execute_with_method(&mut cube, SolveTarget::Pll, SolveMethod::Cfop).unwrap();
+        let stages = solution.stages;
+        assert!(cube.is_solved());
+        let targets: Vec<SolveTarget> = stages.iter().map(|s| s.target).collect();
+        assert_eq!(
+            targets,
+            [
+                SolveTarget::Cross,
+                SolveTarget::F2l,
+                SolveTarget::Oll,
+                SolveTarget::Pll
+            ]
+        );
+        assert!(stages[2].label.as_ref().unwrap().starts_with("OLL "));
+        assert!(stages[3].label.as_ref().unwrap().starts_with("PLL "));
+    }
+}
@@ -0,0 +1,166 @@
+use rubik_cube_core::cube::{notation::Move, Cube};
+
+use super::{after, auf, cubies, last_layer_oriented, parse};
+use crate::solver::{SolveError, SolveTarget, Solver, SolverEnum};
+
+/// The 57 OLL cases by their usual number, with common names where they
+/// have one
+const CASES: [(u8, Option<&str>, &str); 57] = [
+    (1, None, "R U2 R2 F R F' U2 R' F R F'"),
+    (2, None, "F R U R' U' F' f R U R' U' f'"),
+    (3, None, "f R U R' U' f' U' F R U R' U' F'"),
+    (4, None, "f R U R' U' f' U F R U R' U' F'"),
+    (5, None, "r' U2 R U R' U r"),
+    (6, None, "r U2 R' U' R U' r'"),
+    (7, None, "r U R' U R U2 r'"),
+    (8, None, "r' U' R U' R' U2 r"),
+    (9, None, "R U R' U' R' F R2 U R' U' F'"),
+    (10, None, "R U R' U R' F R F' R U2 R'"),
+    (11, None, "r U R' U R' F R F' R U2 r'"),
+    (12, None, "M' R' U' R U' R' U2 R U' R r'"),
+    (13, None, "F U R U' R2 F' R U R U' R'"),
+    (14, None, "R' F R U R' F' R F U' F'"),
+    (15, None, "r' U' r R' U' R U r' U r"),
+    (16, None, "r U r' R U R' U' r U' r'"),
+    (17, None, "R U R' U R' F R F' U2 R' F R F'"),
+    (18, None, "r U R' U R U2 r2 U' R U' R' U2 r"),
+    (19, None, "r' R U R U R' U' M' R' F R F'"),
+    (20, None, "r U R' U' M2 U R U' R' U' M'"),
+    (21, Some("H"), "R U2 R' U' R U R' U' R U' R'"),
+    (22, Some("Pi"), "R U2 R2 U' R2 U' R2 U2 R"),
+    (23, Some("Headlights"), "R2 D' R U2 R' D R U2 R"),
+    (24, Some("Chameleon"), "r U R' U' r' F R F'"),
+    (25, Some("Bowtie"), "F' r U R' U' r' F R"),
+    (26, Some("Antisune"), "R U2 R' U' R U' R'"),
+    (27, Some("Sune"), "R U R' U R U2 R'"),
+    (28, None, "r U R' U' M U R U' R'"),
+    (29, None, "R U R' U' R U' R' F' U' F R U R'"),
+    (30, None, "F R' F R2 U' R' U' R U R' F2"),
+    (31, None, "R' U' F U R U' R' F' R"),
+    (32, None, "L U F' U' L' U L F L'"),
+    (33, None, "R U R' U' R' F R F'"),
+    (34, None, "R U R2 U' R' F R U R U' F'"),
+    (35, None, "R U2 R2 F R F' R U2 R'"),
+    (36, None, "L' U' L U' L' U L U L F' L' F"),
+    (37, None, "F R' F' R U R U' R'"),
+    (38, None, "R U R' U R U' R' U' R' F R F'"),
+    (39, None, "L F' L' U' L U F U' L'"),
+    (40, None, "R' F R U R' U' F' U R"),
+    (41, None, "R U R' U R U2 R' F R U R' U' F'"),
+    (42, None, "R' U' R U' R' U2 R F R U R' U' F'"),
+    (43, None, "F' U' L' U L F"),
+    (44, None, "F U R U' R' F'"),
+    (45, None, "F R U R' U' F'"),
+    (46, None, "R' U' R' F R F' U R"),
+    (47, None, "R' U' R' F R F' R' F R F' U R"),
+    (48, None, "F R U R' U' R U R' U' F'"),
+    (49, None, "r U' r2 U r2 U r2 U' r"),
+    (50, None, "r' U r2 U' r2 U' r2 U r'"),
+    (51, None, "F U R U' R' U R U' R' F'"),
+    (52, None, "R U R' U R U' B U' B' R'"),
+    (53, None, "r' U2 R U R' U' R U R' U r"),
+    (54, None, "r U2 R' U' R U R' U' R U' r'"),
+    (55, None, "R' F R U R U' R2 F' R2 U' R' U R U R'"),
+    (56, None, "r' U' r U' R' U R U' R' U R r' U r"),
+    (57, None, "R U R' U' M' U R U' r'"),
+];
+
+#[derive(Default)]
+pub struct OllSolver {
+    label: Option<String>,
+}
+
+fn case_label(number: u8, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("OLL {} / {}", number, name),
+        None => format!("OLL {}", number),
+    }
+}
+
+impl OllSolver {
+    /// The case of `cube` and the moves that orient its last layer
+    fn recognize(cube: &Cube) -> Option<(Vec<Move>, String)> {
+        for &(number, name, alg) in CASES.iter() {
+            let alg = parse(alg);
+            for quarter_turns in 0..4 {
+                let mut moves = auf(quarter_turns);
+                moves.extend(alg.iter().copied());
+                if last_layer_oriented(&cubies(&after(cube, &moves))) {
+                    return Some((moves, case_label(number, name)));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Solver for OllSolver {
+    fn target(&self) -> SolveTarget {
+        SolveTarget::Oll
+    }
+
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
+        let (moves, label) =
+            Self::recognize(cube).ok_or_else(|| SolveError::stuck(self.target(), cube, &[]))?;
+        for &mv in &moves {
+            cube.apply_move(mv);
+        }
+        self.label = Some(label);
+        Ok(moves)
+    }
+
+    fn is_target_solved(&self, cube: &Cube) -> bool {
+        last_layer_oriented(&cubies(cube))
+    }
+
+    fn next_solver(&self) -> Option<SolverEnum> {
+        Some(SolverEnum::Pll(super::PllSolver::default()))
+    }
+
+    fn label(&self) -> Option<String> {
+        self.label.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rubik_cube_core::cube::cubie::CubieCube;
+
+    use super::*;
+    use crate::solver::cfop::f2l_solved;
+
+    #[test]
+    fn every_algorithm_is_an_orientation() {
+        for &(number, _, alg) in CASES.iter() {
+            // 逆公式从复原状态构造出这个情形
+            let inverse: Vec<Move> = parse(alg).iter().rev().map(|mv| mv.inverse()).collect();
+            let cube = after(&Cube::new(), &inverse);
+            assert!(f2l_solved(&cubies(&cube)), "OLL {} breaks F2L", number);
+            assert!(!last_layer_oriented(&cubies(&cube)), "OLL {}", number);
+        }
+    }
+
+    #[test]
+    fn recognizes_every_orientation() {
+        let mut labels = HashSet::new();
+        for twists in 0..27u32 {
+            for flips in 0..8u32 {
+                let mut cc = CubieCube::solved();
+                for i in 0..3 {
+                    cc.co[i] = (twists / 3u32.pow(i as u32) % 3) as u8;
+                    cc.eo[i] = (flips >> i & 1) as u8;
+                }
+                cc.co[3] = (6 - cc.co[..3].iter().sum::<u8>()) % 3;
+                cc.eo[3] = cc.eo[..3].iter().sum::<u8>() % 2;
+                if last_layer_oriented(&cc) {
+                    continue;
+                }
+                let (_, label) = OllSolver::recognize(&cc.to_cube()).unwrap();
+                labels.insert(label);
+            }
+        }
+        assert_eq!(labels.len(), 57);
+    }
+}
@@ -0,0 +1,155 @@
+use rubik_cube_core::cube::{notation::Move, Cube};
+
+use super::{after, auf, parse};
+use crate::solver::{SolveError, SolveTarget, Solver, SolverEnum};
+
+/// The 21 PLL cases
+const CASES: [(&str, &str); 21] = [
+    ("Aa", "x R' U R' D2 R U' R' D2 R2 x'"),
+    ("Ab", "x R2 D2 R U R' D2 R U' R x'"),
+    ("E", "x' R U' R' D R U R' D' R U R' D R U' R' D' x"),
+    ("F", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    ("Ga", "R2 U R' U R' U' R U' R2 U' D R' U R D'"),
+    ("Gb", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    ("Gc", "R2 U' R U' R U R' U R2 U D' R U' R' D"),
+    ("Gd", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    ("H", "M2 U M2 U2 M2 U M2"),
+    ("Ja", "x R2 F R F' R U2 r' U r U2 x'"),
+    ("Jb", "R U R' F' R U R' U' R' F R2 U' R'"),
+    (
+        "Na",
+        "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+    ),
+    ("Nb", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    ("Ra", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    ("Rb", "R2 F R U R U' R' F' R U2 R' U2 R"),
+    ("T", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    ("Ua", "M2 U M U2 M' U M2"),
+    ("Ub", "M2 U' M U2 M' U' M2"),
+    ("V", "R' U R' U' R D' R' D R' U D' R2 U' R2 D R2"),
+    ("Y", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    ("Z", "M' U M2 U M2 U M' U2 M2"),
+];
+
+#[derive(Default)]
+pub struct PllSolver {
+    label: Option<String>,
+}
+
+impl PllSolver {
+    /// The case of `cube` and the moves, including the final U turn, that
+    /// solve it. Only an adjusting U turn is labelled as a skip.
+    fn recognize(cube: &Cube) -> Option<(Vec<Move>, String)> {
+        let finish = |moves: Vec<Move>| -> Option<Vec<Move>> {
+            let cube = after(cube, &moves);
+            (0..4).find_map(|quarter_turns| {
+                let adjust = auf(quarter_turns);
+                after(&cube, &adjust).is_solved().then(|| {
+                    let mut moves = moves.clone();
+                    moves.extend(adjust);
+                    moves
+                })
+            })
+        };
+
+        if let Some(moves) = finish(vec![]) {
+            return Some((moves, "PLL skip".to_string()));
+        }
+        for &(name, alg) in CASES.iter() {
+            let alg = parse(alg);
+            for quarter_turns in 0..4 {
+                let mut moves = auf(quarter_turns);
+                moves.extend(alg.iter().copied());
+                if let Some(moves) = finish(moves) {
+                    return Some((moves, format!("PLL {}-perm", name)));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Solver for PllSolver {
+    fn target(&self) -> SolveTarget {
+        SolveTarget::Pll
+    }
+
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
+        let (moves, label) =
+            Self::recognize(cube).ok_or_else(|| SolveError::stuck(self.target(), cube, &[]))?;
+        for &mv in &moves {
+            cube.apply_move(mv);
+        }
+        self.label = Some(label);
+        Ok(moves)
+    }
+
+    fn is_target_solved(&self, cube: &Cube) -> bool {
+        cube.is_solved()
+    }
+
+    fn next_solver(&self) -> Option<SolverEnum> {
+        None
+    }
+
+    fn label(&self) -> Option<String> {
+        self.label.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rubik_cube_core::cube::cubie::{Corner, CubieCube, Edge};
+
+    use super::*;
+    use crate::solver::cfop::{cubies, last_layer_oriented};
+
+    fn permutations(items: [usize; 4]) -> Vec<[usize; 4]> {
+        let mut result = vec![];
+        for a in 0..4 {
+            for b in 0..4 {
+                for c in 0..4 {
+                    for d in 0..4 {
+                        let p = [a, b, c, d];
+                        if (0..4).all(|i| p.contains(&i)) {
+                            result.push(p.map(|i| items[i]));
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn every_algorithm_only_permutes_the_last_layer() {
+        for &(name, alg) in CASES.iter() {
+            let inverse: Vec<Move> = parse(alg).iter().rev().map(|mv| mv.inverse()).collect();
+            let cube = after(&Cube::new(), &inverse);
+            assert!(last_layer_oriented(&cubies(&cube)), "{} is not a PLL", name);
+        }
+    }
+
+    #[test]
+    fn recognizes_every_permutation() {
+        let mut labels = HashSet::new();
+        for corners in permutations([0, 1, 2, 3]) {
+            for edges in permutations([0, 1, 2, 3]) {
+                let mut cc = CubieCube::solved();
+                for i in 0..4 {
+                    cc.cp[i] = Corner::from_index(corners[i]);
+                    cc.ep[i] = Edge::from_index(edges[i]);
+                }
+                if cc.verify().is_err() {
+                    continue;
+                }
+                let (moves, label) = PllSolver::recognize(&cc.to_cube()).unwrap();
+                assert!(after(&cc.to_cube(), &moves).is_solved());
+                labels.insert(label);
+            }
+        }
+        assert_eq!(labels.len(), 22);
+    }
+}
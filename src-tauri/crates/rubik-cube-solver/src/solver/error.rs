@@ -0,0 +1,151 @@
+//! Errors reported when a cube cannot be solved
+
+use std::fmt;
+
+use rubik_cube_core::cube::{
+    error::CubeError,
+    notation::{Algorithm, Move},
+    Cube,
+};
+
+use super::SolveTarget;
+
+#[derive(Debug, Clone)]
+pub enum SolveError {
+    /// The cube is not a state reachable by turning
+    InvalidCube(CubeError),
+    /// A solver did not reach its target within its iteration limit
+    Stuck {
+        stage: SolveTarget,
+        state: Cube,
+        /// Everything applied since the start of the solve
+        moves: Algorithm,
+    },
+    /// A stage found the target of the stage before it undone
+    PreviousUnsolved {
+        stage: SolveTarget,
+        previous: SolveTarget,
+        state: Cube,
+        moves: Algorithm,
+    },
+}
+
+impl SolveError {
+    pub(crate) fn stuck(stage: SolveTarget, cube: &Cube, steps: &[Move]) -> SolveError {
+        SolveError::Stuck {
+            stage,
+            state: cube.clone(),
+            moves: Algorithm::from(steps.to_vec()),
+        }
+    }
+
+    /// The stage the solve failed in, `None` before any stage ran
+    pub fn stage(&self) -> Option<SolveTarget> {
+        match self {
+            SolveError::InvalidCube(_) => None,
+            SolveError::Stuck { stage, .. } | SolveError::PreviousUnsolved { stage, .. } => {
+                Some(*stage)
+            }
+        }
+    }
+
+    pub fn state(&self) -> Option<&Cube> {
+        match self {
+            SolveError::InvalidCube(_) => None,
+            SolveError::Stuck { state, .. } | SolveError::PreviousUnsolved { state, .. } => {
+                Some(state)
+            }
+        }
+    }
+
+    pub fn moves(&self) -> Option<&Algorithm> {
+        match self {
+            SolveError::InvalidCube(_) => None,
+            SolveError::Stuck { moves, .. } | SolveError::PreviousUnsolved { moves, .. } => {
+                Some(moves)
+            }
+        }
+    }
+
+    /// Solvers only know their own moves; puts the earlier stages' in front.
+    pub(crate) fn after(mut self, earlier: Algorithm) -> SolveError {
+        if let SolveError::Stuck { moves, .. } | SolveError::PreviousUnsolved { moves, .. } =
+            &mut self
+        {
+            let mut all = earlier;
+            all.extend(std::mem::take(moves));
+            *moves = all;
+        }
+        self
+    }
+}
+
+impl From<CubeError> for SolveError {
+    fn from(error: CubeError) -> Self {
+        SolveError::InvalidCube(error)
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::InvalidCube(error) => write!(f, "invalid cube: {}", error),
+            SolveError::Stuck { stage, moves, .. } => write!(
+                f,
+                "solver for {:?} got stuck after {} moves",
+                stage,
+                moves.len()
+            ),
+            SolveError::PreviousUnsolved {
+                stage, previous, ..
+            } => write!(f, "{:?} was undone before solving {:?}", previous, stage),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+#[cfg(test)]
+mod tests {
+    use rubik_cube_core::cube::face::Face;
+
+    use super::*;
+    use crate::solver::{execute, Solver, TopCrossSolver};
+
+    /// 翻转 UF 棱块：颜色数量正常，但无法通过转动得到
+    fn flipped_edge() -> Cube {
+        let mut cube = Cube::new();
+        let (up, front) = (Face::Up.ordinal(), Face::Front.ordinal());
+        let sticker = cube.state[up][2][1];
+        cube.state[up][2][1] = cube.state[front][0][1];
+        cube.state[front][0][1] = sticker;
+        cube
+    }
+
+    #[test]
+    fn rejects_unreachable_states() {
+        let result = execute(&mut flipped_edge(), SolveTarget::TopEdge);
+        assert!(
+            matches!(
+                result,
+                Err(SolveError::InvalidCube(CubeError::EdgeFlip { .. }))
+            ),
+            "{:?}",
+            result.err()
+        );
+    }
+
+    #[test]
+    fn reports_where_a_solver_got_stuck() {
+        let mut cube = flipped_edge();
+        let error = TopCrossSolver {}.solve_target(&mut cube).unwrap_err();
+        assert_eq!(error.stage(), Some(SolveTarget::TopCross));
+        assert_eq!(error.state(), Some(&cube));
+        assert!(!error.moves().unwrap().is_empty());
+
+        let earlier: Algorithm = "R U".parse().unwrap();
+        let count = error.moves().unwrap().len();
+        let error = error.after(earlier);
+        assert_eq!(error.moves().unwrap().len(), count + 2);
+    }
+}
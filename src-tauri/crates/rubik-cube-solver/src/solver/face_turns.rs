@@ -0,0 +1,48 @@
+//! The 18 face turns at cubie level, shared by the search-based solvers
+
+use std::sync::OnceLock;
+
+use rubik_cube_core::cube::{
+    cubie::CubieCube,
+    face::{Face, TwistDirection},
+    notation::{Move, MoveKind, Turn},
+    Cube,
+};
+
+/// Face turns are numbered `face.ordinal() * 3 + quarter_turns - 1`.
+pub const N_MOVES: usize = 18;
+
+pub fn move_face(m: usize) -> usize {
+    m / 3
+}
+
+/// Whether `face` may follow `prev`: never the same face twice, and opposite
+/// faces (which commute) only in one order.
+pub fn may_follow(prev: Option<usize>, face: usize) -> bool {
+    match prev {
+        None => true,
+        Some(prev) => face != prev && !(face / 2 == prev / 2 && face < prev),
+    }
+}
+
+pub fn to_move(m: usize) -> Move {
+    let face = Face::values()[m / 3];
+    let turn = Turn::from_quarter_turns(m as u32 % 3 + 1).unwrap();
+    Move::new(MoveKind::Face(face), turn)
+}
+
+/// The cubie-level effect of each of the 18 face turns.
+pub fn move_cubies() -> &'static [CubieCube; N_MOVES] {
+    static MOVES: OnceLock<[CubieCube; N_MOVES]> = OnceLock::new();
+    MOVES.get_or_init(|| {
+        let mut moves = [CubieCube::solved(); N_MOVES];
+        for face in Face::values() {
+            let mut cube = Cube::new();
+            for k in 0..3 {
+                cube.apply_move(Move::face(face, TwistDirection::Clockwise));
+                moves[face.ordinal() * 3 + k] = CubieCube::try_from(&cube).unwrap();
+            }
+        }
+        moves
+    })
+}
@@ -7,7 +7,7 @@ impl Solver for BottomCornerSolver {
         SolveTarget::BottomCorner
     }
 
-    fn solve_target(&mut self, cube: &mut Cube) -> Vec<char> {
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
         let mut steps = vec![];
 
         'l: for _ in 0..4 {
@@ -25,7 +25,7 @@ impl Solver for BottomCornerSolver {
             }
         }
 
-        steps
+        Ok(steps)
     }
 
     fn is_target_solved(&self, cube: &Cube) -> bool {
@@ -66,7 +66,7 @@ impl BottomCornerSolver {
         face: Face,
         row: usize,
         col: usize,
-        steps: &mut Vec<char>,
+        steps: &mut Vec<Move>,
     ) -> bool {
         if Self::is_white_corner_need_solve(cube, face, row, col) {
             let mut row0 = row;
@@ -87,8 +87,8 @@ impl BottomCornerSolver {
         col: usize,
     ) -> bool {
         let colors = Self::get_corner_colors(cube, face, row, col);
-        let has_white =
-            colors.0 == Color::White || colors.1 == Color::White || colors.2 == Color::White;
+        let bottom = center_color(cube, Face::Down);
+        let has_white = colors.0 == bottom || colors.1 == bottom || colors.2 == bottom;
         if !has_white {
             return false;
         }
@@ -102,16 +102,16 @@ impl BottomCornerSolver {
     fn is_bottom_corner_done(cube: &Cube, row: usize, col: usize) -> bool {
         let colors = Self::get_corner_colors(cube, Face::Down, row, col);
 
-        // Check if white faces down
-        if colors.0 != Color::White {
+        // Check if the bottom colour faces down
+        if colors.0 != center_color(cube, Face::Down) {
             return false;
         }
 
         // Get center colors of adjacent faces
-        let front_color = Color::Blue;
-        let right_color = Color::Red;
-        let back_color = Color::Green;
-        let left_color = Color::Orange;
+        let front_color = center_color(cube, Face::Front);
+        let right_color = center_color(cube, Face::Right);
+        let back_color = center_color(cube, Face::Back);
+        let left_color = center_color(cube, Face::Left);
 
         // Check corner colors match with center colors
         match (row, col) {
@@ -123,7 +123,7 @@ impl BottomCornerSolver {
         }
     }
 
-    fn extract_corner(cube: &mut Cube, row: usize, col: usize, steps: &mut Vec<char>) {
+    fn extract_corner(cube: &mut Cube, row: usize, col: usize, steps: &mut Vec<Move>) {
         let right_face =
             Self::get_right_face_on_position(Face::Down, row, col);
 
@@ -133,7 +133,7 @@ impl BottomCornerSolver {
         rotate_and_record(cube, Face::Up, false, steps);
     }
 
-    fn align_top_corner(cube: &mut Cube, row: usize, col: usize, steps: &mut Vec<char>) {
+    fn align_top_corner(cube: &mut Cube, row: usize, col: usize, steps: &mut Vec<Move>) {
         let mut current_row = row;
         let mut current_col = col;
 
@@ -157,9 +157,9 @@ impl BottomCornerSolver {
         let colors = Self::get_corner_colors(cube, up_face, row, col);
         let faces = Self::get_side_faces(up_face, row, col);
         let center_colors = (
-            Color::White,
-            cube.get_block_color(faces.0.ordinal(), 1, 1),
-            cube.get_block_color(faces.1.ordinal(), 1, 1),
+            center_color(cube, Face::Down),
+            center_color(cube, faces.0),
+            center_color(cube, faces.1),
         );
         (colors.0 == center_colors.0 || colors.1 == center_colors.0 || colors.2 == center_colors.0)
             && (colors.0 == center_colors.1
@@ -170,7 +170,7 @@ impl BottomCornerSolver {
                 || colors.2 == center_colors.2)
     }
 
-    fn insert_corner(cube: &mut Cube, row: usize, col: usize, steps: &mut Vec<char>) {
+    fn insert_corner(cube: &mut Cube, row: usize, col: usize, steps: &mut Vec<Move>) {
         let right_face =
             Self::get_right_face_on_position(Face::Up, row, col);
         let mut at_bottom = false;
@@ -7,15 +7,15 @@ impl Solver for BottomCrossSolver {
         super::super::SolveTarget::BottomCross
     }
 
-    fn solve_target(&mut self, cube: &mut Cube) -> Vec<char> {
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
         let mut steps = vec![];
 
         for f in 2..=5 {
-            let face = Face::from(f as u8);
+            let face = Face::try_from(f as u8).unwrap();
             steps.extend(Self::solve_edge(cube, face));
         }
 
-        steps
+        Ok(steps)
     }
 
     fn is_target_solved(&self, cube: &Cube) -> bool {
@@ -47,7 +47,7 @@ impl Solver for BottomCrossSolver {
 }
 
 impl BottomCrossSolver {
-    fn solve_edge(cube: &mut Cube, face: Face) -> Vec<char> {
+    fn solve_edge(cube: &mut Cube, face: Face) -> Vec<Move> {
         let mut steps = vec![];
         if Self::is_edge_solved(cube, face) {
             return steps;
@@ -89,18 +89,19 @@ impl BottomCrossSolver {
         }
     }
 
-    fn find_edge_in_top(cube: &mut Cube, face: Face, steps: &mut Vec<char>) -> bool {
-        let face_color = face.color();
+    fn find_edge_in_top(cube: &mut Cube, face: Face, steps: &mut Vec<Move>) -> bool {
+        let face_color = center_color(cube, face);
+        let bottom = center_color(cube, Face::Down);
 
         let up_center = get_up_center(face);
         let top_face = Face::Up.ordinal();
 
-        if cube.get_block_color(top_face, up_center.0, up_center.1) == Color::White
+        if cube.get_block_color(top_face, up_center.0, up_center.1) == bottom
             && cube.get_block_color(face.ordinal(), 0, 1) == face_color
         {
             return true;
         }
-        if cube.get_block_color(face.ordinal(), 0, 1) == Color::White
+        if cube.get_block_color(face.ordinal(), 0, 1) == bottom
             && cube.get_block_color(top_face, up_center.0, up_center.1) == face_color
         {
             Self::swap_edge_on_top(cube, face, steps);
@@ -109,13 +110,13 @@ impl BottomCrossSolver {
 
         let right_side = get_right_side(face);
         let up_center = get_up_center(right_side);
-        if cube.get_block_color(top_face, up_center.0, up_center.1) == Color::White
+        if cube.get_block_color(top_face, up_center.0, up_center.1) == bottom
             && cube.get_block_color(right_side.ordinal(), 0, 1) == face_color
         {
             rotate_and_record(cube, Face::Up, true, steps);
             return true;
         }
-        if cube.get_block_color(right_side.ordinal(), 0, 1) == Color::White
+        if cube.get_block_color(right_side.ordinal(), 0, 1) == bottom
             && cube.get_block_color(top_face, up_center.0, up_center.1) == face_color
         {
             rotate_and_record(cube, Face::Up, true, steps);
@@ -125,13 +126,13 @@ impl BottomCrossSolver {
 
         let left_side = get_left_side(face);
         let up_center = get_up_center(left_side);
-        if cube.get_block_color(top_face, up_center.0, up_center.1) == Color::White
+        if cube.get_block_color(top_face, up_center.0, up_center.1) == bottom
             && cube.get_block_color(left_side.ordinal(), 0, 1) == face_color
         {
             rotate_and_record(cube, Face::Up, false, steps);
             return true;
         }
-        if cube.get_block_color(left_side.ordinal(), 0, 1) == Color::White
+        if cube.get_block_color(left_side.ordinal(), 0, 1) == bottom
             && cube.get_block_color(top_face, up_center.0, up_center.1) == face_color
         {
             rotate_and_record(cube, Face::Up, false, steps);
@@ -141,14 +142,14 @@ impl BottomCrossSolver {
 
         let back_side = get_right_side(right_side);
         let up_center = get_up_center(back_side);
-        if cube.get_block_color(top_face, up_center.0, up_center.1) == Color::White
+        if cube.get_block_color(top_face, up_center.0, up_center.1) == bottom
             && cube.get_block_color(back_side.ordinal(), 0, 1) == face_color
         {
             rotate_and_record(cube, Face::Up, false, steps);
             rotate_and_record(cube, Face::Up, false, steps);
             return true;
         }
-        if cube.get_block_color(back_side.ordinal(), 0, 1) == Color::White
+        if cube.get_block_color(back_side.ordinal(), 0, 1) == bottom
             && cube.get_block_color(top_face, up_center.0, up_center.1) == face_color
         {
             rotate_and_record(cube, Face::Up, false, steps);
@@ -160,7 +161,7 @@ impl BottomCrossSolver {
         false
     }
 
-    fn swap_edge_on_top(cube: &mut Cube, face: Face, steps: &mut Vec<char>) {
+    fn swap_edge_on_top(cube: &mut Cube, face: Face, steps: &mut Vec<Move>) {
         let right_face = get_right_side(face);
         rotate_and_record(cube, face, true, steps);
         rotate_and_record(cube, right_face, true, steps);
@@ -168,17 +169,18 @@ impl BottomCrossSolver {
         rotate_and_record(cube, right_face, false, steps);
     }
 
-    fn find_edge_in_middle(cube: &mut Cube, face: Face, steps: &mut Vec<char>) -> bool {
-        let face_color = face.color();
+    fn find_edge_in_middle(cube: &mut Cube, face: Face, steps: &mut Vec<Move>) -> bool {
+        let face_color = center_color(cube, face);
+        let bottom = center_color(cube, Face::Down);
 
         let left_side = get_left_side(face);
-        if cube.get_block_color(left_side.ordinal(), 1, 2) == Color::White
+        if cube.get_block_color(left_side.ordinal(), 1, 2) == bottom
             && cube.get_block_color(face.ordinal(), 1, 0) == face_color
         {
             rotate_and_record(cube, face, true, steps);
             return true;
         }
-        if cube.get_block_color(face.ordinal(), 1, 0) == Color::White
+        if cube.get_block_color(face.ordinal(), 1, 0) == bottom
             && cube.get_block_color(left_side.ordinal(), 1, 2) == face_color
         {
             rotate_and_record(cube, left_side, false, steps);
@@ -188,13 +190,13 @@ impl BottomCrossSolver {
         }
 
         let right_side = get_right_side(face);
-        if cube.get_block_color(right_side.ordinal(), 1, 0) == Color::White
+        if cube.get_block_color(right_side.ordinal(), 1, 0) == bottom
             && cube.get_block_color(face.ordinal(), 1, 2) == face_color
         {
             rotate_and_record(cube, face, false, steps);
             return true;
         }
-        if cube.get_block_color(face.ordinal(), 1, 2) == Color::White
+        if cube.get_block_color(face.ordinal(), 1, 2) == bottom
             && cube.get_block_color(right_side.ordinal(), 1, 0) == face_color
         {
             rotate_and_record(cube, right_side, true, steps);
@@ -204,7 +206,7 @@ impl BottomCrossSolver {
         }
 
         let back_side = get_right_side(right_side);
-        if cube.get_block_color(back_side.ordinal(), 1, 0) == Color::White
+        if cube.get_block_color(back_side.ordinal(), 1, 0) == bottom
             && cube.get_block_color(right_side.ordinal(), 1, 2) == face_color
         {
             rotate_and_record(cube, right_side, false, steps);
@@ -212,7 +214,7 @@ impl BottomCrossSolver {
             rotate_and_record(cube, right_side, true, steps);
             return true;
         }
-        if cube.get_block_color(right_side.ordinal(), 1, 2) == Color::White
+        if cube.get_block_color(right_side.ordinal(), 1, 2) == bottom
             && cube.get_block_color(back_side.ordinal(), 1, 0) == face_color
         {
             rotate_and_record(cube, back_side, true, steps);
@@ -222,7 +224,7 @@ impl BottomCrossSolver {
             return true;
         }
 
-        if cube.get_block_color(back_side.ordinal(), 1, 2) == Color::White
+        if cube.get_block_color(back_side.ordinal(), 1, 2) == bottom
             && cube.get_block_color(left_side.ordinal(), 1, 0) == face_color
         {
             rotate_and_record(cube, left_side, true, steps);
@@ -230,7 +232,7 @@ impl BottomCrossSolver {
             rotate_and_record(cube, left_side, false, steps);
             return true;
         }
-        if cube.get_block_color(left_side.ordinal(), 1, 0) == Color::White
+        if cube.get_block_color(left_side.ordinal(), 1, 0) == bottom
             && cube.get_block_color(back_side.ordinal(), 1, 2) == face_color
         {
             rotate_and_record(cube, back_side, false, steps);
@@ -243,8 +245,9 @@ impl BottomCrossSolver {
         false
     }
 
-    fn find_edge_in_bottom(cube: &mut Cube, face: Face, steps: &mut Vec<char>) -> bool {
-        let face_color = face.color();
+    fn find_edge_in_bottom(cube: &mut Cube, face: Face, steps: &mut Vec<Move>) -> bool {
+        let face_color = center_color(cube, face);
+        let bottom = center_color(cube, Face::Down);
         let down_center = Self::down_center_index(face);
 
         if cube.get_block_color(
@@ -252,7 +255,7 @@ impl BottomCrossSolver {
             down_center.0,
             down_center.1,
         ) == face_color
-            && cube.get_block_color(face.ordinal(), 2, 1) == Color::White
+            && cube.get_block_color(face.ordinal(), 2, 1) == bottom
         {
             rotate_and_record(cube, face, true, steps);
             rotate_and_record(cube, face, true, steps);
@@ -281,10 +284,11 @@ impl BottomCrossSolver {
         cube: &mut Cube,
         current_face: Face,
         side_face: Face,
-        steps: &mut Vec<char>,
+        steps: &mut Vec<Move>,
     ) -> bool {
         let down_center = Self::down_center_index(side_face);
-        let current_color = current_face.color();
+        let current_color = center_color(cube, current_face);
+        let bottom = center_color(cube, Face::Down);
 
         let side_color = cube.get_block_color(side_face.ordinal(), 2, 1);
         let down_color = cube.get_block_color(
@@ -293,8 +297,8 @@ impl BottomCrossSolver {
             down_center.1,
         );
 
-        if (side_color == current_color && down_color == Color::White)
-            || (side_color == Color::White && down_color == current_color)
+        if (side_color == current_color && down_color == bottom)
+            || (side_color == bottom && down_color == current_color)
         {
             rotate_and_record(cube, side_face, true, steps);
             rotate_and_record(cube, side_face, true, steps);
@@ -1,5 +1,3 @@
-use crate::utils::print_cube;
-
 use super::prelude::*;
 
 pub struct MiddleSolver;
@@ -13,7 +11,7 @@ impl Solver for MiddleSolver {
         super::super::SolveTarget::MiddleEdge
     }
 
-    fn solve_target(&mut self, cube: &mut Cube) -> Vec<char> {
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
         let mut steps = vec![];
         // print_cube(cube);
 
@@ -23,9 +21,9 @@ impl Solver for MiddleSolver {
             }
             Self::handle_target_edge_in_middle(cube, face, &mut steps);
 
-            Self::settle_target_edge_on_top(cube, face, &mut steps);
+            Self::settle_target_edge_on_top(cube, face, &mut steps)?;
 
-            if cube.get_block_color(face.ordinal(), 0, 1) == face.color() {
+            if cube.get_block_color(face.ordinal(), 0, 1) == center_color(cube, face) {
                 Self::insert_edge_right(cube, face, &mut steps);
             } else {
                 // The Java code rotates U' then calls insert_edge_left on the *right* side.
@@ -36,13 +34,13 @@ impl Solver for MiddleSolver {
             }
         }
 
-        steps
+        Ok(steps)
     }
 
     fn is_target_solved(&self, cube: &Cube) -> bool {
         for f in side_faces() {
             let face_colors = cube.state[f.ordinal()];
-            let color = f.color();
+            let color = center_color(cube, f);
             if face_colors[1][0] != color || face_colors[1][2] != color {
                 return false;
             }
@@ -59,14 +57,14 @@ impl MiddleSolver {
     // Checks if the middle edge between 'face' and its right side is correctly placed.
     fn is_edge_correct(cube: &Cube, face: Face) -> bool {
         let right_side = get_right_side(face);
-        cube.get_block_color(face.ordinal(), 1, 2) == face.color()
-            && cube.get_block_color(right_side.ordinal(), 1, 0) == right_side.color()
+        cube.get_block_color(face.ordinal(), 1, 2) == center_color(cube, face)
+            && cube.get_block_color(right_side.ordinal(), 1, 0) == center_color(cube, right_side)
     }
 
     // Checks if the target edge for 'target_face' is currently misplaced in another middle layer slot.
-    fn handle_target_edge_in_middle(cube: &mut Cube, target_face: Face, steps: &mut Vec<char>) {
-        let target_color = target_face.color();
-        let target_right_color = get_right_side(target_face).color();
+    fn handle_target_edge_in_middle(cube: &mut Cube, target_face: Face, steps: &mut Vec<Move>) {
+        let target_color = center_color(cube, target_face);
+        let target_right_color = center_color(cube, get_right_side(target_face));
 
         for check_face in side_faces() {
             // Skip the target face itself
@@ -89,9 +87,13 @@ impl MiddleSolver {
     }
 
     // Rotates the top layer until the target edge for 'target_face' is positioned above 'target_face'.
-    fn settle_target_edge_on_top(cube: &mut Cube, target_face: Face, steps: &mut Vec<char>) {
-        let target_color = target_face.color();
-        let target_right_color = get_right_side(target_face).color();
+    fn settle_target_edge_on_top(
+        cube: &mut Cube,
+        target_face: Face,
+        steps: &mut Vec<Move>,
+    ) -> Result<(), SolveError> {
+        let target_color = center_color(cube, target_face);
+        let target_right_color = center_color(cube, get_right_side(target_face));
         let (up_row, up_col) = get_up_center(target_face);
 
         let mut count = 0;
@@ -102,20 +104,23 @@ impl MiddleSolver {
             if (top_color == target_color && front_color == target_right_color)
                 || (top_color == target_right_color && front_color == target_color)
             {
-                break; // Found the edge
+                return Ok(()); // Found the edge
             }
 
             rotate_and_record(cube, Face::Up, true, steps);
             count += 1;
             if count > 4 {
-                print_cube(cube);
-                panic!("find_target_edge_on_top exceeded max rotations");
+                return Err(SolveError::stuck(
+                    super::super::SolveTarget::MiddleEdge,
+                    cube,
+                    steps,
+                ));
             }
         }
     }
 
     // Performs the left insertion algorithm: U' L' U L U F U' F'
-    fn insert_edge_left(cube: &mut Cube, face: Face, steps: &mut Vec<char>) {
+    fn insert_edge_left(cube: &mut Cube, face: Face, steps: &mut Vec<Move>) {
         let up = Face::Up;
         let left = get_left_side(face);
 
@@ -130,7 +135,7 @@ impl MiddleSolver {
     }
 
     // Performs the right insertion algorithm: U R U' R' U' F' U F
-    fn insert_edge_right(cube: &mut Cube, face: Face, steps: &mut Vec<char>) {
+    fn insert_edge_right(cube: &mut Cube, face: Face, steps: &mut Vec<Move>) {
         let up = Face::Up;
         let right = get_right_side(face);
 
@@ -17,7 +17,7 @@ impl TopCornerSolver {
         None
     }
     
-    fn align_solved_corner(&self, cube: &mut Cube, face: Face, steps: &mut Vec<char>) {
+    fn align_solved_corner(&self, cube: &mut Cube, face: Face, steps: &mut Vec<Move>) {
         match face {
             Face::Right => {
                 rotate_and_record(cube, Face::Up, true, steps);
@@ -33,7 +33,7 @@ impl TopCornerSolver {
         }
     }
     
-    fn execute_corner_permutation_algorithm(&self, cube: &mut Cube, steps: &mut Vec<char>) {
+    fn execute_corner_permutation_algorithm(&self, cube: &mut Cube, steps: &mut Vec<Move>) {
         // RB'RF2 R'BRF2 R2
         let right = Face::Right;
         let back = Face::Back;
@@ -75,14 +75,14 @@ impl Solver for TopCornerSolver {
         super::super::SolveTarget::TopCorner
     }
 
-    fn solve_target(&mut self, cube: &mut Cube) -> Vec<char> {
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
         let mut steps = vec![];
         let mut count = 0;
         let mut to_fix = false;
         
         while !self.is_target_solved(cube) {
             if count > 2 {
-                panic!("Exceeded maximum iterations");
+                return Err(SolveError::stuck(self.target(), cube, &steps));
             }
             count += 1;
             
@@ -102,28 +102,28 @@ impl Solver for TopCornerSolver {
             let front = Face::Front;
             let color = cube.get_block_color(front.ordinal(), 0, 0);
             
-            match color {
-                Color::Orange => rotate_and_record(cube, Face::Up, true, &mut steps),
-                Color::Green => {
-                    rotate_and_record(cube, Face::Up, true, &mut steps);
-                    rotate_and_record(cube, Face::Up, true, &mut steps);
-                },
-                Color::Red => rotate_and_record(cube, Face::Up, false, &mut steps),
-                _ => {}
+            if color == center_color(cube, Face::Left) {
+                rotate_and_record(cube, Face::Up, true, &mut steps);
+            } else if color == center_color(cube, Face::Back) {
+                rotate_and_record(cube, Face::Up, true, &mut steps);
+                rotate_and_record(cube, Face::Up, true, &mut steps);
+            } else if color == center_color(cube, Face::Right) {
+                rotate_and_record(cube, Face::Up, false, &mut steps);
             }
         }
         
-        steps
+        Ok(steps)
     }
     
     fn is_target_solved(&self, cube: &Cube) -> bool {
         let up = Face::Up;
         let up_ordinal = up.ordinal();
         
-        // Check all top face colors are yellow
+        // Check all top face colors match the top center
+        let top = center_color(cube, up);
         for i in 0..3 {
             for j in 0..3 {
-                if cube.get_block_color(up_ordinal, i, j) != Color::Yellow {
+                if cube.get_block_color(up_ordinal, i, j) != top {
                     return false;
                 }
             }
@@ -136,8 +136,8 @@ impl Solver for TopCornerSolver {
             Face::Back,
             Face::Left
         ] {
-            if cube.get_block_color(face.ordinal(), 0, 0) != face.color() ||
-               cube.get_block_color(face.ordinal(), 0, 2) != face.color() {
+            if cube.get_block_color(face.ordinal(), 0, 0) != center_color(cube, face) ||
+               cube.get_block_color(face.ordinal(), 0, 2) != center_color(cube, face) {
                 return false;
             }
         }
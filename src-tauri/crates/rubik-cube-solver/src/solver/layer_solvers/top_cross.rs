@@ -7,19 +7,19 @@ impl Solver for TopCrossSolver {
         super::super::SolveTarget::TopCross
     }
 
-    fn solve_target(&mut self, cube: &mut Cube) -> Vec<char> {
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
         let mut steps = vec![];
         let mut count = 0;
         
         while !self.is_target_solved(cube) {
             if count > 4 {
-                panic!("Exceeded maximum iterations");
+                return Err(SolveError::stuck(self.target(), cube, &steps));
             }
             count += 1;
             
             if Self::is_top_dot(cube) {
                 Self::execute_top_cross(cube, &mut steps);
-            } else if Self::is_top_l(cube) {
+            } else if Self::is_top_l(cube, &mut steps) {
                 Self::execute_top_cross(cube, &mut steps);
             } else {
                 Self::align_top_line(cube, &mut steps);
@@ -27,7 +27,7 @@ impl Solver for TopCrossSolver {
             }
         }
         
-        steps
+        Ok(steps)
     }
     
   
@@ -49,40 +49,42 @@ impl Solver for TopCrossSolver {
 
 impl TopCrossSolver {
     fn is_top_dot(cube: &Cube) -> bool {
+        let top = center_color(cube, Face::Up);
         let up = Face::Up;
         let up_ordinal = up.ordinal();
         
-        cube.get_block_color(up_ordinal, 0, 1) != Color::Yellow &&
-        cube.get_block_color(up_ordinal, 1, 0) != Color::Yellow &&
-        cube.get_block_color(up_ordinal, 1, 2) != Color::Yellow &&
-        cube.get_block_color(up_ordinal, 2, 1) != Color::Yellow
+        cube.get_block_color(up_ordinal, 0, 1) != top &&
+        cube.get_block_color(up_ordinal, 1, 0) != top &&
+        cube.get_block_color(up_ordinal, 1, 2) != top &&
+        cube.get_block_color(up_ordinal, 2, 1) != top
     }
     
-    fn is_top_l(cube: &mut Cube) -> bool {
+    fn is_top_l(cube: &mut Cube, steps: &mut Vec<Move>) -> bool {
+        let top = center_color(cube, Face::Up);
         let up = Face::Up;
         let up_ordinal = up.ordinal();
         
-        let down = cube.get_block_color(up_ordinal, 2, 1) == Color::Yellow;
-        let left = cube.get_block_color(up_ordinal, 1, 0) == Color::Yellow;
-        let right = cube.get_block_color(up_ordinal, 1, 2) == Color::Yellow;
-        let up_color = cube.get_block_color(up_ordinal, 0, 1) == Color::Yellow;
+        let down = cube.get_block_color(up_ordinal, 2, 1) == top;
+        let left = cube.get_block_color(up_ordinal, 1, 0) == top;
+        let right = cube.get_block_color(up_ordinal, 1, 2) == top;
+        let up_color = cube.get_block_color(up_ordinal, 0, 1) == top;
         
         let left_down = left && down;
         if left_down {
-            rotate_and_record(cube, up, true, &mut vec![]);
+            rotate_and_record(cube, up, true, steps);
             return true;
         }
         
         let right_down = right && down;
         if right_down {
-            rotate_and_record(cube, up, false, &mut vec![]);
-            rotate_and_record(cube, up, false, &mut vec![]);
+            rotate_and_record(cube, up, false, steps);
+            rotate_and_record(cube, up, false, steps);
             return true;
         }
         
         let right_up = right && up_color;
         if right_up {
-            rotate_and_record(cube, up, false, &mut vec![]);
+            rotate_and_record(cube, up, false, steps);
             return true;
         }
         
@@ -90,14 +92,15 @@ impl TopCrossSolver {
     }
     
     fn is_top_line(cube: &Cube) -> bool {
+        let top = center_color(cube, Face::Up);
         let up = Face::Up;
         let up_ordinal = up.ordinal();
         
-        cube.get_block_color(up_ordinal, 1, 0) == Color::Yellow &&
-        cube.get_block_color(up_ordinal, 1, 2) == Color::Yellow
+        cube.get_block_color(up_ordinal, 1, 0) == top &&
+        cube.get_block_color(up_ordinal, 1, 2) == top
     }
     
-    fn execute_top_cross(cube: &mut Cube, steps: &mut Vec<char>) {
+    fn execute_top_cross(cube: &mut Cube, steps: &mut Vec<Move>) {
         let front = Face::Front;
         let right = Face::Right;
         let up = Face::Up;
@@ -110,7 +113,7 @@ impl TopCrossSolver {
         rotate_and_record(cube, front, false, steps);
     }
     
-    fn align_top_line(cube: &mut Cube, steps: &mut Vec<char>) {
+    fn align_top_line(cube: &mut Cube, steps: &mut Vec<Move>) {
         if !Self::is_top_line(cube) {
             rotate_and_record(cube, Face::Up, true, steps);
         }
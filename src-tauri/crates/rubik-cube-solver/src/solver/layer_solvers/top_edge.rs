@@ -1,5 +1,3 @@
-use crate::utils::print_cube;
-
 use super::prelude::*;
 
 pub struct TopEdgeSolver {}
@@ -9,14 +7,13 @@ impl Solver for TopEdgeSolver {
         super::super::SolveTarget::TopEdge
     }
 
-    fn solve_target(&mut self, cube: &mut Cube) -> Vec<char> {
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
         let mut steps = vec![];
         let mut count = 0;
 
         while !self.is_target_solved(cube) {
             if count > 3 {
-                print_cube(cube);
-                panic!("Exceeded maximum iterations");
+                return Err(SolveError::stuck(self.target(), cube, &steps));
             }
             count += 1;
 
@@ -24,7 +21,7 @@ impl Solver for TopEdgeSolver {
             self.execute_edge_permutation_algorithm(cube, face, &mut steps);
         }
 
-        steps
+        Ok(steps)
     }
 
     fn next_solver(&self) -> Option<SolverEnum> {
@@ -45,7 +42,7 @@ impl TopEdgeSolver {
             Face::Back,
             Face::Left,
         ] {
-            if cube.get_block_color(face.ordinal(), 0, 1) == face.color() {
+            if cube.get_block_color(face.ordinal(), 0, 1) == center_color(cube, face) {
                 return face;
             }
         }
@@ -56,7 +53,7 @@ impl TopEdgeSolver {
         &self,
         cube: &mut Cube,
         face: Face,
-        steps: &mut Vec<char>,
+        steps: &mut Vec<Move>,
     ) {
         // Calculate pre-rotation count based on face
         let rotations = match face {
@@ -7,19 +7,19 @@ impl Solver for TopFaceSolver {
         super::super::SolveTarget::TopFace
     }
 
-    fn solve_target(&mut self, cube: &mut Cube) -> Vec<char> {
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
         let mut steps = vec![];
         let mut count = 0;
         
         while !self.is_target_solved(cube) {
             if count > 6 {
-                panic!("Exceeded maximum iterations");
+                return Err(SolveError::stuck(self.target(), cube, &steps));
             }
             count += 1;
             
             let yellow_corners = self.count_yellow_corners(cube);
             if yellow_corners == 1 {
-                if self.on_right_side(cube) {
+                if self.on_right_side(cube, &mut steps)? {
                     self.right_hand_algorithm(cube, &mut steps);
                 } else {
                     self.left_hand_algorithm(cube, &mut steps);
@@ -33,7 +33,7 @@ impl Solver for TopFaceSolver {
             }
         }
         
-        steps
+        Ok(steps)
     }
 
     fn is_target_solved(&self, cube: &Cube) -> bool {
@@ -59,87 +59,91 @@ impl Solver for TopFaceSolver {
 
 impl TopFaceSolver {
     fn count_yellow_corners(&self, cube: &Cube) -> usize {
+        let top = center_color(cube, Face::Up);
         let up = Face::Up;
         let up_ordinal = up.ordinal();
         
         let mut count = 0;
-        if cube.get_block_color(up_ordinal, 0, 0) == Color::Yellow { count += 1; }
-        if cube.get_block_color(up_ordinal, 0, 2) == Color::Yellow { count += 1; }
-        if cube.get_block_color(up_ordinal, 2, 0) == Color::Yellow { count += 1; }
-        if cube.get_block_color(up_ordinal, 2, 2) == Color::Yellow { count += 1; }
+        if cube.get_block_color(up_ordinal, 0, 0) == top { count += 1; }
+        if cube.get_block_color(up_ordinal, 0, 2) == top { count += 1; }
+        if cube.get_block_color(up_ordinal, 2, 0) == top { count += 1; }
+        if cube.get_block_color(up_ordinal, 2, 2) == top { count += 1; }
         count
     }
 
-    fn on_right_side(&self, cube: &mut Cube) -> bool {
+    fn on_right_side(&self, cube: &mut Cube, steps: &mut Vec<Move>) -> Result<bool, SolveError> {
+        let top = center_color(cube, Face::Up);
         let up = Face::Up;
         let left = Face::Left;
         let front = Face::Front;
         let right = Face::Right;
         let back = Face::Back;
         
-        if cube.get_block_color(up.ordinal(), 0, 0) == Color::Yellow {
-            if cube.get_block_color(left.ordinal(), 0, 2) == Color::Yellow {
-                rotate_and_record(cube, up, false, &mut vec![]);
-                return true;
+        if cube.get_block_color(up.ordinal(), 0, 0) == top {
+            if cube.get_block_color(left.ordinal(), 0, 2) == top {
+                rotate_and_record(cube, up, false, steps);
+                return Ok(true);
             }
-            if cube.get_block_color(back.ordinal(), 0, 0) == Color::Yellow {
-                rotate_and_record(cube, up, true, &mut vec![]);
-                rotate_and_record(cube, up, true, &mut vec![]);
-                return false;
+            if cube.get_block_color(back.ordinal(), 0, 0) == top {
+                rotate_and_record(cube, up, true, steps);
+                rotate_and_record(cube, up, true, steps);
+                return Ok(false);
             }
         }
 
-        if cube.get_block_color(up.ordinal(), 0, 2) == Color::Yellow {
-            if cube.get_block_color(back.ordinal(), 0, 2) == Color::Yellow {
-                rotate_and_record(cube, up, false, &mut vec![]);
-                rotate_and_record(cube, up, false, &mut vec![]);
-                return true;
+        if cube.get_block_color(up.ordinal(), 0, 2) == top {
+            if cube.get_block_color(back.ordinal(), 0, 2) == top {
+                rotate_and_record(cube, up, false, steps);
+                rotate_and_record(cube, up, false, steps);
+                return Ok(true);
             }
-            if cube.get_block_color(right.ordinal(), 0, 0) == Color::Yellow {
-                rotate_and_record(cube, up, true, &mut vec![]);
-                return false;
+            if cube.get_block_color(right.ordinal(), 0, 0) == top {
+                rotate_and_record(cube, up, true, steps);
+                return Ok(false);
             }
         }
 
-        if cube.get_block_color(up.ordinal(), 2, 2) == Color::Yellow {
-            if cube.get_block_color(right.ordinal(), 0, 2) == Color::Yellow {
-                rotate_and_record(cube, up, true, &mut vec![]);
-                return true;
+        if cube.get_block_color(up.ordinal(), 2, 2) == top {
+            if cube.get_block_color(right.ordinal(), 0, 2) == top {
+                rotate_and_record(cube, up, true, steps);
+                return Ok(true);
             }
-            if cube.get_block_color(front.ordinal(), 0, 0) == Color::Yellow {
-                return false;
+            if cube.get_block_color(front.ordinal(), 0, 0) == top {
+                return Ok(false);
             }
         }
 
-        if cube.get_block_color(up.ordinal(), 2, 0) == Color::Yellow {
-            if cube.get_block_color(front.ordinal(), 0, 2) == Color::Yellow {
-                rotate_and_record(cube, up, true, &mut vec![]);
-                return true;
+        if cube.get_block_color(up.ordinal(), 2, 0) == top {
+            if cube.get_block_color(front.ordinal(), 0, 2) == top {
+                rotate_and_record(cube, up, true, steps);
+                return Ok(true);
             }
-            if cube.get_block_color(left.ordinal(), 0, 0) == Color::Yellow {
-                rotate_and_record(cube, up, false, &mut vec![]);
-                return false;
+            if cube.get_block_color(left.ordinal(), 0, 0) == top {
+                rotate_and_record(cube, up, false, steps);
+                return Ok(false);
             }
         }
         
-        panic!("Invalid state for on_right_side check");
+        Err(SolveError::stuck(self.target(), cube, steps))
     }
 
-    fn align_2_not_yellow(&self, cube: &mut Cube, steps: &mut Vec<char>) {
+    fn align_2_not_yellow(&self, cube: &mut Cube, steps: &mut Vec<Move>) {
+        let top = center_color(cube, Face::Up);
         let front = Face::Front;
-        while cube.get_block_color(front.ordinal(), 0, 0) != Color::Yellow {
+        while cube.get_block_color(front.ordinal(), 0, 0) != top {
             rotate_and_record(cube, Face::Up, true, steps);
         }
     }
 
-    fn align_4_not_yellow(&self, cube: &mut Cube, steps: &mut Vec<char>) {
+    fn align_4_not_yellow(&self, cube: &mut Cube, steps: &mut Vec<Move>) {
+        let top = center_color(cube, Face::Up);
         let left = Face::Left;
-        while cube.get_block_color(left.ordinal(), 0, 2) != Color::Yellow {
+        while cube.get_block_color(left.ordinal(), 0, 2) != top {
             rotate_and_record(cube, Face::Up, true, steps);
         }
     }
 
-    fn left_hand_algorithm(&self, cube: &mut Cube, steps: &mut Vec<char>) {
+    fn left_hand_algorithm(&self, cube: &mut Cube, steps: &mut Vec<Move>) {
         let left = Face::Left;
         let up = Face::Up;
         
@@ -153,7 +157,7 @@ impl TopFaceSolver {
         rotate_and_record(cube, left, true, steps);
     }
 
-    fn right_hand_algorithm(&self, cube: &mut Cube, steps: &mut Vec<char>) {
+    fn right_hand_algorithm(&self, cube: &mut Cube, steps: &mut Vec<Move>) {
         let right = Face::Right;
         let up = Face::Up;
         
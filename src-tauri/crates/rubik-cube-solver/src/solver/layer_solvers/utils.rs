@@ -1,75 +1,34 @@
 use rubik_cube_core::cube::{
+    color::Color,
     face::{Face, TwistDirection},
+    notation::Move,
     Cube,
 };
-use rubik_cube_shuffler::CubeScrambler;
 
 pub fn rotate_and_record(
     cube: &mut Cube,
     face: Face,
     clockwize_direction: bool,
-    steps: &mut Vec<char>,
+    steps: &mut Vec<Move>,
 ) {
-    let mut shuffler = CubeScrambler::new(cube);
-    shuffler.scramble(
-        face,
-        if clockwize_direction {
-            TwistDirection::Clockwise
-        } else {
-            TwistDirection::CounterClockwise
-        },
-    );
-
-    let step = match face {
-        Face::Front => {
-            if clockwize_direction {
-                'f'
-            } else {
-                'F'
-            }
-        }
-        Face::Back => {
-            if clockwize_direction {
-                'b'
-            } else {
-                'B'
-            }
-        }
-        Face::Left => {
-            if clockwize_direction {
-                'l'
-            } else {
-                'L'
-            }
-        }
-        Face::Right => {
-            if clockwize_direction {
-                'r'
-            } else {
-                'R'
-            }
-        }
-        Face::Up => {
-            if clockwize_direction {
-                'u'
-            } else {
-                'U'
-            }
-        }
-        Face::Down => {
-            if clockwize_direction {
-                'd'
-            } else {
-                'D'
-            }
-        }
+    let direction = if clockwize_direction {
+        TwistDirection::Clockwise
+    } else {
+        TwistDirection::CounterClockwise
     };
-    steps.push(step);
+    let mv = Move::face(face, direction);
+    cube.apply_move(mv);
+    steps.push(mv);
 
-    // println!("\nrotate_and_record: {:?}", step);
+    // println!("\nrotate_and_record: {:?}", steps.last());
     // print_cube(cube);
 }
 
+/// 每个面的颜色以中心块为准：魔方可以任意朝向，底面不一定是白色
+pub fn center_color(cube: &Cube, face: Face) -> Color {
+    cube.get_block_color(face.ordinal(), 1, 1)
+}
+
 pub fn get_up_center(face: Face) -> (usize, usize) {
     match face {
         Face::Front => (2, 1),
@@ -1,7 +1,37 @@
+pub use cfop::{CrossSolver, F2lSolver, OllSolver, PllSolver};
+pub use error::SolveError;
 pub use layer_solvers::*;
-use rubik_cube_core::cube::Cube;
+pub use neutral::execute_color_neutral;
+pub use optimal::{CancelToken, OptimalSolution, OptimalSolver, Pattern, PatternDatabase};
+pub use playback::{Playback, PlaybackStep};
+pub use pocket::{PocketCube, PocketSolver};
+pub use reduction::{
+    execute_reduction, ReductionError, ReductionPhase, ReductionSolution, ReductionStage,
+};
+pub use simplify::simplify;
+pub use solution::{Solution, SolutionStage};
+pub use two_phase::TwoPhaseSolver;
 
+use std::time::Duration;
+
+use rubik_cube_core::cube::{
+    error::UnknownCode,
+    notation::{Algorithm, Move},
+    Cube,
+};
+
+mod cfop;
+mod error;
+mod face_turns;
 mod layer_solvers;
+mod neutral;
+mod optimal;
+mod playback;
+mod pocket;
+mod reduction;
+mod simplify;
+mod solution;
+mod two_phase;
 
 /// 求解分分步骤
 /// 1. 底层十字
@@ -11,6 +41,8 @@ mod layer_solvers;
 /// 5. 顶层黄面
 /// 6. 顶层棱块
 /// 7. 顶层角块
+///
+/// CFOP 的步骤：十字、前两层（F2L）、顶层朝向（OLL）、顶层排列（PLL）
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum SolveTarget {
@@ -21,11 +53,17 @@ pub enum SolveTarget {
     TopFace,
     TopCorner,
     TopEdge,
+    Cross,
+    F2l,
+    Oll,
+    Pll,
 }
 
-impl SolveTarget {
-    pub fn from_u8(value: u8) -> SolveTarget {
-        match value {
+impl TryFrom<u8> for SolveTarget {
+    type Error = UnknownCode;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(match value {
             0 => SolveTarget::BottomCross,
             1 => SolveTarget::BottomCorner,
             2 => SolveTarget::MiddleEdge,
@@ -33,33 +71,155 @@ impl SolveTarget {
             4 => SolveTarget::TopFace,
             5 => SolveTarget::TopCorner,
             6 => SolveTarget::TopEdge,
-            _ => panic!("Invalid solve target value: {}", value),
+            7 => SolveTarget::Cross,
+            8 => SolveTarget::F2l,
+            9 => SolveTarget::Oll,
+            10 => SolveTarget::Pll,
+            _ => {
+                return Err(UnknownCode {
+                    kind: "stage",
+                    value,
+                })
+            }
+        })
+    }
+}
+
+impl SolveTarget {
+    /// The stages of this target's method, from the first up to this one
+    pub fn stages(&self) -> Vec<SolveTarget> {
+        let first = if *self as u8 >= SolveTarget::Cross as u8 {
+            SolveTarget::Cross as u8
+        } else {
+            SolveTarget::BottomCross as u8
+        };
+        (first..=*self as u8)
+            .map(|value| SolveTarget::try_from(value).unwrap())
+            .collect()
+    }
+
+    /// Whether this stage and all stages before it are solved
+    pub fn is_reached(&self, cube: &Cube) -> bool {
+        self.stages()
+            .iter()
+            .all(|stage| stage.solver().is_target_solved(cube))
+    }
+
+    fn solver(&self) -> SolverEnum {
+        match self {
+            SolveTarget::BottomCross => SolverEnum::BottomCross(BottomCrossSolver {}),
+            SolveTarget::BottomCorner => SolverEnum::BottomCorner(BottomCornerSolver {}),
+            SolveTarget::MiddleEdge => SolverEnum::MiddleEdge(MiddleSolver {}),
+            SolveTarget::TopCross => SolverEnum::TopCross(TopCrossSolver {}),
+            SolveTarget::TopFace => SolverEnum::TopFace(TopFaceSolver {}),
+            SolveTarget::TopCorner => SolverEnum::TopCorner(TopCornerSolver {}),
+            SolveTarget::TopEdge => SolverEnum::TopEdge(TopEdgeSolver {}),
+            SolveTarget::Cross => SolverEnum::Cross(CrossSolver {}),
+            SolveTarget::F2l => SolverEnum::F2l(F2lSolver::default()),
+            SolveTarget::Oll => SolverEnum::Oll(OllSolver::default()),
+            SolveTarget::Pll => SolverEnum::Pll(PllSolver::default()),
         }
     }
 }
 
-pub fn execute(cube: &mut Cube, target: SolveTarget) -> (Vec<char>, Cube) {
+/// 求解方法
+/// - LayerByLayer: 按层还原，可以停在任意中间目标
+/// - TwoPhase: Kociemba 两阶段算法，一次还原整个魔方
+/// - Cfop: 十字、F2L、OLL、PLL，每步都标出公式的情形
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveMethod {
+    LayerByLayer,
+    TwoPhase {
+        max_length: usize,
+        time_budget: Duration,
+    },
+    Cfop,
+}
+
+impl TryFrom<u8> for SolveMethod {
+    type Error = UnknownCode;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SolveMethod::LayerByLayer),
+            1 => Ok(SolveMethod::two_phase()),
+            2 => Ok(SolveMethod::Cfop),
+            _ => Err(UnknownCode {
+                kind: "solve method",
+                value,
+            }),
+        }
+    }
+}
+
+impl SolveMethod {
+    /// Two-phase with the default length and time budget
+    pub fn two_phase() -> SolveMethod {
+        let solver = TwoPhaseSolver::default();
+        SolveMethod::TwoPhase {
+            max_length: solver.max_length,
+            time_budget: solver.time_budget,
+        }
+    }
+
+    fn first_solver(&self) -> SolverEnum {
+        match *self {
+            SolveMethod::LayerByLayer => SolverEnum::BottomCross(BottomCrossSolver {}),
+            SolveMethod::TwoPhase {
+                max_length,
+                time_budget,
+            } => SolverEnum::TwoPhase(TwoPhaseSolver::new(max_length, time_budget)),
+            SolveMethod::Cfop => SolverEnum::Cross(CrossSolver {}),
+        }
+    }
+}
+
+pub fn execute(cube: &mut Cube, target: SolveTarget) -> Result<Solution, SolveError> {
+    execute_with_method(cube, target, SolveMethod::LayerByLayer)
+}
+
+/// Solves up to `target`, keeping each solver's moves apart. Methods that
+/// solve the whole cube at once go past `target`. States no real cube can
+/// reach are rejected before any stage runs.
+pub fn execute_with_method(
+    cube: &mut Cube,
+    target: SolveTarget,
+    method: SolveMethod,
+) -> Result<Solution, SolveError> {
+    let mut solution = Solution::new(cube.clone());
     if cube.is_solved() {
-        return (vec![], cube.clone());
+        return Ok(solution);
     }
+    cube.validate()?;
 
-    let mut seq = vec![];
-    let first_solver = BottomCrossSolver {};
-    let mut solver = SolverEnum::BottomCross(first_solver);
+    let mut solver = method.first_solver();
     let mut prev_solver: Option<SolverEnum> = None;
 
     loop {
         if !solver.is_target_solved(cube) {
             if let Some(prev) = prev_solver {
                 if !prev.is_target_solved(cube) {
-                    panic!("Previous solver is not solved");
+                    return Err(SolveError::PreviousUnsolved {
+                        stage: solver.target(),
+                        previous: prev.target(),
+                        state: cube.clone(),
+                        moves: solution.moves(),
+                    });
                 }
             }
-            seq.extend(solver.solve_target(cube));
+            let state_before = cube.clone();
+            let moves = solver
+                .solve_target(cube)
+                .map_err(|e| e.after(solution.moves()))?;
+            solution.stages.push(SolutionStage {
+                target: solver.target(),
+                label: solver.label(),
+                moves: Algorithm::from(moves),
+                state_before,
+                state_after: cube.clone(),
+            });
         }
-        println!("DONE: current solver is {:?}", solver.target());
         if solver.target() == target {
-            println!("reached target  {:?}", target);
             break;
         }
 
@@ -69,9 +229,8 @@ pub fn execute(cube: &mut Cube, target: SolveTarget) -> (Vec<char>, Cube) {
             Some(s) => s,
             None => break,
         };
-
     }
-    (seq, cube.clone())
+    Ok(solution)
 }
 
 pub enum SolverEnum {
@@ -82,6 +241,11 @@ pub enum SolverEnum {
     TopFace(TopFaceSolver),
     TopEdge(TopEdgeSolver),
     TopCorner(TopCornerSolver),
+    TwoPhase(TwoPhaseSolver),
+    Cross(CrossSolver),
+    F2l(F2lSolver),
+    Oll(OllSolver),
+    Pll(PllSolver),
 }
 
 impl Solver for SolverEnum {
@@ -94,10 +258,15 @@ impl Solver for SolverEnum {
             SolverEnum::TopFace(s) => s.target(),
             SolverEnum::TopEdge(s) => s.target(),
             SolverEnum::TopCorner(s) => s.target(),
+            SolverEnum::TwoPhase(s) => s.target(),
+            SolverEnum::Cross(s) => s.target(),
+            SolverEnum::F2l(s) => s.target(),
+            SolverEnum::Oll(s) => s.target(),
+            SolverEnum::Pll(s) => s.target(),
         }
     }
 
-    fn solve_target(&mut self, cube: &mut Cube) -> Vec<char> {
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
         match self {
             SolverEnum::BottomCross(s) => s.solve_target(cube),
             SolverEnum::BottomCorner(s) => s.solve_target(cube),
@@ -106,6 +275,11 @@ impl Solver for SolverEnum {
             SolverEnum::TopFace(s) => s.solve_target(cube),
             SolverEnum::TopEdge(s) => s.solve_target(cube),
             SolverEnum::TopCorner(s) => s.solve_target(cube),
+            SolverEnum::TwoPhase(s) => s.solve_target(cube),
+            SolverEnum::Cross(s) => s.solve_target(cube),
+            SolverEnum::F2l(s) => s.solve_target(cube),
+            SolverEnum::Oll(s) => s.solve_target(cube),
+            SolverEnum::Pll(s) => s.solve_target(cube),
         }
     }
 
@@ -118,6 +292,11 @@ impl Solver for SolverEnum {
             SolverEnum::TopFace(s) => s.is_target_solved(cube),
             SolverEnum::TopEdge(s) => s.is_target_solved(cube),
             SolverEnum::TopCorner(s) => s.is_target_solved(cube),
+            SolverEnum::TwoPhase(s) => s.is_target_solved(cube),
+            SolverEnum::Cross(s) => s.is_target_solved(cube),
+            SolverEnum::F2l(s) => s.is_target_solved(cube),
+            SolverEnum::Oll(s) => s.is_target_solved(cube),
+            SolverEnum::Pll(s) => s.is_target_solved(cube),
         }
     }
 
@@ -130,6 +309,20 @@ impl Solver for SolverEnum {
             SolverEnum::TopFace(s) => s.next_solver(),
             SolverEnum::TopEdge(s) => s.next_solver(),
             SolverEnum::TopCorner(s) => s.next_solver(),
+            SolverEnum::TwoPhase(s) => s.next_solver(),
+            SolverEnum::Cross(s) => s.next_solver(),
+            SolverEnum::F2l(s) => s.next_solver(),
+            SolverEnum::Oll(s) => s.next_solver(),
+            SolverEnum::Pll(s) => s.next_solver(),
+        }
+    }
+
+    fn label(&self) -> Option<String> {
+        match self {
+            SolverEnum::F2l(s) => s.label(),
+            SolverEnum::Oll(s) => s.label(),
+            SolverEnum::Pll(s) => s.label(),
+            _ => None,
         }
     }
 }
@@ -137,9 +330,14 @@ impl Solver for SolverEnum {
 pub trait Solver {
     fn target(&self) -> SolveTarget;
 
-    fn solve_target(&mut self, cube: &mut Cube) -> Vec<char>;
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError>;
 
     fn is_target_solved(&self, cube: &Cube) -> bool;
 
     fn next_solver(&self) -> Option<SolverEnum>;
+
+    /// The case the last `solve_target` recognized, e.g. "OLL 27 / Sune"
+    fn label(&self) -> Option<String> {
+        None
+    }
 }
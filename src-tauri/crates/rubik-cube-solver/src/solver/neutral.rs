@@ -0,0 +1,190 @@
+//! Colour neutrality: the first layer may go on any of the six colours
+//!
+//! The solvers take their colours from the centres, so starting on another
+//! colour is a matter of turning that centre to the bottom first. Every
+//! colour is tried and the shortest solution kept, with its rotation as the
+//! first moves.
+
+use rubik_cube_core::cube::{
+    notation::{Algorithm, Axis, Move, MoveKind, Turn},
+    Cube,
+};
+
+use super::{execute_with_method, Solution, SolutionStage, SolveError, SolveMethod, SolveTarget};
+
+/// Rotations bringing each centre to the bottom, the current bottom first
+fn bottom_rotations() -> [Vec<Move>; 6] {
+    let rotation = |axis, turn| vec![Move::new(MoveKind::Rotation(axis), turn)];
+    [
+        vec![],
+        rotation(Axis::X, Turn::Double),
+        rotation(Axis::X, Turn::Clockwise),
+        rotation(Axis::X, Turn::CounterClockwise),
+        rotation(Axis::Z, Turn::Clockwise),
+        rotation(Axis::Z, Turn::CounterClockwise),
+    ]
+}
+
+/// Like [`execute_with_method`], but starts on whichever bottom colour
+/// gives the fewest moves (HTM). The winning rotation leads the first
+/// stage's moves; ties keep the cube as it is.
+pub fn execute_color_neutral(
+    cube: &mut Cube,
+    target: SolveTarget,
+    method: SolveMethod,
+) -> Result<Solution, SolveError> {
+    if cube.is_solved() {
+        return Ok(Solution::new(cube.clone()));
+    }
+    cube.validate()?;
+
+    let mut best: Option<(Vec<Move>, Cube, Solution)> = None;
+    let mut first_error = None;
+    for rotation in bottom_rotations() {
+        let mut turned = cube.clone();
+        for &mv in &rotation {
+            turned.apply_move(mv);
+        }
+        match execute_with_method(&mut turned, target, method) {
+            Ok(solution) => {
+                let shorter = best
+                    .as_ref()
+                    .is_none_or(|(_, _, best)| solution.htm() < best.htm());
+                if shorter {
+                    best = Some((rotation, turned, solution));
+                }
+            }
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+    let Some((rotation, turned, mut solution)) = best else {
+        return Err(first_error.unwrap());
+    };
+
+    solution.initial = cube.clone();
+    match solution.stages.first_mut() {
+        Some(first) => {
+            let mut moves = Algorithm::from(rotation);
+            moves.extend(first.moves.clone());
+            first.moves = moves;
+            first.state_before = cube.clone();
+        }
+        // 转过来就已经达到目标，只剩整体转动
+        None if !rotation.is_empty() => solution.stages.push(SolutionStage {
+            target: target.stages()[0],
+            label: None,
+            moves: Algorithm::from(rotation),
+            state_before: cube.clone(),
+            state_after: turned.clone(),
+        }),
+        None => {}
+    }
+    *cube = turned;
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use rubik_cube_core::cube::{face::Face, notation::Metric, scheme::ColorScheme};
+
+    use super::*;
+
+    fn scrambled(alg: &str) -> Cube {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&alg.parse().unwrap());
+        cube
+    }
+
+    #[test]
+    fn rotations_cover_every_bottom() {
+        let mut bottoms: Vec<_> = bottom_rotations()
+            .iter()
+            .map(|rotation| {
+                let mut cube = Cube::new();
+                for &mv in rotation {
+                    cube.apply_move(mv);
+                }
+                cube.get_block_color(Face::Down.ordinal(), 1, 1)
+            })
+            .collect();
+        bottoms.sort_by_key(|&color| color as u8);
+        bottoms.dedup();
+        assert_eq!(bottoms.len(), 6);
+    }
+
+    #[test]
+    fn picks_the_cheapest_bottom() {
+        let cube = scrambled("R U F' L2 D B' R2 U' F D2 L B2 U R' F2");
+        let fixed = execute_with_method(
+            &mut cube.clone(),
+            SolveTarget::TopEdge,
+            SolveMethod::LayerByLayer,
+        )
+        .unwrap();
+
+        let mut solved = cube.clone();
+        let solution =
+            execute_color_neutral(&mut solved, SolveTarget::TopEdge, SolveMethod::LayerByLayer)
+                .unwrap();
+        assert!(solved.is_solved());
+        assert!(solution.htm() <= fixed.htm());
+        assert_eq!(solution.stages[0].state_before, cube);
+
+        let mut replay = cube.clone();
+        replay.apply_algorithm(&solution.moves());
+        assert_eq!(replay, solved);
+    }
+
+    #[test]
+    fn turns_a_solved_cross_of_another_colour_down() {
+        // R2 不动橙色面的十字，转到底面就不用再转别的
+        let initial = scrambled("R2");
+        let mut cube = initial.clone();
+        let solution = execute_color_neutral(
+            &mut cube,
+            SolveTarget::BottomCross,
+            SolveMethod::LayerByLayer,
+        )
+        .unwrap();
+        assert_eq!(solution.length(Metric::Htm), 0);
+        assert_eq!(solution.stages.len(), 1);
+        assert_eq!(solution.stages[0].moves.to_string(), "z'");
+        assert_eq!(
+            cube.get_block_color(Face::Down.ordinal(), 1, 1),
+            Face::Left.color()
+        );
+        assert!(SolveTarget::BottomCross.is_reached(&cube));
+
+        let mut replay = initial;
+        replay.apply_algorithm(&solution.moves());
+        assert_eq!(replay, cube);
+    }
+
+    #[test]
+    fn layer_solvers_follow_the_centres() {
+        let mut cube = scrambled("x y R U2 F' L D2 B");
+        execute_with_method(&mut cube, SolveTarget::TopEdge, SolveMethod::LayerByLayer).unwrap();
+        assert!(cube.is_solved());
+    }
+
+    #[test]
+    fn solves_any_colour_scheme() {
+        let mut japanese = Cube::with_scheme(ColorScheme::JAPANESE);
+        japanese.apply_algorithm(&"R U F' L2 D B' R2 U' F D2".parse().unwrap());
+        for method in [
+            SolveMethod::LayerByLayer,
+            SolveMethod::Cfop,
+            SolveMethod::two_phase(),
+        ] {
+            let mut cube = japanese.clone();
+            execute_with_method(&mut cube, SolveTarget::TopEdge, method).unwrap();
+            assert_eq!(cube, Cube::with_scheme(ColorScheme::JAPANESE));
+
+            let mut cube = japanese.clone();
+            execute_color_neutral(&mut cube, SolveTarget::TopEdge, method).unwrap();
+            assert!(cube.is_solved());
+        }
+    }
+}
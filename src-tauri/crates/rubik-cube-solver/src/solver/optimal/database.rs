@@ -0,0 +1,221 @@
+//! Pattern databases: the exact distance to solved of every pattern state,
+//! packed two entries per byte
+//!
+//! Generating the large databases takes minutes, so they are written to disk
+//! once and memory-mapped afterwards.
+//!
+//! File layout: the magic bytes, the tracked corners and edges (each list
+//! prefixed by its length), then the packed distances.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::ops::Deref;
+use std::path::Path;
+
+use memmap2::Mmap;
+use rubik_cube_core::cube::cubie::{Corner, CubieCube, Edge};
+
+use super::pattern::{Pattern, SlotMoves};
+use crate::solver::face_turns::N_MOVES;
+
+const MAGIC: &[u8; 8] = b"RCPDB\0\0\x01";
+const UNKNOWN: u8 = 0xF;
+
+enum Storage {
+    Memory(Vec<u8>),
+    Mapped { map: Mmap, offset: usize },
+}
+
+impl Deref for Storage {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Storage::Memory(data) => data,
+            Storage::Mapped { map, offset } => &map[*offset..],
+        }
+    }
+}
+
+pub struct PatternDatabase {
+    pattern: Pattern,
+    data: Storage,
+}
+
+fn get(data: &[u8], index: usize) -> u8 {
+    (data[index / 2] >> (index % 2 * 4)) & 0xF
+}
+
+fn set(data: &mut [u8], index: usize, value: u8) {
+    let shift = index % 2 * 4;
+    data[index / 2] = (data[index / 2] & !(0xF << shift)) | (value << shift);
+}
+
+fn header(pattern: &Pattern) -> Vec<u8> {
+    let mut header = MAGIC.to_vec();
+    header.push(pattern.corners().len() as u8);
+    header.extend(pattern.corners().iter().map(|c| c.index() as u8));
+    header.push(pattern.edges().len() as u8);
+    header.extend(pattern.edges().iter().map(|e| e.index() as u8));
+    header
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn parse_header(bytes: &[u8]) -> io::Result<(Pattern, usize)> {
+    if bytes.len() < MAGIC.len() || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(invalid_data("not a pattern database"));
+    }
+    let mut pos = MAGIC.len();
+    let mut read_list = |limit: usize| -> io::Result<Vec<usize>> {
+        let len = *bytes
+            .get(pos)
+            .ok_or_else(|| invalid_data("truncated header"))? as usize;
+        let list = bytes
+            .get(pos + 1..pos + 1 + len)
+            .ok_or_else(|| invalid_data("truncated header"))?
+            .iter()
+            .map(|&b| b as usize)
+            .collect::<Vec<_>>();
+        if len > limit || list.iter().any(|&i| i >= limit) {
+            return Err(invalid_data("bad cubie in header"));
+        }
+        pos += 1 + len;
+        Ok(list)
+    };
+    let corners: Vec<Corner> = read_list(8)?.into_iter().map(Corner::from_index).collect();
+    let edges: Vec<Edge> = read_list(12)?.into_iter().map(Edge::from_index).collect();
+    if corners.is_empty() && edges.is_empty() {
+        return Err(invalid_data("empty pattern"));
+    }
+    let header_len = MAGIC.len() + 2 + corners.len() + edges.len();
+    Ok((Pattern::new(&corners, &edges), header_len))
+}
+
+impl PatternDatabase {
+    /// Breadth-first search over all pattern states, one depth at a time.
+    pub fn generate(pattern: Pattern) -> PatternDatabase {
+        let size = pattern.size();
+        let mut data = vec![0xFF; size.div_ceil(2)];
+        let slot_moves = SlotMoves::new();
+        set(&mut data, pattern.index(&CubieCube::solved()), 0);
+
+        let mut depth = 0;
+        loop {
+            let mut found = 0usize;
+            for index in 0..size {
+                if get(&data, index) != depth {
+                    continue;
+                }
+                let state = pattern.unrank(index);
+                for m in 0..N_MOVES {
+                    let next = pattern.rank(&slot_moves.apply(&pattern, &state, m));
+                    if get(&data, next) == UNKNOWN {
+                        set(&mut data, next, depth + 1);
+                        found += 1;
+                    }
+                }
+            }
+            if found == 0 {
+                break;
+            }
+            depth += 1;
+        }
+
+        PatternDatabase {
+            pattern,
+            data: Storage::Memory(data),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&header(&self.pattern))?;
+        writer.write_all(&self.data)?;
+        writer.flush()
+    }
+
+    /// Memory-maps a database written by [`PatternDatabase::save`].
+    pub fn load(path: &Path) -> io::Result<PatternDatabase> {
+        let file = File::open(path)?;
+        // SAFETY: the file is only ever written by `save`, never while mapped
+        let map = unsafe { Mmap::map(&file)? };
+        let (pattern, offset) = parse_header(&map)?;
+        if map.len() - offset != pattern.size().div_ceil(2) {
+            return Err(invalid_data("pattern database has the wrong size"));
+        }
+        Ok(PatternDatabase {
+            pattern,
+            data: Storage::Mapped { map, offset },
+        })
+    }
+
+    /// Loads the database at `path`, generating and saving it first if the
+    /// file does not exist yet. The new file is written next to `path` and
+    /// renamed into place, so an interrupted run leaves no truncated file.
+    pub fn load_or_generate(path: &Path, pattern: Pattern) -> io::Result<PatternDatabase> {
+        if !path.exists() {
+            let partial = path.with_extension("tmp");
+            PatternDatabase::generate(pattern.clone()).save(&partial)?;
+            fs::rename(&partial, path)?;
+        }
+        let database = PatternDatabase::load(path)?;
+        if database.pattern != pattern {
+            return Err(invalid_data("pattern database tracks other cubies"));
+        }
+        Ok(database)
+    }
+
+    pub fn pattern(&self) -> &Pattern {
+        &self.pattern
+    }
+
+    /// A lower bound on the number of moves needed to solve `cube`
+    pub fn distance(&self, cube: &CubieCube) -> u8 {
+        get(&self.data, self.pattern.index(cube))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::face_turns::move_cubies;
+
+    #[test]
+    fn saved_database_loads_identically() {
+        let pattern = Pattern::new(&[Corner::URF, Corner::UFL], &[Edge::UF, Edge::FR]);
+        let generated = PatternDatabase::generate(pattern.clone());
+        let path = std::env::temp_dir().join(format!("rubik-pdb-test-{}.bin", std::process::id()));
+        generated.save(&path).unwrap();
+        let loaded = PatternDatabase::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.pattern(), &pattern);
+        assert_eq!(&*loaded.data, &*generated.data);
+        assert_eq!(loaded.distance(&CubieCube::solved()), 0);
+        assert_eq!(loaded.distance(&move_cubies()[0]), 1);
+    }
+
+    #[test]
+    fn generates_missing_databases_in_place() {
+        let pattern = Pattern::new(&[Corner::URF], &[Edge::UF]);
+        let path = std::env::temp_dir().join(format!("rubik-pdb-new-{}.bin", std::process::id()));
+        let generated = PatternDatabase::load_or_generate(&path, pattern.clone()).unwrap();
+        let partial_left = path.with_extension("tmp").exists();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!partial_left);
+        assert_eq!(generated.pattern(), &pattern);
+    }
+
+    #[test]
+    fn rejects_foreign_files() {
+        let path = std::env::temp_dir().join(format!("rubik-pdb-bad-{}.bin", std::process::id()));
+        std::fs::write(&path, b"not a database").unwrap();
+        let result = PatternDatabase::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.err().unwrap().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,231 @@
+//! Optimal solver: Korf's IDA* with pattern database heuristics
+//!
+//! Every iteration searches all move sequences up to a bound, pruned by the
+//! largest distance any database reports. The first solution found is
+//! therefore a shortest one.
+
+mod database;
+mod pattern;
+
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use rubik_cube_core::cube::{
+    cubie::{CubieCube, Edge},
+    notation::Move,
+};
+
+pub use database::PatternDatabase;
+pub use pattern::Pattern;
+
+use super::face_turns::{may_follow, move_cubies, move_face, to_move, N_MOVES};
+
+/// No position needs more than 20 face turns.
+const GODS_NUMBER: usize = 20;
+
+/// Checking the flag on every node would slow the search down.
+const CANCEL_CHECK_INTERVAL: u64 = 1 << 14;
+
+/// Shared flag to stop a running search from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimalSolution {
+    /// A shortest solution, or `None` if the search was cancelled first
+    pub moves: Option<Vec<Move>>,
+    /// No solution shorter than this exists
+    pub depth: usize,
+    /// Positions visited over all iterations
+    pub nodes: u64,
+}
+
+pub struct OptimalSolver {
+    databases: Vec<PatternDatabase>,
+}
+
+/// File names and patterns of the standard databases: all corners and two
+/// disjoint sets of six edges
+fn standard_patterns() -> [(&'static str, Pattern); 3] {
+    [
+        ("corners.pdb", Pattern::all_corners()),
+        ("edges-a.pdb", Pattern::new(&[], &Edge::ALL[..6])),
+        ("edges-b.pdb", Pattern::new(&[], &Edge::ALL[6..])),
+    ]
+}
+
+impl OptimalSolver {
+    pub fn new(databases: Vec<PatternDatabase>) -> Self {
+        assert!(
+            !databases.is_empty(),
+            "At least one pattern database is required"
+        );
+        OptimalSolver { databases }
+    }
+
+    /// Memory-maps the standard databases from `dir`, generating any that
+    /// are missing. Generation takes a few minutes and about 90 MB of disk.
+    pub fn from_dir(dir: &Path) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let databases = standard_patterns()
+            .into_iter()
+            .map(|(name, pattern)| PatternDatabase::load_or_generate(&dir.join(name), pattern))
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(OptimalSolver::new(databases))
+    }
+
+    fn heuristic(&self, cube: &CubieCube) -> usize {
+        self.databases
+            .iter()
+            .map(|db| db.distance(cube))
+            .max()
+            .unwrap() as usize
+    }
+
+    pub fn solve(&self, cube: &CubieCube, cancel: &CancelToken) -> OptimalSolution {
+        let mut search = Search {
+            solver: self,
+            cancel,
+            path: vec![],
+            nodes: 0,
+            cancelled: false,
+        };
+
+        let mut bound = self.heuristic(cube);
+        while bound <= GODS_NUMBER {
+            if search.dfs(cube, bound) {
+                return OptimalSolution {
+                    moves: Some(search.path.into_iter().map(to_move).collect()),
+                    depth: bound,
+                    nodes: search.nodes,
+                };
+            }
+            if search.cancelled {
+                break;
+            }
+            bound += 1;
+        }
+        OptimalSolution {
+            moves: None,
+            depth: bound,
+            nodes: search.nodes,
+        }
+    }
+}
+
+struct Search<'a> {
+    solver: &'a OptimalSolver,
+    cancel: &'a CancelToken,
+    path: Vec<usize>,
+    nodes: u64,
+    cancelled: bool,
+}
+
+impl Search<'_> {
+    /// Looks for a solution of exactly `depth` more moves.
+    fn dfs(&mut self, cube: &CubieCube, depth: usize) -> bool {
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(CANCEL_CHECK_INTERVAL) && self.cancel.is_cancelled() {
+            self.cancelled = true;
+            return false;
+        }
+        if depth == 0 {
+            return cube.is_solved();
+        }
+
+        let prev = self.path.last().map(|&m| move_face(m));
+        for m in 0..N_MOVES {
+            if !may_follow(prev, move_face(m)) {
+                continue;
+            }
+            let next = cube.multiply(&move_cubies()[m]);
+            if self.solver.heuristic(&next) >= depth {
+                continue;
+            }
+            self.path.push(m);
+            if self.dfs(&next, depth - 1) {
+                return true;
+            }
+            self.path.pop();
+            if self.cancelled {
+                return false;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use rubik_cube_core::cube::{
+        cubie::Corner,
+        notation::{Algorithm, Move},
+        Cube,
+    };
+
+    use super::*;
+
+    /// Small databases that generate quickly; weaker but still admissible.
+    fn solver() -> &'static OptimalSolver {
+        static SOLVER: OnceLock<OptimalSolver> = OnceLock::new();
+        SOLVER.get_or_init(|| {
+            OptimalSolver::new(vec![
+                PatternDatabase::generate(Pattern::new(&Corner::ALL[..4], &[])),
+                PatternDatabase::generate(Pattern::new(&Corner::ALL[4..], &[])),
+                PatternDatabase::generate(Pattern::new(&[], &Edge::ALL[..4])),
+                PatternDatabase::generate(Pattern::new(&[], &Edge::ALL[4..8])),
+                PatternDatabase::generate(Pattern::new(&[], &Edge::ALL[8..])),
+            ])
+        })
+    }
+
+    fn scrambled(alg: &str) -> CubieCube {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&alg.parse().unwrap());
+        CubieCube::try_from(&cube).unwrap()
+    }
+
+    #[test]
+    fn finds_shortest_solutions() {
+        for (scramble, optimal) in [("", 0), ("R U R' U'", 4), ("F R2 D' B L' U2", 6)] {
+            let cube = scrambled(scramble);
+            let result = solver().solve(&cube, &CancelToken::new());
+            let moves = result.moves.unwrap();
+            assert_eq!(moves.len(), optimal, "{}", scramble);
+            assert_eq!(result.depth, optimal);
+
+            let mut cube = cube.to_cube();
+            cube.apply_algorithm(&Algorithm::from(moves));
+            assert!(cube.is_solved());
+        }
+    }
+
+    #[test]
+    fn stops_when_cancelled() {
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let cube = scrambled("R U2 D' B D' F2 L' U R2 B' D2 L F' U'");
+        let result = solver().solve(&cube, &cancel);
+        assert_eq!(result.moves, None::<Vec<Move>>);
+        assert!(result.depth <= 14);
+        assert!(result.nodes <= CANCEL_CHECK_INTERVAL);
+    }
+}
@@ -0,0 +1,288 @@
+//! Patterns: the subset of cubies a pattern database tracks
+//!
+//! A pattern state is the slot and orientation of every tracked cubie; all
+//! other cubies are ignored. States are ranked to a dense index so a
+//! database is a flat array of distances.
+
+use rubik_cube_core::cube::cubie::{Corner, CubieCube, Edge};
+
+use crate::solver::face_turns::{move_cubies, N_MOVES};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    corners: Vec<Corner>,
+    edges: Vec<Edge>,
+}
+
+/// Slots and orientations of the tracked cubies, in pattern order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PatternState {
+    corner_slots: [u8; 8],
+    corner_twists: [u8; 8],
+    edge_slots: [u8; 12],
+    edge_flips: [u8; 12],
+}
+
+/// `P(n, k)`: arrangements of `k` cubies in `n` slots
+fn arrangements(n: usize, k: usize) -> usize {
+    (n - k + 1..=n).product()
+}
+
+fn rank_slots(slots: &[u8], n: usize) -> usize {
+    let k = slots.len();
+    let mut used = 0u32;
+    let mut rank = 0;
+    for (i, &slot) in slots.iter().enumerate() {
+        let smaller_free = (!used & ((1 << slot) - 1)).count_ones() as usize;
+        rank += smaller_free * arrangements(n - i - 1, k - i - 1);
+        used |= 1 << slot;
+    }
+    rank
+}
+
+fn unrank_slots(mut rank: usize, n: usize, slots: &mut [u8]) {
+    let k = slots.len();
+    let mut used = 0u32;
+    for (i, target) in slots.iter_mut().enumerate() {
+        let weight = arrangements(n - i - 1, k - i - 1);
+        let mut skip = rank / weight;
+        rank %= weight;
+        let mut slot = 0;
+        loop {
+            if used & (1 << slot) == 0 {
+                if skip == 0 {
+                    break;
+                }
+                skip -= 1;
+            }
+            slot += 1;
+        }
+        *target = slot as u8;
+        used |= 1 << slot;
+    }
+}
+
+/// Orientations in base `modulus`. When every cubie of the kind is tracked
+/// the last orientation follows from the others and is left out.
+fn rank_orientations(orientations: &[u8], modulus: u8, complete: bool) -> usize {
+    let free = if complete {
+        &orientations[..orientations.len() - 1]
+    } else {
+        orientations
+    };
+    free.iter()
+        .fold(0, |acc, &o| acc * modulus as usize + o as usize)
+}
+
+fn unrank_orientations(mut rank: usize, modulus: u8, complete: bool, orientations: &mut [u8]) {
+    let k = orientations.len();
+    let free = if complete { k - 1 } else { k };
+    for i in (0..free).rev() {
+        orientations[i] = (rank % modulus as usize) as u8;
+        rank /= modulus as usize;
+    }
+    if complete {
+        let sum: u8 = orientations[..free].iter().sum();
+        orientations[k - 1] = (modulus - sum % modulus) % modulus;
+    }
+}
+
+fn orientation_count(k: usize, modulus: usize, complete: bool) -> usize {
+    modulus.pow(if complete { k as u32 - 1 } else { k as u32 })
+}
+
+impl Pattern {
+    pub fn new(corners: &[Corner], edges: &[Edge]) -> Pattern {
+        assert!(
+            !corners.is_empty() || !edges.is_empty(),
+            "A pattern must track at least one cubie"
+        );
+        Pattern {
+            corners: corners.to_vec(),
+            edges: edges.to_vec(),
+        }
+    }
+
+    /// All eight corners, the classic corner database (88 million states)
+    pub fn all_corners() -> Pattern {
+        Pattern::new(&Corner::ALL, &[])
+    }
+
+    pub fn corners(&self) -> &[Corner] {
+        &self.corners
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    fn complete_corners(&self) -> bool {
+        self.corners.len() == 8
+    }
+
+    fn complete_edges(&self) -> bool {
+        self.edges.len() == 12
+    }
+
+    fn corner_size(&self) -> usize {
+        let k = self.corners.len();
+        if k == 0 {
+            return 1;
+        }
+        arrangements(8, k) * orientation_count(k, 3, self.complete_corners())
+    }
+
+    fn edge_size(&self) -> usize {
+        let k = self.edges.len();
+        if k == 0 {
+            return 1;
+        }
+        arrangements(12, k) * orientation_count(k, 2, self.complete_edges())
+    }
+
+    /// Number of distinct pattern states
+    pub fn size(&self) -> usize {
+        self.corner_size() * self.edge_size()
+    }
+
+    pub(crate) fn state(&self, cube: &CubieCube) -> PatternState {
+        let mut state = PatternState {
+            corner_slots: [0; 8],
+            corner_twists: [0; 8],
+            edge_slots: [0; 12],
+            edge_flips: [0; 12],
+        };
+        for slot in 0..8 {
+            if let Some(i) = self.corners.iter().position(|&c| c == cube.cp[slot]) {
+                state.corner_slots[i] = slot as u8;
+                state.corner_twists[i] = cube.co[slot];
+            }
+        }
+        for slot in 0..12 {
+            if let Some(i) = self.edges.iter().position(|&e| e == cube.ep[slot]) {
+                state.edge_slots[i] = slot as u8;
+                state.edge_flips[i] = cube.eo[slot];
+            }
+        }
+        state
+    }
+
+    pub(crate) fn rank(&self, state: &PatternState) -> usize {
+        let (kc, ke) = (self.corners.len(), self.edges.len());
+        let corner_orientations = orientation_count(kc, 3, self.complete_corners());
+        let edge_orientations = orientation_count(ke, 2, self.complete_edges());
+        let corner = rank_slots(&state.corner_slots[..kc], 8) * corner_orientations
+            + rank_orientations(&state.corner_twists[..kc], 3, self.complete_corners());
+        let edge = rank_slots(&state.edge_slots[..ke], 12) * edge_orientations
+            + rank_orientations(&state.edge_flips[..ke], 2, self.complete_edges());
+        corner * self.edge_size() + edge
+    }
+
+    pub(crate) fn unrank(&self, index: usize) -> PatternState {
+        let (kc, ke) = (self.corners.len(), self.edges.len());
+        let corner_orientations = orientation_count(kc, 3, self.complete_corners());
+        let edge_orientations = orientation_count(ke, 2, self.complete_edges());
+        let (corner, edge) = (index / self.edge_size(), index % self.edge_size());
+        let mut state = PatternState {
+            corner_slots: [0; 8],
+            corner_twists: [0; 8],
+            edge_slots: [0; 12],
+            edge_flips: [0; 12],
+        };
+        unrank_slots(
+            corner / corner_orientations,
+            8,
+            &mut state.corner_slots[..kc],
+        );
+        unrank_orientations(
+            corner % corner_orientations,
+            3,
+            self.complete_corners(),
+            &mut state.corner_twists[..kc],
+        );
+        unrank_slots(edge / edge_orientations, 12, &mut state.edge_slots[..ke]);
+        unrank_orientations(
+            edge % edge_orientations,
+            2,
+            self.complete_edges(),
+            &mut state.edge_flips[..ke],
+        );
+        state
+    }
+
+    pub fn index(&self, cube: &CubieCube) -> usize {
+        self.rank(&self.state(cube))
+    }
+}
+
+/// Where each slot's cubie goes under a face turn, and the orientation it
+/// picks up on the way
+pub(crate) struct SlotMoves {
+    corners: [[(u8, u8); 8]; N_MOVES],
+    edges: [[(u8, u8); 12]; N_MOVES],
+}
+
+impl SlotMoves {
+    pub(crate) fn new() -> SlotMoves {
+        let mut slot_moves = SlotMoves {
+            corners: [[(0, 0); 8]; N_MOVES],
+            edges: [[(0, 0); 12]; N_MOVES],
+        };
+        for (m, mv) in move_cubies().iter().enumerate() {
+            for to in 0..8 {
+                slot_moves.corners[m][mv.cp[to].index()] = (to as u8, mv.co[to]);
+            }
+            for to in 0..12 {
+                slot_moves.edges[m][mv.ep[to].index()] = (to as u8, mv.eo[to]);
+            }
+        }
+        slot_moves
+    }
+
+    pub(crate) fn apply(&self, pattern: &Pattern, state: &PatternState, m: usize) -> PatternState {
+        let mut next = *state;
+        for i in 0..pattern.corners.len() {
+            let (to, twist) = self.corners[m][state.corner_slots[i] as usize];
+            next.corner_slots[i] = to;
+            next.corner_twists[i] = (state.corner_twists[i] + twist) % 3;
+        }
+        for i in 0..pattern.edges.len() {
+            let (to, flip) = self.edges[m][state.edge_slots[i] as usize];
+            next.edge_slots[i] = to;
+            next.edge_flips[i] = (state.edge_flips[i] + flip) % 2;
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_and_unrank_are_inverse() {
+        let patterns = [
+            Pattern::new(&[Corner::URF, Corner::DBL], &[Edge::UF, Edge::BR, Edge::DL]),
+            Pattern::all_corners(),
+            Pattern::new(&[], &Edge::ALL),
+        ];
+        for pattern in patterns {
+            let step = pattern.size() / 997 + 1;
+            for index in (0..pattern.size()).step_by(step) {
+                assert_eq!(pattern.rank(&pattern.unrank(index)), index);
+            }
+        }
+    }
+
+    #[test]
+    fn slot_moves_match_cubie_moves() {
+        let pattern = Pattern::new(&[Corner::UFL, Corner::DRB], &[Edge::UR, Edge::FL]);
+        let slot_moves = SlotMoves::new();
+        let mut cube = CubieCube::solved();
+        for m in [0, 17, 5, 9, 13, 2, 7] {
+            let state = slot_moves.apply(&pattern, &pattern.state(&cube), m);
+            cube = cube.multiply(&move_cubies()[m]);
+            assert_eq!(state, pattern.state(&cube));
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Stepping through a solution move by move, for demonstrations and replays
+
+use rubik_cube_core::cube::{
+    notation::{Algorithm, Move},
+    Cube,
+};
+
+use super::Solution;
+
+/// A move and the state right after it
+#[derive(Debug, Clone)]
+pub struct PlaybackStep {
+    /// Position of the move in the whole solution
+    pub index: usize,
+    pub mv: Move,
+    pub state: Cube,
+}
+
+/// A cursor over the moves of a solution. `position` counts the moves
+/// applied so far, from 0 (the start state) to `len()` (the end state).
+#[derive(Debug, Clone)]
+pub struct Playback {
+    initial: Cube,
+    moves: Vec<Move>,
+    /// Index of the first move of every stage
+    stage_starts: Vec<usize>,
+    position: usize,
+    state: Cube,
+}
+
+impl Playback {
+    /// A single-stage playback of `moves` starting from `initial`
+    pub fn new(initial: Cube, moves: &Algorithm) -> Playback {
+        Playback {
+            state: initial.clone(),
+            initial,
+            moves: moves.moves(),
+            stage_starts: vec![0],
+            position: 0,
+        }
+    }
+
+    pub fn from_solution(solution: &Solution) -> Playback {
+        let mut playback = Playback::new(solution.initial.clone(), &Algorithm::new());
+        playback.stage_starts.clear();
+        for stage in &solution.stages {
+            playback.stage_starts.push(playback.moves.len());
+            playback.moves.extend(stage.moves.moves());
+        }
+        if playback.stage_starts.is_empty() {
+            playback.stage_starts.push(0);
+        }
+        playback
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn state(&self) -> &Cube {
+        &self.state
+    }
+
+    pub fn initial(&self) -> &Cube {
+        &self.initial
+    }
+
+    /// The move that led to the current state, `None` at the start
+    pub fn last_move(&self) -> Option<Move> {
+        self.position.checked_sub(1).map(|index| self.moves[index])
+    }
+
+    /// The stage the next move belongs to; the last stage at the end
+    pub fn stage(&self) -> usize {
+        self.stage_starts
+            .partition_point(|&start| start <= self.position)
+            .saturating_sub(1)
+    }
+
+    pub fn stage_count(&self) -> usize {
+        self.stage_starts.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.moves.len()
+    }
+
+    /// Applies the next move, `None` at the end
+    pub fn step_forward(&mut self) -> Option<PlaybackStep> {
+        let mv = *self.moves.get(self.position)?;
+        self.state.apply_move(mv);
+        self.position += 1;
+        Some(PlaybackStep {
+            index: self.position - 1,
+            mv,
+            state: self.state.clone(),
+        })
+    }
+
+    /// Undoes the last move, false at the start
+    pub fn step_backward(&mut self) -> bool {
+        let Some(mv) = self.last_move() else {
+            return false;
+        };
+        self.state.apply_move(mv.inverse());
+        self.position -= 1;
+        true
+    }
+
+    /// Moves to `position`, clamped to the end
+    pub fn seek(&mut self, position: usize) {
+        let position = position.min(self.moves.len());
+        // 往回退超过一半时从头重放更快
+        if position < self.position && self.position - position > position {
+            self.state = self.initial.clone();
+            self.position = 0;
+        }
+        while self.position < position {
+            self.step_forward();
+        }
+        while self.position > position {
+            self.step_backward();
+        }
+    }
+
+    /// Jumps to the end of the current stage, i.e. the start of the next
+    pub fn next_stage(&mut self) {
+        let next = self
+            .stage_starts
+            .iter()
+            .copied()
+            .find(|&start| start > self.position)
+            .unwrap_or(self.moves.len());
+        self.seek(next);
+    }
+
+    /// Jumps back to the start of the current stage, or of the previous
+    /// one when already there
+    pub fn previous_stage(&mut self) {
+        let previous = self
+            .stage_starts
+            .iter()
+            .rev()
+            .copied()
+            .find(|&start| start < self.position)
+            .unwrap_or(0);
+        self.seek(previous);
+    }
+}
+
+impl Iterator for Playback {
+    type Item = PlaybackStep;
+
+    fn next(&mut self) -> Option<PlaybackStep> {
+        self.step_forward()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{execute_with_method, SolveMethod, SolveTarget};
+
+    fn solved_playback() -> Playback {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&"R U2 F' L D2 B R2 U'".parse().unwrap());
+        let solution = execute_with_method(&mut cube, SolveTarget::Pll, SolveMethod::Cfop).unwrap();
+        Playback::from_solution(&solution)
+    }
+
+    #[test]
+    fn yields_every_move_with_its_state() {
+        let alg: Algorithm = "R U R' y M2".parse().unwrap();
+        let steps: Vec<PlaybackStep> = Playback::new(Cube::new(), &alg).collect();
+        assert_eq!(steps.len(), 5);
+        let mut cube = Cube::new();
+        for (index, (step, mv)) in steps.iter().zip(alg.moves()).enumerate() {
+            cube.apply_move(mv);
+            assert_eq!(step.index, index);
+            assert_eq!(step.mv, mv);
+            assert_eq!(step.state, cube);
+        }
+    }
+
+    #[test]
+    fn seeks_and_steps_both_ways() {
+        let mut playback = solved_playback();
+        let states: Vec<Cube> = std::iter::once(playback.initial().clone())
+            .chain(playback.clone().map(|step| step.state))
+            .collect();
+        for position in [5, 2, playback.len(), 1, 0, playback.len() + 10] {
+            playback.seek(position);
+            let position = position.min(playback.len());
+            assert_eq!(playback.position(), position);
+            assert_eq!(playback.state(), &states[position]);
+        }
+        assert!(playback.is_finished() && playback.state().is_solved());
+        assert!(playback.step_forward().is_none());
+        assert!(playback.step_backward());
+        assert_eq!(playback.state(), &states[playback.len() - 1]);
+    }
+
+    #[test]
+    fn jumps_between_stages() {
+        let mut playback = solved_playback();
+        assert_eq!(playback.stage(), 0);
+        let mut boundaries = vec![];
+        while !playback.is_finished() {
+            playback.next_stage();
+            boundaries.push(playback.position());
+        }
+        assert_eq!(boundaries.len(), playback.stage_count());
+        assert!(boundaries.windows(2).all(|pair| pair[0] < pair[1]));
+
+        playback.previous_stage();
+        assert_eq!(playback.stage(), playback.stage_count() - 1);
+        playback.step_forward();
+        playback.previous_stage();
+        assert_eq!(playback.stage(), playback.stage_count() - 1);
+        playback.previous_stage();
+        assert_eq!(playback.stage(), playback.stage_count() - 2);
+    }
+}
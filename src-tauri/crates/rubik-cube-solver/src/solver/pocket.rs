@@ -0,0 +1,427 @@
+//! Optimal 2x2 solver: the distance of every state, looked up
+//!
+//! Holding the down-back-left corner still leaves 7! · 3^6 = 3,674,160
+//! states, all reached by turning U, R and F. A breadth-first search from
+//! solved stores each state's distance, packed two per byte, on first use
+//! of a metric. A shortest solution then just follows moves that bring the
+//! distance down by one.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use rubik_cube_core::cube::{
+    color::Color,
+    cubie::{Corner, CubieCube},
+    error::CubeError,
+    face::Face,
+    notation::{Metric, Move},
+    nxn::NxnCube,
+    scheme::ColorScheme,
+};
+
+use super::face_turns::{move_cubies, to_move};
+
+/// The corner slots that move, DBL left out
+const SLOTS: [Corner; 7] = [
+    Corner::URF,
+    Corner::UFL,
+    Corner::ULB,
+    Corner::UBR,
+    Corner::DFR,
+    Corner::DLF,
+    Corner::DRB,
+];
+
+const N_PERM: usize = 5040;
+const N_TWIST: usize = 729;
+const N_STATES: usize = N_PERM * N_TWIST;
+
+/// U, F and R turns in [`to_move`] numbering
+const MOVES: [usize; 9] = [0, 1, 2, 6, 7, 8, 15, 16, 17];
+/// The quarter turns among [`MOVES`]
+const QUARTER_MOVES: [usize; 6] = [0, 2, 3, 5, 6, 8];
+const UNKNOWN: u8 = 0xF;
+
+/// A 2x2 as the corners of a [`CubieCube`] with DBL in place. Stickers
+/// are read relative to the DBL corner, so the whole-cube orientation
+/// does not matter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PocketCube(CubieCube);
+
+impl PocketCube {
+    pub fn solved() -> PocketCube {
+        PocketCube(CubieCube::solved())
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.0.is_solved()
+    }
+
+    /// Turns U, R or F; other faces would move the DBL corner.
+    ///
+    /// # Panics
+    ///
+    /// If `mv` is not a turn of U, R or F.
+    pub fn apply_move(&mut self, mv: Move) {
+        let m = MOVES
+            .into_iter()
+            .find(|&m| to_move(m) == mv)
+            .unwrap_or_else(|| panic!("{} moves the DBL corner", mv));
+        self.0 = self.0.multiply(&move_cubies()[m]);
+    }
+
+    /// The state's number, below 3,674,160
+    pub fn index(&self) -> usize {
+        perm_index(&self.0) * N_TWIST + twist_index(&self.0)
+    }
+
+    pub fn from_index(index: usize) -> PocketCube {
+        let mut cube = CubieCube::solved();
+        set_perm(&mut cube, index / N_TWIST);
+        set_twist(&mut cube, index % N_TWIST);
+        PocketCube(cube)
+    }
+}
+
+impl TryFrom<&NxnCube> for PocketCube {
+    type Error = CubeError;
+
+    fn try_from(cube: &NxnCube) -> Result<Self, Self::Error> {
+        if cube.size() != 2 {
+            return Err(CubeError::WrongSize {
+                size: cube.size(),
+                expected: 2,
+            });
+        }
+        // 3x3 的角块贴纸坐标 0、2 对应二阶的 0、1
+        let colors = |corner: Corner| {
+            corner
+                .facelets()
+                .map(|(face, row, col)| cube.get_block_color(face.ordinal(), row / 2, col / 2))
+        };
+        // DBL 角块的三种颜色确定 D、B、L 三个面，对面的颜色从其他角块推出
+        let scheme = ColorScheme::from_corners(cube)?;
+        let face_of = |color: Color| scheme.face(color);
+
+        let mut state = CubieCube::solved();
+        let mut seen: [Option<Corner>; 8] = [None; 8];
+        for position in Corner::ALL {
+            let colors = colors(position);
+            let faces = colors.map(face_of);
+            let twist = faces
+                .iter()
+                .position(|f| *f == Face::Up || *f == Face::Down);
+            let cubie = twist.and_then(|t| {
+                let rotated = [faces[t], faces[(t + 1) % 3], faces[(t + 2) % 3]];
+                Corner::ALL.into_iter().find(|c| c.faces() == rotated)
+            });
+            let (Some(twist), Some(cubie)) = (twist, cubie) else {
+                return Err(CubeError::InvalidCorner { position, colors });
+            };
+            if let Some(other) = seen[cubie.index()] {
+                return Err(CubeError::DuplicateCorner {
+                    corner: cubie,
+                    positions: [other, position],
+                });
+            }
+            seen[cubie.index()] = Some(position);
+            state.cp[position.index()] = cubie;
+            state.co[position.index()] = twist as u8;
+        }
+        let twist: u32 = state.co.iter().map(|&t| t as u32).sum();
+        if !twist.is_multiple_of(3) {
+            return Err(CubeError::CornerTwist {
+                sum: (twist % 3) as u8,
+            });
+        }
+        Ok(PocketCube(state))
+    }
+}
+
+fn slot_number(corner: Corner) -> usize {
+    SLOTS.iter().position(|&c| c == corner).unwrap()
+}
+
+/// Lehmer code of the seven moving corners
+fn perm_index(cube: &CubieCube) -> usize {
+    let perm = SLOTS.map(|slot| slot_number(cube.cp[slot.index()]));
+    let mut index = 0;
+    for i in 0..7 {
+        let smaller = perm[i + 1..].iter().filter(|&&p| p < perm[i]).count();
+        index = index * (7 - i) + smaller;
+    }
+    index
+}
+
+fn set_perm(cube: &mut CubieCube, mut index: usize) {
+    let mut digits = [0; 7];
+    for i in (0..7).rev() {
+        digits[i] = index % (7 - i);
+        index /= 7 - i;
+    }
+    let mut left: Vec<usize> = (0..7).collect();
+    for (slot, digit) in SLOTS.into_iter().zip(digits) {
+        cube.cp[slot.index()] = SLOTS[left.remove(digit)];
+    }
+}
+
+/// Twists of the first six moving corners, the seventh follows from them
+fn twist_index(cube: &CubieCube) -> usize {
+    SLOTS[..6]
+        .iter()
+        .fold(0, |index, slot| index * 3 + cube.co[slot.index()] as usize)
+}
+
+fn set_twist(cube: &mut CubieCube, mut index: usize) {
+    let mut sum = 0;
+    for slot in SLOTS[..6].iter().rev() {
+        cube.co[slot.index()] = (index % 3) as u8;
+        sum += index % 3;
+        index /= 3;
+    }
+    cube.co[Corner::DRB.index()] = ((3 - sum % 3) % 3) as u8;
+}
+
+fn get(data: &[u8], index: usize) -> u8 {
+    (data[index / 2] >> (index % 2 * 4)) & 0xF
+}
+
+fn set(data: &mut [u8], index: usize, value: u8) {
+    let shift = index % 2 * 4;
+    data[index / 2] = (data[index / 2] & !(0xF << shift)) | (value << shift);
+}
+
+/// Where each of [`MOVES`] takes every permutation and every twist
+struct MoveTables {
+    perm: Vec<[u16; 9]>,
+    twist: Vec<[u16; 9]>,
+}
+
+fn move_tables() -> &'static MoveTables {
+    static TABLES: OnceLock<MoveTables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let perm = (0..N_PERM)
+            .map(|index| {
+                let mut cube = CubieCube::solved();
+                set_perm(&mut cube, index);
+                MOVES.map(|m| perm_index(&cube.multiply(&move_cubies()[m])) as u16)
+            })
+            .collect();
+        let twist = (0..N_TWIST)
+            .map(|index| {
+                let mut cube = CubieCube::solved();
+                set_twist(&mut cube, index);
+                MOVES.map(|m| twist_index(&cube.multiply(&move_cubies()[m])) as u16)
+            })
+            .collect();
+        MoveTables { perm, twist }
+    })
+}
+
+/// The state `m`, a position in [`MOVES`], leads to
+fn next_index(index: usize, m: usize) -> usize {
+    let tables = move_tables();
+    let perm = tables.perm[index / N_TWIST][m] as usize;
+    let twist = tables.twist[index % N_TWIST][m] as usize;
+    perm * N_TWIST + twist
+}
+
+/// Positions in [`MOVES`] of the moves `metric` counts as one
+fn metric_moves(metric: Metric) -> &'static [usize] {
+    match metric {
+        Metric::Qtm => &QUARTER_MOVES,
+        // 二阶没有中层，STM 与 HTM 相同
+        Metric::Htm | Metric::Stm => &[0, 1, 2, 3, 4, 5, 6, 7, 8],
+    }
+}
+
+fn distances(metric: Metric) -> &'static [u8] {
+    static HTM: OnceLock<Vec<u8>> = OnceLock::new();
+    static QTM: OnceLock<Vec<u8>> = OnceLock::new();
+    let table = if metric == Metric::Qtm { &QTM } else { &HTM };
+    table.get_or_init(|| {
+        let mut data = vec![0xFF; N_STATES.div_ceil(2)];
+        let solved = PocketCube::solved().index();
+        set(&mut data, solved, 0);
+        let mut frontier = vec![solved as u32];
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            depth += 1;
+            let mut next = vec![];
+            for &index in &frontier {
+                for &m in metric_moves(metric) {
+                    let to = next_index(index as usize, m);
+                    if get(&data, to) == UNKNOWN {
+                        set(&mut data, to, depth);
+                        next.push(to as u32);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        data
+    })
+}
+
+/// Shortest 2x2 solutions in one metric. The distance table of each
+/// metric takes about a second and 1.8 MB the first time it is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PocketSolver {
+    metric: Metric,
+}
+
+impl PocketSolver {
+    pub fn new(metric: Metric) -> PocketSolver {
+        PocketSolver { metric }
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    /// The length of a shortest solution
+    pub fn distance(&self, cube: &PocketCube) -> usize {
+        get(distances(self.metric), cube.index()) as usize
+    }
+
+    /// A shortest solution, turning only U, R and F
+    pub fn solve(&self, cube: &PocketCube) -> Vec<Move> {
+        let table = distances(self.metric);
+        let mut index = cube.index();
+        let mut moves = vec![];
+        while get(table, index) > 0 {
+            let depth = get(table, index);
+            let m = *metric_moves(self.metric)
+                .iter()
+                .find(|&&m| get(table, next_index(index, m)) == depth - 1)
+                .unwrap();
+            moves.push(to_move(MOVES[m]));
+            index = next_index(index, m);
+        }
+        moves
+    }
+
+    /// How many different move sequences of the shortest length solve the
+    /// cube. In QTM `R R` and `R' R'` count as two.
+    pub fn count_solutions(&self, cube: &PocketCube) -> u64 {
+        let mut counts = HashMap::new();
+        self.count_from(cube.index(), &mut counts)
+    }
+
+    fn count_from(&self, index: usize, counts: &mut HashMap<usize, u64>) -> u64 {
+        let table = distances(self.metric);
+        let depth = get(table, index);
+        if depth == 0 {
+            return 1;
+        }
+        if let Some(&count) = counts.get(&index) {
+            return count;
+        }
+        let count = metric_moves(self.metric)
+            .iter()
+            .map(|&m| next_index(index, m))
+            .filter(|&next| get(table, next) == depth - 1)
+            .map(|next| self.count_from(next, counts))
+            .sum();
+        counts.insert(index, count);
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rubik_cube_core::cube::notation::Algorithm;
+
+    use super::*;
+
+    fn scrambled(alg: &str) -> NxnCube {
+        let mut cube = NxnCube::new(2);
+        cube.apply_algorithm(&alg.parse().unwrap());
+        cube
+    }
+
+    #[test]
+    fn indices_round_trip() {
+        for index in [0, 1, 728, 729, 1_000_000, N_STATES - 1] {
+            assert_eq!(PocketCube::from_index(index).index(), index);
+        }
+        let cube = PocketCube::try_from(&scrambled("R U2 F' R'")).unwrap();
+        assert_eq!(PocketCube::from_index(cube.index()), cube);
+        assert_eq!(
+            PocketCube::try_from(&NxnCube::new(3)),
+            Err(CubeError::WrongSize {
+                size: 3,
+                expected: 2
+            })
+        );
+    }
+
+    #[test]
+    fn every_state_is_reached_within_gods_number() {
+        for (metric, gods_number) in [(Metric::Htm, 11), (Metric::Qtm, 14)] {
+            let table = distances(metric);
+            let deepest = (0..N_STATES).map(|i| get(table, i)).max().unwrap();
+            assert_eq!(deepest, gods_number);
+        }
+    }
+
+    #[test]
+    fn solves_optimally_in_any_orientation() {
+        let alg = "R U' F2 U R' F U2 R2 F' U";
+        for (metric, longest) in [(Metric::Htm, 10), (Metric::Qtm, 13)] {
+            let solver = PocketSolver::new(metric);
+            for (prefix, scheme) in [
+                ("", ColorScheme::WESTERN),
+                ("x y2", ColorScheme::WESTERN),
+                ("z' y", ColorScheme::JAPANESE),
+            ] {
+                let mut cube = NxnCube::with_scheme(2, scheme);
+                cube.apply_algorithm(&format!("{} {}", prefix, alg).parse().unwrap());
+                let pocket = PocketCube::try_from(&cube).unwrap();
+                let moves = solver.solve(&pocket);
+                assert_eq!(moves.len(), solver.distance(&pocket));
+                assert!(moves.len() <= longest);
+                cube.apply_algorithm(&Algorithm::from(moves));
+                assert!(cube.is_solved());
+            }
+        }
+    }
+
+    #[test]
+    fn counts_optimal_solutions() {
+        let count = |metric, alg: &str| {
+            let cube = PocketCube::try_from(&scrambled(alg)).unwrap();
+            PocketSolver::new(metric).count_solutions(&cube)
+        };
+        assert_eq!(count(Metric::Htm, ""), 1);
+        assert_eq!(count(Metric::Htm, "R U"), 1);
+        assert_eq!(count(Metric::Htm, "R2"), 1);
+        assert_eq!(count(Metric::Qtm, "R2"), 2);
+        // 相对 DBL 角块，D 与 U 的效果相同，U D 相当于 U2
+        assert_eq!(count(Metric::Htm, "U D"), 1);
+        assert_eq!(count(Metric::Qtm, "U D"), 2);
+        assert!(count(Metric::Htm, "R U R' U' F2") >= 1);
+    }
+
+    #[test]
+    fn rejects_impossible_stickers() {
+        let mut cube = NxnCube::new(2);
+        cube.set_block_color(Face::Up.ordinal(), 1, 1, Color::Red);
+        cube.set_block_color(Face::Right.ordinal(), 0, 0, Color::Blue);
+        cube.set_block_color(Face::Front.ordinal(), 0, 1, Color::Yellow);
+        assert!(matches!(
+            PocketCube::try_from(&cube),
+            Err(CubeError::CornerTwist { .. })
+        ));
+
+        let mut cube = NxnCube::new(2);
+        cube.set_block_color(Face::Down.ordinal(), 1, 0, Color::Green);
+        assert!(matches!(
+            PocketCube::try_from(&cube),
+            Err(CubeError::InvalidCorner {
+                position: Corner::DBL,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,289 @@
+//! Pure 3-cycles of the centre pieces or the edge wings of a big cube
+//!
+//! A commutator `X Y X' Y'` whose two halves share a single piece cycles
+//! three pieces and leaves everything else alone. One such commutator is
+//! found per orbit by trying short ones; setup moves found by a
+//! breadth-first search over where the three pieces start then turn it into
+//! a cycle of any three slots of the orbit.
+
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use rubik_cube_core::cube::{
+    color::Color,
+    face::Face,
+    notation::{Move, MoveKind, Turn},
+    nxn::{move_permutation, sticker_cubie, NxnCube},
+};
+
+const TURNS: [Turn; 3] = [Turn::Clockwise, Turn::Double, Turn::CounterClockwise];
+
+/// Outer and single inner layer turns, the moves setups are made of
+fn layer_turns(size: usize) -> Vec<Move> {
+    let mut moves = vec![];
+    for face in Face::values() {
+        for depth in 1..size as u8 {
+            let kind = if depth == 1 {
+                MoveKind::Face(face)
+            } else {
+                MoveKind::Layer(face, depth)
+            };
+            moves.extend(TURNS.map(|turn| Move::new(kind, turn)));
+        }
+    }
+    moves
+}
+
+/// The stickers sharing a cubie with sticker `index`, `index` first
+pub(super) fn piece(size: usize, index: usize) -> Vec<usize> {
+    let cubie = sticker_cubie(size, index);
+    let mut stickers = vec![index];
+    stickers
+        .extend((0..6 * size * size).filter(|&i| i != index && sticker_cubie(size, i) == cubie));
+    stickers
+}
+
+/// Where the piece at each sticker ends up after `moves`
+fn net_permutation(size: usize, moves: &[Move]) -> Vec<usize> {
+    let mut position: Vec<usize> = (0..6 * size * size).collect();
+    for &mv in moves {
+        let table = move_permutation(size, mv);
+        for p in position.iter_mut() {
+            *p = table[*p];
+        }
+    }
+    position
+}
+
+fn inverse(moves: &[Move]) -> Vec<Move> {
+    moves.iter().rev().map(Move::inverse).collect()
+}
+
+/// The slots one kind of piece can move between, e.g. the 24 wings of a
+/// 4x4, and a 3-cycle through a fixed buffer slot for every pair of the
+/// other slots
+pub(super) struct Orbit {
+    /// The stickers of every slot, ordered alike: a move takes the n-th
+    /// sticker of one slot to the n-th sticker of another
+    slots: Vec<Vec<usize>>,
+    buffer: usize,
+    /// `cycles[from * slots + to]` takes the piece at `from` to `to`, the
+    /// one at `to` to the buffer and the buffer's to `from`
+    cycles: Vec<Vec<Move>>,
+}
+
+impl Orbit {
+    /// The orbit of the piece with sticker `reference`, with the slot the
+    /// `buffer` sticker belongs to as buffer
+    ///
+    /// # Panics
+    ///
+    /// If no short commutator cycles three pieces of the orbit.
+    pub(super) fn new(size: usize, reference: usize, buffer: usize) -> Orbit {
+        let turns = layer_turns(size);
+        let tables: Vec<&[usize]> = turns.iter().map(|&mv| move_permutation(size, mv)).collect();
+
+        let mut slots = vec![piece(size, reference)];
+        let mut slot_of: HashMap<usize, usize> =
+            slots[0].iter().map(|&sticker| (sticker, 0)).collect();
+        let mut queue = VecDeque::from([0]);
+        while let Some(slot) = queue.pop_front() {
+            for table in &tables {
+                let image: Vec<usize> = slots[slot].iter().map(|&s| table[s]).collect();
+                if !slot_of.contains_key(&image[0]) {
+                    for &sticker in &image {
+                        slot_of.insert(sticker, slots.len());
+                    }
+                    queue.push_back(slots.len());
+                    slots.push(image);
+                }
+            }
+        }
+        let slot_perms: Vec<Vec<usize>> = tables
+            .iter()
+            .map(|table| slots.iter().map(|s| slot_of[&table[s[0]]]).collect())
+            .collect();
+
+        let (commutator, cycle) = find_commutator(size, &turns, &slots, &slot_of)
+            .expect("No commutator cycles three pieces of the orbit");
+
+        // 以 [X, Y] 和它的逆为起点，每个状态是三个块的位置，找到把任意三个
+        // 位置轮换起来的最短前置步骤
+        let bases = [commutator.clone(), inverse(&commutator)];
+        let mut found: HashMap<[usize; 3], (usize, Vec<Move>)> = HashMap::new();
+        let mut queue = VecDeque::new();
+        let [a, b, c] = cycle;
+        for (base, triple) in [(0, [a, b, c]), (1, [a, c, b])] {
+            for rotation in 0..3 {
+                let mut triple = triple;
+                triple.rotate_left(rotation);
+                found.insert(triple, (base, vec![]));
+                queue.push_back(triple);
+            }
+        }
+        while let Some(triple) = queue.pop_front() {
+            let (base, setup) = found[&triple].clone();
+            for (mv, perm) in turns.iter().zip(&slot_perms) {
+                let next = triple.map(|slot| perm[slot]);
+                if let Entry::Vacant(entry) = found.entry(next) {
+                    let mut next_setup = vec![mv.inverse()];
+                    next_setup.extend(&setup);
+                    entry.insert((base, next_setup));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let buffer = slot_of[&buffer];
+        let count = slots.len();
+        let mut cycles = vec![vec![]; count * count];
+        for from in 0..count {
+            for to in 0..count {
+                if from == to || from == buffer || to == buffer {
+                    continue;
+                }
+                let (base, setup) = &found[&[from, to, buffer]];
+                let mut moves = setup.clone();
+                moves.extend(&bases[*base]);
+                moves.extend(inverse(setup));
+                cycles[from * count + to] = moves;
+            }
+        }
+        Orbit {
+            slots,
+            buffer,
+            cycles,
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub(super) fn buffer(&self) -> usize {
+        self.buffer
+    }
+
+    pub(super) fn stickers(&self, slot: usize) -> &[usize] {
+        &self.slots[slot]
+    }
+
+    /// The colours of the piece in `slot`, in the slot's sticker order
+    pub(super) fn piece(&self, stickers: &[Color], slot: usize) -> Vec<Color> {
+        self.slots[slot].iter().map(|&s| stickers[s]).collect()
+    }
+
+    /// Moves the pieces until every slot in `order` holds what `target`
+    /// asks for when its turn comes; the buffer ends up with what is left.
+    /// `None` if a piece asked for is nowhere to be found.
+    pub(super) fn solve(
+        &self,
+        cube: &mut NxnCube,
+        order: &[usize],
+        target: impl Fn(&[Color], usize) -> Vec<Color>,
+    ) -> Option<Vec<Move>> {
+        let mut moves = vec![];
+        let mut locked = vec![false; self.len()];
+        locked[self.buffer] = true;
+        for &slot in order.iter().filter(|&&slot| slot != self.buffer) {
+            let stickers = flatten(cube);
+            let wanted = target(&stickers, slot);
+            if self.piece(&stickers, slot) != wanted {
+                let cycle = self.cycle_to(&stickers, &locked, slot, &wanted)?;
+                for &mv in cycle {
+                    cube.apply_move(mv);
+                }
+                moves.extend(cycle);
+            }
+            locked[slot] = true;
+        }
+        Some(moves)
+    }
+
+    /// The shortest cycle bringing `wanted` into `slot` without touching
+    /// the locked slots other than as described below
+    fn cycle_to(
+        &self,
+        stickers: &[Color],
+        locked: &[bool],
+        slot: usize,
+        wanted: &[Color],
+    ) -> Option<&[Move]> {
+        let count = self.len();
+        let cycle = |from: usize, to: usize| self.cycles[from * count + to].as_slice();
+        if let Some(from) = (0..count)
+            .filter(|&s| !locked[s] && s != slot && self.piece(stickers, s) == wanted)
+            .min_by_key(|&s| cycle(s, slot).len())
+        {
+            return Some(cycle(from, slot));
+        }
+        if self.piece(stickers, self.buffer) != wanted {
+            return None;
+        }
+        // 需要的块在缓冲位：slot 的块送到另一个位置，再由缓冲位补上。
+        // 没有空位时借用一个与 slot 现有颜色相同的已还原位置，相同颜色的
+        // 中心块互换不影响结果
+        let current = self.piece(stickers, slot);
+        (0..count)
+            .filter(|&s| s != slot && !locked[s])
+            .min_by_key(|&s| cycle(slot, s).len())
+            .or_else(|| {
+                (0..count)
+                    .find(|&s| s != slot && s != self.buffer && self.piece(stickers, s) == current)
+            })
+            .map(|to| cycle(slot, to))
+    }
+}
+
+/// The cube's stickers numbered like [`move_permutation`]
+pub(super) fn flatten(cube: &NxnCube) -> Vec<Color> {
+    cube.state().iter().flatten().flatten().copied().collect()
+}
+
+/// A commutator `X Y X' Y'` with `X` an inner layer turn and `Y` a layer
+/// turn conjugated by a face turn, cycling three slots of the orbit and
+/// nothing else, with the slots it cycles in order
+fn find_commutator(
+    size: usize,
+    turns: &[Move],
+    slots: &[Vec<usize>],
+    slot_of: &HashMap<usize, usize>,
+) -> Option<(Vec<Move>, [usize; 3])> {
+    let inner = turns
+        .iter()
+        .filter(|mv| matches!(mv.kind, MoveKind::Layer(..)));
+    let outer: Vec<Option<Move>> = std::iter::once(None)
+        .chain(
+            turns
+                .iter()
+                .filter(|mv| matches!(mv.kind, MoveKind::Face(_)))
+                .map(|&mv| Some(mv)),
+        )
+        .collect();
+    let piece_size = slots[0].len();
+    for &x in inner {
+        for &setup in &outer {
+            for &y in turns {
+                let mut half: Vec<Move> = setup.into_iter().collect();
+                half.push(y);
+                half.extend(setup.map(|mv| mv.inverse()));
+                let mut moves = vec![x];
+                moves.extend(&half);
+                moves.push(x.inverse());
+                moves.extend(inverse(&half));
+
+                let perm = net_permutation(size, &moves);
+                let moved: Vec<usize> = (0..perm.len()).filter(|&i| perm[i] != i).collect();
+                if moved.len() != 3 * piece_size || moved.iter().any(|s| !slot_of.contains_key(s)) {
+                    continue;
+                }
+                let a = slot_of[&moved[0]];
+                let b = slot_of[&perm[slots[a][0]]];
+                let c = slot_of[&perm[slots[b][0]]];
+                if slot_of.get(&perm[slots[c][0]]) == Some(&a) {
+                    return Some((moves, [a, b, c]));
+                }
+            }
+        }
+    }
+    None
+}
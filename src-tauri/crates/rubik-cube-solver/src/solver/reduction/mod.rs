@@ -0,0 +1,589 @@
+//! Reduction for 4x4 and 5x5 cubes
+//!
+//! The centre pieces of every face are brought together and the edge
+//! pieces paired up, after which the cube turns like a 3x3 whose middle
+//! layer is all the inner layers. The reduced cube is then solved by the
+//! 3x3 methods, with their slice and wide turns widened to match.
+//!
+//! Both steps move a few pieces at a time with the commutators of
+//! [`cycles`]. An even cube can reduce to a state no 3x3 can be in: a
+//! single flipped edge (OLL parity) or two swapped edges (PLL parity). Each
+//! gets its own algorithm before the 3x3 stages.
+
+mod cycles;
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use rubik_cube_core::cube::{
+    color::Color,
+    error::CubeError,
+    face::Face,
+    notation::{Algorithm, Move, MoveKind, Slice},
+    nxn::{sticker_index, NxnCube},
+    scheme::ColorScheme,
+    Cube,
+};
+
+use cycles::{flatten, Orbit};
+
+use super::{execute_with_method, SolveError, SolveMethod, SolveTarget};
+
+/// Swaps the two wings of the UF edge, which flips the paired edge on a
+/// 4x4 and fixes the last edge of a 5x5
+const OLL_PARITY: &str = "2R2 B2 U2 2L U2 2R' U2 2R U2 F2 2R F2 2L' B2 2R2";
+
+/// Swaps the UF and UB edges of a 4x4
+const PLL_PARITY: &str = "2R2 U2 2R2 Uw2 2R2 2U2";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReductionPhase {
+    Centers,
+    Edges,
+    OllParity,
+    PllParity,
+    /// A stage of the 3x3 method solving the reduced cube
+    Stage(SolveTarget),
+}
+
+impl fmt::Display for ReductionPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReductionPhase::Centers => write!(f, "centers"),
+            ReductionPhase::Edges => write!(f, "edges"),
+            ReductionPhase::OllParity => write!(f, "OLL parity"),
+            ReductionPhase::PllParity => write!(f, "PLL parity"),
+            ReductionPhase::Stage(target) => write!(f, "{:?}", target),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReductionStage {
+    pub phase: ReductionPhase,
+    /// The case the 3x3 solver recognized, e.g. "OLL 27 / Sune"
+    pub label: Option<String>,
+    pub moves: Algorithm,
+    pub state_before: NxnCube,
+    pub state_after: NxnCube,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReductionSolution {
+    pub initial: NxnCube,
+    /// Phases that were already done are left out.
+    pub stages: Vec<ReductionStage>,
+}
+
+impl ReductionSolution {
+    /// All stages' moves in order
+    pub fn moves(&self) -> Algorithm {
+        let mut moves = Algorithm::new();
+        for stage in &self.stages {
+            moves.extend(stage.moves.clone());
+        }
+        moves
+    }
+
+    pub fn final_state(&self) -> &NxnCube {
+        self.stages
+            .last()
+            .map_or(&self.initial, |stage| &stage.state_after)
+    }
+
+    fn push(
+        &mut self,
+        phase: ReductionPhase,
+        label: Option<String>,
+        moves: Vec<Move>,
+        cube: &NxnCube,
+    ) {
+        if moves.is_empty() {
+            return;
+        }
+        let state_before = self.final_state().clone();
+        self.stages.push(ReductionStage {
+            phase,
+            label,
+            moves: Algorithm::from(moves),
+            state_before,
+            state_after: cube.clone(),
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ReductionError {
+    /// Only 4x4 and 5x5 cubes are reduced
+    UnsupportedSize(usize),
+    /// Some pieces of the phase are missing and others appear twice
+    InvalidPieces(ReductionPhase),
+    /// The reduced cube could not be solved as a 3x3
+    Solve(SolveError),
+}
+
+impl From<SolveError> for ReductionError {
+    fn from(error: SolveError) -> Self {
+        ReductionError::Solve(error)
+    }
+}
+
+impl fmt::Display for ReductionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReductionError::UnsupportedSize(size) => {
+                write!(
+                    f,
+                    "only 4x4 and 5x5 cubes can be reduced, not {0}x{0}",
+                    size
+                )
+            }
+            ReductionError::InvalidPieces(phase) => {
+                write!(f, "the {} pieces cannot come from a real cube", phase)
+            }
+            ReductionError::Solve(error) => write!(f, "reduced cube: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ReductionError {}
+
+/// The orbits of one size, built on first use
+struct SizeOrbits {
+    centers: Vec<Orbit>,
+    wings: Orbit,
+}
+
+fn orbits(size: usize) -> &'static SizeOrbits {
+    static ORBITS: [OnceLock<SizeOrbits>; 6] = [const { OnceLock::new() }; 6];
+    ORBITS[size].get_or_init(|| {
+        let n = size;
+        // 中心块按面 U D F B L R 的顺序还原，缓冲位放在最后的 R 面
+        let mut centers: Vec<Orbit> = vec![];
+        for row in 1..n - 1 {
+            for col in 1..n - 1 {
+                let sticker = sticker_index(n, Face::Up, row, col);
+                let known = centers
+                    .iter()
+                    .any(|orbit| (0..orbit.len()).any(|slot| orbit.stickers(slot)[0] == sticker));
+                if known || (n % 2 == 1 && row == n / 2 && col == n / 2) {
+                    continue;
+                }
+                let buffer = sticker_index(n, Face::Right, row, col);
+                centers.push(Orbit::new(n, sticker, buffer));
+            }
+        }
+        // 翼块以 UF 棱为参照，缓冲位在 UB 棱上
+        let wings = Orbit::new(
+            n,
+            sticker_index(n, Face::Up, n - 1, 1),
+            sticker_index(n, Face::Up, 0, 1),
+        );
+        SizeOrbits { centers, wings }
+    })
+}
+
+/// Reduces the cube and solves it with `method` up to `target`, like
+/// [`execute_with_method`] does for a 3x3. The centres take the colours the
+/// cube itself shows, see [`NxnCube::scheme`], so no rotation is needed
+/// and any colour scheme works.
+pub fn execute_reduction(
+    cube: &mut NxnCube,
+    target: SolveTarget,
+    method: SolveMethod,
+) -> Result<ReductionSolution, ReductionError> {
+    let size = cube.size();
+    if size != 4 && size != 5 {
+        return Err(ReductionError::UnsupportedSize(size));
+    }
+    let mut solution = ReductionSolution {
+        initial: cube.clone(),
+        stages: vec![],
+    };
+    let orbits = orbits(size);
+
+    let scheme = cube
+        .scheme()
+        .map_err(|_| ReductionError::InvalidPieces(ReductionPhase::Centers))?;
+    let mut moves = vec![];
+    for orbit in &orbits.centers {
+        let order: Vec<usize> = (0..orbit.len()).collect();
+        let target = |_: &[Color], slot: usize| {
+            let face = orbit.stickers(slot)[0] / (size * size);
+            vec![scheme.colors()[face]]
+        };
+        moves.extend(
+            orbit
+                .solve(cube, &order, target)
+                .ok_or(ReductionError::InvalidPieces(ReductionPhase::Centers))?,
+        );
+    }
+    if !centers_solved(cube, &scheme) {
+        return Err(ReductionError::InvalidPieces(ReductionPhase::Centers));
+    }
+    solution.push(ReductionPhase::Centers, None, moves, cube);
+
+    let (moves, label) = pair_edges(cube, &orbits.wings)
+        .ok_or(ReductionError::InvalidPieces(ReductionPhase::Edges))?;
+    solution.push(ReductionPhase::Edges, label, moves, cube);
+
+    let mut reduced = reduce(cube);
+    for (phase, error, alg) in [
+        (
+            ReductionPhase::OllParity,
+            CubeError::EdgeFlip { sum: 1 },
+            OLL_PARITY,
+        ),
+        (ReductionPhase::PllParity, CubeError::Parity, PLL_PARITY),
+    ] {
+        if size.is_multiple_of(2) && reduced.validate() == Err(error) {
+            let moves = parse(alg);
+            apply(cube, &moves);
+            solution.push(phase, None, moves, cube);
+            reduced = reduce(cube);
+        }
+    }
+
+    let solved = execute_with_method(&mut reduced, target, method)?;
+    for stage in solved.stages {
+        let moves: Vec<Move> = stage
+            .moves
+            .moves()
+            .into_iter()
+            .flat_map(|mv| widen(mv, size))
+            .collect();
+        apply(cube, &moves);
+        solution.push(
+            ReductionPhase::Stage(stage.target),
+            stage.label,
+            moves,
+            cube,
+        );
+    }
+    Ok(solution)
+}
+
+fn parse(alg: &str) -> Vec<Move> {
+    alg.parse::<Algorithm>().unwrap().moves()
+}
+
+fn apply(cube: &mut NxnCube, moves: &[Move]) {
+    for &mv in moves {
+        cube.apply_move(mv);
+    }
+}
+
+fn centers_solved(cube: &NxnCube, scheme: &ColorScheme) -> bool {
+    let size = cube.size();
+    Face::values().iter().all(|face| {
+        (1..size - 1).all(|row| {
+            (1..size - 1)
+                .all(|col| cube.get_block_color(face.ordinal(), row, col) == scheme.color(*face))
+        })
+    })
+}
+
+/// The sticker at position `k` along the edge of its face that `sticker`
+/// lies on
+fn along_edge(size: usize, sticker: usize, k: usize) -> usize {
+    let (face, row, col) = (
+        sticker / (size * size),
+        sticker / size % size,
+        sticker % size,
+    );
+    if row == 0 || row == size - 1 {
+        (face * size + row) * size + k
+    } else {
+        (face * size + k) * size + col
+    }
+}
+
+fn position_along_edge(size: usize, sticker: usize) -> usize {
+    let (row, col) = (sticker / size % size, sticker % size);
+    if row == 0 || row == size - 1 {
+        col
+    } else {
+        row
+    }
+}
+
+/// Pairs the wings of every edge, and with the middle edge piece on odd
+/// cubes. Even cubes pair the second wing of an edge with the first, edge
+/// by edge; the wings of the last edge are then a pair by elimination.
+/// Odd cubes know where every wing goes, and swap the UF wings first when
+/// that takes an odd permutation, which 3-cycles cannot do.
+fn pair_edges(cube: &mut NxnCube, wings: &Orbit) -> Option<(Vec<Move>, Option<String>)> {
+    let size = cube.size();
+    // 每个翼块位置对应的同一条棱上另一个位置：偶数阶是另一个翼块，奇数阶是中棱
+    let partner = |sticker: usize| {
+        let k = position_along_edge(size, sticker);
+        let other = if size % 2 == 1 {
+            size / 2
+        } else {
+            size - 1 - k
+        };
+        along_edge(size, sticker, other)
+    };
+    let target = |stickers: &[Color], slot: usize| {
+        wings
+            .stickers(slot)
+            .iter()
+            .map(|&sticker| stickers[partner(sticker)])
+            .collect::<Vec<Color>>()
+    };
+
+    let mut moves = vec![];
+    let mut label = None;
+    if size % 2 == 1 && odd_permutation(&flatten(cube), wings, target)? {
+        moves = parse(OLL_PARITY);
+        apply(cube, &moves);
+        label = Some("edge parity".to_string());
+    }
+
+    let slot_with = |sticker: usize| {
+        (0..wings.len())
+            .find(|&slot| wings.stickers(slot).contains(&sticker))
+            .unwrap()
+    };
+    let order: Vec<usize> = if size % 2 == 1 {
+        (0..wings.len()).collect()
+    } else {
+        // 每条棱的两个翼块放在一起，第一个保持不动，缓冲位所在的棱不处理
+        let pair = |slot: usize| slot_with(partner(wings.stickers(slot)[0]));
+        let mut order = vec![];
+        for slot in 0..wings.len() {
+            if slot == wings.buffer() || slot == pair(wings.buffer()) || order.contains(&slot) {
+                continue;
+            }
+            order.push(slot);
+            order.push(pair(slot));
+        }
+        order
+    };
+    moves.extend(wings.solve(cube, &order, target)?);
+    Some((moves, label))
+}
+
+/// Whether putting every wing where `target` wants it is an odd permutation
+fn odd_permutation(
+    stickers: &[Color],
+    wings: &Orbit,
+    target: impl Fn(&[Color], usize) -> Vec<Color>,
+) -> Option<bool> {
+    let count = wings.len();
+    let mut source = vec![0; count];
+    for (slot, from) in source.iter_mut().enumerate() {
+        let wanted = target(stickers, slot);
+        *from = (0..count).find(|&s| wings.piece(stickers, s) == wanted)?;
+    }
+    let mut seen = vec![false; count];
+    let mut odd = false;
+    for start in 0..count {
+        let mut slot = start;
+        let mut length = 0;
+        while !seen[slot] {
+            seen[slot] = true;
+            slot = source[slot];
+            length += 1;
+        }
+        if length % 2 == 0 && length > 0 {
+            odd = !odd;
+        }
+    }
+    Some(odd)
+}
+
+/// The 3x3 the reduced cube turns like: corners, one wing of each edge
+/// and one centre piece of each face
+fn reduce(cube: &NxnCube) -> Cube {
+    let n = cube.size();
+    let index = |i: usize| [0, 1, n - 1][i];
+    let mut state = [[[Color::White; 3]; 3]; 6];
+    for (face, rows) in state.iter_mut().enumerate() {
+        for (row, colors) in rows.iter_mut().enumerate() {
+            for (col, color) in colors.iter_mut().enumerate() {
+                *color = cube.get_block_color(face, index(row), index(col));
+            }
+        }
+    }
+    Cube::from_state(state)
+}
+
+/// The moves doing to the reduced cube what `mv` does to a 3x3: its
+/// middle layer stands for all the inner layers
+fn widen(mv: Move, size: usize) -> Vec<Move> {
+    let inner = size as u8 - 1;
+    let middle = |face: Face| {
+        vec![
+            Move::new(MoveKind::WideLayers(face, inner), mv.turn),
+            Move::new(MoveKind::Face(face), mv.turn.inverse()),
+        ]
+    };
+    match mv.kind {
+        MoveKind::Face(_) | MoveKind::Rotation(_) => vec![mv],
+        MoveKind::Wide(face) => vec![Move::new(MoveKind::WideLayers(face, inner), mv.turn)],
+        MoveKind::Slice(Slice::M) => middle(Face::Left),
+        MoveKind::Slice(Slice::E) => middle(Face::Down),
+        MoveKind::Slice(Slice::S) => middle(Face::Front),
+        MoveKind::Layer(face, 2) => middle(face),
+        MoveKind::Layer(face, _) => vec![Move::new(MoveKind::Layer(face, size as u8), mv.turn)],
+        MoveKind::WideLayers(face, _) => {
+            vec![Move::new(MoveKind::WideLayers(face, size as u8), mv.turn)]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrambled(size: usize, alg: &str) -> NxnCube {
+        let mut cube = NxnCube::new(size);
+        cube.apply_algorithm(&alg.parse().unwrap());
+        cube
+    }
+
+    fn check(cube: &NxnCube, method: SolveMethod, target: SolveTarget) -> ReductionSolution {
+        let mut solved = cube.clone();
+        let solution = execute_reduction(&mut solved, target, method).unwrap();
+        assert!(solved.is_solved());
+        let mut state = cube.clone();
+        for stage in &solution.stages {
+            assert_eq!(stage.state_before, state);
+            state.apply_algorithm(&stage.moves);
+            assert_eq!(stage.state_after, state);
+        }
+        assert_eq!(&state, solution.final_state());
+        solution
+    }
+
+    #[test]
+    fn parity_algorithms_touch_only_their_edges() {
+        let solved = NxnCube::new(4);
+        let changed = |alg: &str| {
+            let cube = scrambled(4, alg);
+            let mut stickers = vec![];
+            for face in 0..6 {
+                for row in 0..4 {
+                    for col in 0..4 {
+                        if cube.get_block_color(face, row, col)
+                            != solved.get_block_color(face, row, col)
+                        {
+                            stickers.push((face, row, col));
+                        }
+                    }
+                }
+            }
+            stickers
+        };
+        let (up, front, back) = (
+            Face::Up.ordinal(),
+            Face::Front.ordinal(),
+            Face::Back.ordinal(),
+        );
+        assert_eq!(
+            changed(OLL_PARITY),
+            [(up, 3, 1), (up, 3, 2), (front, 0, 1), (front, 0, 2)]
+        );
+        assert_eq!(
+            changed(PLL_PARITY),
+            [(front, 0, 1), (front, 0, 2), (back, 0, 1), (back, 0, 2)]
+        );
+    }
+
+    #[test]
+    fn reduces_and_solves_a_4x4() {
+        let cube = scrambled(
+            4,
+            "Rw U2 2F' L 2D B2 Uw' R' 2U F2 Lw D' 2R B U' Fw2 L2 2B' D R2 2L U Bw' F",
+        );
+        let solution = check(&cube, SolveMethod::Cfop, SolveTarget::Pll);
+        let phases: Vec<ReductionPhase> = solution.stages.iter().map(|s| s.phase).collect();
+        assert_eq!(
+            &phases[..2],
+            [ReductionPhase::Centers, ReductionPhase::Edges]
+        );
+        assert_eq!(
+            phases.last(),
+            Some(&ReductionPhase::Stage(SolveTarget::Pll))
+        );
+    }
+
+    #[test]
+    fn fixes_both_parities() {
+        // 翻转 UF 棱、交换 UF 与 UB 棱：中心和配对都已完成，只剩奇偶校验
+        let cube = scrambled(4, &format!("{} {} R U F", OLL_PARITY, PLL_PARITY));
+        let solution = check(&cube, SolveMethod::LayerByLayer, SolveTarget::TopEdge);
+        let phases: Vec<ReductionPhase> = solution.stages.iter().map(|s| s.phase).collect();
+        assert_eq!(
+            &phases[..2],
+            [ReductionPhase::OllParity, ReductionPhase::PllParity]
+        );
+    }
+
+    #[test]
+    fn keeps_the_colour_scheme_of_the_cube() {
+        for size in [4, 5] {
+            let mut cube = NxnCube::with_scheme(size, ColorScheme::JAPANESE);
+            cube.apply_algorithm(&"Rw U2 2F' L D Bw2 R' 2U".parse().unwrap());
+            check(&cube, SolveMethod::Cfop, SolveTarget::Pll);
+        }
+    }
+
+    #[test]
+    fn reduces_and_solves_a_5x5() {
+        let cube = scrambled(
+            5,
+            "x 2R U2 3F' L 2D B2 Uw' R' 3U F2 Lw D' 2R B U' 3Fw2 L2 2B' D R2 2L U Bw' F y'",
+        );
+        let solution = check(&cube, SolveMethod::LayerByLayer, SolveTarget::TopEdge);
+        assert!(solution.stages.iter().all(|stage| !matches!(
+            stage.phase,
+            ReductionPhase::OllParity | ReductionPhase::PllParity
+        )));
+
+        // 只交换 UF 棱的两个翼块，配对时要先处理奇偶
+        let cube = scrambled(5, OLL_PARITY);
+        let solution = check(&cube, SolveMethod::two_phase(), SolveTarget::TopEdge);
+        assert_eq!(solution.stages[0].phase, ReductionPhase::Edges);
+        assert_eq!(solution.stages[0].label.as_deref(), Some("edge parity"));
+    }
+
+    #[test]
+    fn widened_moves_act_like_the_3x3() {
+        let alg = parse("R M' U2 Rw E S' x Lw' 2F");
+        let mut cube = Cube::new();
+        apply_3x3(&mut cube, &alg);
+        for size in [4, 5] {
+            let mut big = NxnCube::new(size);
+            apply(
+                &mut big,
+                &alg.iter()
+                    .flat_map(|&mv| widen(mv, size))
+                    .collect::<Vec<_>>(),
+            );
+            assert_eq!(reduce(&big), cube);
+        }
+    }
+
+    fn apply_3x3(cube: &mut Cube, moves: &[Move]) {
+        for &mv in moves {
+            cube.apply_move(mv);
+        }
+    }
+
+    #[test]
+    fn rejects_other_sizes_and_impossible_pieces() {
+        let mut cube = NxnCube::new(3);
+        assert!(matches!(
+            execute_reduction(&mut cube, SolveTarget::Pll, SolveMethod::Cfop),
+            Err(ReductionError::UnsupportedSize(3))
+        ));
+        let mut cube = NxnCube::new(4);
+        cube.set_block_color(0, 1, 1, Color::White);
+        assert!(matches!(
+            execute_reduction(&mut cube, SolveTarget::Pll, SolveMethod::Cfop),
+            Err(ReductionError::InvalidPieces(ReductionPhase::Centers))
+        ));
+    }
+}
@@ -0,0 +1,186 @@
+//! Move cancellation: merges turns of the same layer, drops turns that undo
+//! each other and looks past turns on the same axis, which commute.
+//!
+//! For the slice turn metric a pair like `R L'` is also rewritten as the
+//! slice turn and rotation it equals (`x M`).
+
+use rubik_cube_core::cube::{
+    face::Face,
+    notation::{Algorithm, Axis, Metric, Move, MoveKind, Slice, Turn},
+};
+
+fn axis(kind: MoveKind) -> Axis {
+    match kind {
+        MoveKind::Face(face)
+        | MoveKind::Wide(face)
+        | MoveKind::Layer(face, _)
+        | MoveKind::WideLayers(face, _) => match face {
+            Face::Left | Face::Right => Axis::X,
+            Face::Up | Face::Down => Axis::Y,
+            Face::Front | Face::Back => Axis::Z,
+        },
+        MoveKind::Slice(Slice::M) => Axis::X,
+        MoveKind::Slice(Slice::E) => Axis::Y,
+        MoveKind::Slice(Slice::S) => Axis::Z,
+        MoveKind::Rotation(axis) => axis,
+    }
+}
+
+/// `R L' = x M`, `U D' = y E`, `F B' = z S'`: the face the rotation
+/// follows, the opposite face, the slice and whether the slice follows
+/// the rotation
+fn slice_pair(axis: Axis) -> (Face, Face, Slice, bool) {
+    match axis {
+        Axis::X => (Face::Right, Face::Left, Slice::M, false),
+        Axis::Y => (Face::Up, Face::Down, Slice::E, false),
+        Axis::Z => (Face::Front, Face::Back, Slice::S, true),
+    }
+}
+
+/// Moves tagged with the index of the stage that produced them, so that a
+/// solution can be split into stages again afterwards
+pub(crate) struct Simplifier {
+    metric: Metric,
+    moves: Vec<(Move, usize)>,
+}
+
+impl Simplifier {
+    pub(crate) fn new(metric: Metric) -> Simplifier {
+        Simplifier {
+            metric,
+            moves: vec![],
+        }
+    }
+
+    /// The earlier moves `mv` can be swapped with, latest first
+    fn commuting(&self, mv: Move) -> impl Iterator<Item = usize> + '_ {
+        let mv_axis = axis(mv.kind);
+        (0..self.moves.len())
+            .rev()
+            .take_while(move |&i| axis(self.moves[i].0.kind) == mv_axis)
+    }
+
+    pub(crate) fn push(&mut self, mv: Move, tag: usize) {
+        // 同一层的转动合并，合并后的转动挪到最后，中间的转动都与它可交换
+        let same_layer = self
+            .commuting(mv)
+            .find(|&i| self.moves[i].0.kind == mv.kind);
+        if let Some(i) = same_layer {
+            let (prev, _) = self.moves.remove(i);
+            let quarter_turns = prev.turn.quarter_turns() + mv.turn.quarter_turns();
+            if let Some(turn) = Turn::from_quarter_turns(quarter_turns) {
+                self.push(Move::new(mv.kind, turn), tag);
+            }
+            return;
+        }
+
+        if self.metric == Metric::Stm {
+            if let Some((i, rotation, slice)) = self.slice_turn(mv) {
+                self.moves.remove(i);
+                self.push(rotation, tag);
+                self.push(slice, tag);
+                return;
+            }
+        }
+
+        self.moves.push((mv, tag));
+    }
+
+    /// An earlier turn of the opposite face that makes a slice turn with `mv`
+    fn slice_turn(&self, mv: Move) -> Option<(usize, Move, Move)> {
+        let MoveKind::Face(face) = mv.kind else {
+            return None;
+        };
+        let axis = axis(mv.kind);
+        let (positive, negative, slice, follows) = slice_pair(axis);
+        let opposite = if face == positive { negative } else { positive };
+        let i = self.commuting(mv).find(|&i| {
+            self.moves[i].0.kind == MoveKind::Face(opposite)
+                && self.moves[i].0.turn == mv.turn.inverse()
+        })?;
+
+        // 以 positive 面的方向计的转动量
+        let turn = if face == positive {
+            mv.turn
+        } else {
+            mv.turn.inverse()
+        };
+        let slice_turn = if follows { turn.inverse() } else { turn };
+        Some((
+            i,
+            Move::new(MoveKind::Rotation(axis), turn),
+            Move::new(MoveKind::Slice(slice), slice_turn),
+        ))
+    }
+
+    pub(crate) fn into_moves(self) -> Vec<(Move, usize)> {
+        self.moves
+    }
+}
+
+/// Cancels redundant moves. With [`Metric::Stm`] opposite face turns are
+/// also combined into slice turns.
+pub fn simplify(alg: &Algorithm, metric: Metric) -> Algorithm {
+    let mut simplifier = Simplifier::new(metric);
+    for mv in alg.moves() {
+        simplifier.push(mv, 0);
+    }
+    simplifier
+        .into_moves()
+        .into_iter()
+        .map(|(mv, _)| mv)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rubik_cube_core::cube::Cube;
+
+    use super::*;
+
+    fn simplified(alg: &str, metric: Metric) -> String {
+        simplify(&alg.parse().unwrap(), metric).to_string()
+    }
+
+    fn same_effect(a: &Algorithm, b: &Algorithm) -> bool {
+        let mut x = Cube::new();
+        x.apply_algorithm(a);
+        let mut y = Cube::new();
+        y.apply_algorithm(b);
+        x == y
+    }
+
+    #[test]
+    fn merges_and_cancels() {
+        assert_eq!(simplified("U U U", Metric::Htm), "U'");
+        assert_eq!(simplified("R U U' R'", Metric::Htm), "");
+        assert_eq!(simplified("R' R' F", Metric::Htm), "R2 F");
+        assert_eq!(simplified("R L R", Metric::Htm), "L R2");
+        assert_eq!(simplified("U D y U'", Metric::Htm), "D y");
+        assert_eq!(simplified("R U R'", Metric::Htm), "R U R'");
+    }
+
+    #[test]
+    fn forms_slice_turns_for_stm() {
+        for alg in ["R L'", "L' R", "R2 L2", "U D'", "D U'", "F B'", "B2 F2"] {
+            let original: Algorithm = alg.parse().unwrap();
+            let stm = simplify(&original, Metric::Stm);
+            assert_eq!(stm.length(Metric::Stm), 1, "{} -> {}", alg, stm);
+            assert!(same_effect(&original, &stm), "{} -> {}", alg, stm);
+        }
+        assert_eq!(simplified("R L", Metric::Stm), "R L");
+        assert_eq!(simplified("R L'", Metric::Htm), "R L'");
+    }
+
+    #[test]
+    fn keeps_the_effect() {
+        let alg: Algorithm = "R U R' U' U' L R2 L' D2 U F B' B M M' y U D2 U'"
+            .parse()
+            .unwrap();
+        for metric in [Metric::Htm, Metric::Qtm, Metric::Stm] {
+            let simplified = simplify(&alg, metric);
+            assert!(same_effect(&alg, &simplified));
+            assert!(simplified.length(metric) < alg.length(metric));
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! A solution split into the stages that produced it
+
+use rubik_cube_core::cube::{
+    notation::{Algorithm, Metric},
+    Cube,
+};
+
+use super::{simplify::Simplifier, SolveTarget};
+
+/// The moves one solver contributed and the cube around them
+#[derive(Debug, Clone)]
+pub struct SolutionStage {
+    pub target: SolveTarget,
+    /// The case the solver recognized, e.g. "OLL 27 / Sune"
+    pub label: Option<String>,
+    pub moves: Algorithm,
+    pub state_before: Cube,
+    pub state_after: Cube,
+}
+
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub initial: Cube,
+    /// Stages that were already solved are left out.
+    pub stages: Vec<SolutionStage>,
+}
+
+impl Solution {
+    pub fn new(initial: Cube) -> Solution {
+        Solution {
+            initial,
+            stages: vec![],
+        }
+    }
+
+    /// All stages' moves in order
+    pub fn moves(&self) -> Algorithm {
+        let mut moves = Algorithm::new();
+        for stage in &self.stages {
+            moves.extend(stage.moves.clone());
+        }
+        moves
+    }
+
+    pub fn final_state(&self) -> &Cube {
+        self.stages
+            .last()
+            .map_or(&self.initial, |stage| &stage.state_after)
+    }
+
+    pub fn length(&self, metric: Metric) -> u32 {
+        self.stages
+            .iter()
+            .map(|stage| stage.moves.length(metric))
+            .sum()
+    }
+
+    pub fn htm(&self) -> u32 {
+        self.length(Metric::Htm)
+    }
+
+    pub fn qtm(&self) -> u32 {
+        self.length(Metric::Qtm)
+    }
+
+    pub fn stm(&self) -> u32 {
+        self.length(Metric::Stm)
+    }
+
+    /// Cancels redundant moves, also across stage boundaries, and returns
+    /// how many moves that saved in `metric`. A move that merges with one
+    /// of an earlier stage ends up in the later stage; stages left without
+    /// moves are dropped.
+    pub fn simplify(&mut self, metric: Metric) -> u32 {
+        let before = self.length(metric);
+        let mut simplifier = Simplifier::new(metric);
+        for (index, stage) in self.stages.iter().enumerate() {
+            for mv in stage.moves.moves() {
+                simplifier.push(mv, index);
+            }
+        }
+        let moves = simplifier.into_moves();
+
+        let mut state = self.initial.clone();
+        let stages = std::mem::take(&mut self.stages);
+        for (index, stage) in stages.into_iter().enumerate() {
+            let stage_moves: Algorithm = moves
+                .iter()
+                .filter(|&&(_, tag)| tag == index)
+                .map(|&(mv, _)| mv)
+                .collect();
+            if stage_moves.is_empty() {
+                continue;
+            }
+            let state_before = state.clone();
+            state.apply_algorithm(&stage_moves);
+            self.stages.push(SolutionStage {
+                moves: stage_moves,
+                state_before,
+                state_after: state.clone(),
+                ..stage
+            });
+        }
+        before - self.length(metric)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rubik_cube_core::cube::notation::MoveKind;
+
+    use super::*;
+    use crate::solver::{execute_with_method, SolveMethod};
+
+    #[test]
+    fn stages_chain_from_initial_to_final_state() {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&"R U' F2 L D B' R2 U L' F".parse().unwrap());
+        let scrambled = cube.clone();
+        let solution = execute_with_method(&mut cube, SolveTarget::Pll, SolveMethod::Cfop).unwrap();
+
+        assert_eq!(solution.initial, scrambled);
+        let mut state = scrambled;
+        for stage in &solution.stages {
+            assert_eq!(stage.state_before, state);
+            state.apply_algorithm(&stage.moves);
+            assert_eq!(stage.state_after, state);
+        }
+        assert!(solution.final_state().is_solved());
+        assert_eq!(solution.final_state(), &cube);
+
+        // 整体转动不计步数，M 层在 HTM 里算两步
+        let turns = solution
+            .moves()
+            .moves()
+            .into_iter()
+            .filter(|mv| !matches!(mv.kind, MoveKind::Rotation(_)))
+            .count();
+        assert_eq!(solution.stm() as usize, turns);
+        assert!(solution.htm() >= solution.stm());
+        assert!(solution.qtm() >= solution.htm());
+    }
+
+    /// 固定种子的打乱，保证失败时可以复现
+    fn seeded_scramble(mut seed: u64, length: usize) -> Algorithm {
+        let mut turns = Vec::new();
+        for _ in 0..length {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let face = ["U", "D", "F", "B", "L", "R"][(seed % 6) as usize];
+            let suffix = ["", "'", "2"][(seed / 6 % 3) as usize];
+            turns.push(format!("{face}{suffix}"));
+        }
+        turns.join(" ").parse().unwrap()
+    }
+
+    #[test]
+    fn layer_by_layer_stages_replay_to_their_states() {
+        for seed in 1..=20 {
+            let mut cube = Cube::new();
+            cube.apply_algorithm(&seeded_scramble(seed, 25));
+            let solution =
+                execute_with_method(&mut cube, SolveTarget::TopEdge, SolveMethod::LayerByLayer)
+                    .unwrap();
+
+            let mut state = solution.initial.clone();
+            for stage in &solution.stages {
+                assert_eq!(stage.state_before, state, "seed {seed}");
+                state.apply_algorithm(&stage.moves);
+                assert_eq!(stage.state_after, state, "seed {seed}, {:?}", stage.target);
+            }
+            assert_eq!(solution.final_state(), &cube, "seed {seed}");
+            assert!(cube.is_solved(), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn simplifying_keeps_the_final_state() {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&"F2 D R' B U2 L F' D2 R U' B2".parse().unwrap());
+        let mut solution =
+            execute_with_method(&mut cube, SolveTarget::TopEdge, SolveMethod::LayerByLayer)
+                .unwrap();
+        let htm = solution.htm();
+
+        let saved = solution.simplify(Metric::Htm);
+        assert!(saved > 0);
+        assert_eq!(solution.htm() + saved, htm);
+        assert_eq!(solution.final_state(), &cube);
+        for pair in solution.stages.windows(2) {
+            assert_eq!(pair[0].state_after, pair[1].state_before);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Coordinates of the two-phase algorithm
+//!
+//! Phase 1 brings the cube into the subgroup G1 = <U, D, R2, L2, F2, B2>,
+//! tracked by corner twist, edge flip and the positions of the four
+//! UD-slice edges. Phase 2 solves the cube inside G1, tracked by the corner
+//! permutation, the permutation of the eight U/D edges and the permutation
+//! of the four slice edges.
+
+use rubik_cube_core::cube::cubie::CubieCube;
+
+pub use crate::solver::face_turns::*;
+
+pub const N_TWIST: usize = 2187;
+pub const N_FLIP: usize = 2048;
+pub const N_SLICE: usize = 495;
+pub const N_CORNER_PERM: usize = 40320;
+pub const N_UD_EDGE_PERM: usize = 40320;
+pub const N_SLICE_PERM: usize = 24;
+
+/// The moves that keep a cube inside G1
+pub const PHASE2_MOVES: [usize; 10] = [0, 1, 2, 3, 4, 5, 7, 10, 13, 16];
+
+pub fn twist(cc: &CubieCube) -> usize {
+    cc.co[..7].iter().fold(0, |acc, &t| acc * 3 + t as usize)
+}
+
+pub fn flip(cc: &CubieCube) -> usize {
+    cc.eo[..11].iter().fold(0, |acc, &f| acc * 2 + f as usize)
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    let mut result = 1;
+    for i in 0..k {
+        result = result * (n - i) / (i + 1);
+    }
+    result
+}
+
+/// Which four slots hold the FR, FL, BL and BR edges, 0 when solved.
+pub fn slice(cc: &CubieCube) -> usize {
+    let mut result = 0;
+    let mut found = 0;
+    for j in (0..12).rev() {
+        if cc.ep[j].index() >= 8 {
+            result += binomial(11 - j, found + 1);
+            found += 1;
+        }
+    }
+    result
+}
+
+fn lehmer(perm: &[usize]) -> usize {
+    let mut result = 0;
+    for i in 0..perm.len() {
+        let smaller = perm[i + 1..].iter().filter(|&&p| p < perm[i]).count();
+        result = result * (perm.len() - i) + smaller;
+    }
+    result
+}
+
+pub fn corner_perm(cc: &CubieCube) -> usize {
+    lehmer(&cc.cp.map(|c| c.index()))
+}
+
+/// Only meaningful inside G1, where the first eight slots hold the U/D edges.
+pub fn ud_edge_perm(cc: &CubieCube) -> usize {
+    let perm: Vec<usize> = cc.ep[..8].iter().map(|e| e.index()).collect();
+    lehmer(&perm)
+}
+
+/// Only meaningful inside G1, where the last four slots hold the slice edges.
+pub fn slice_perm(cc: &CubieCube) -> usize {
+    let perm: Vec<usize> = cc.ep[8..].iter().map(|e| e.index() - 8).collect();
+    lehmer(&perm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solved_coordinates_are_zero() {
+        let cc = CubieCube::solved();
+        assert_eq!(twist(&cc), 0);
+        assert_eq!(flip(&cc), 0);
+        assert_eq!(slice(&cc), 0);
+        assert_eq!(corner_perm(&cc), 0);
+        assert_eq!(ud_edge_perm(&cc), 0);
+        assert_eq!(slice_perm(&cc), 0);
+    }
+
+    #[test]
+    fn phase2_moves_stay_in_g1() {
+        for &m in PHASE2_MOVES.iter() {
+            let cc = move_cubies()[m];
+            assert_eq!(
+                (twist(&cc), flip(&cc), slice(&cc)),
+                (0, 0, 0),
+                "{}",
+                to_move(m)
+            );
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Kociemba's two-phase algorithm
+//!
+//! Unlike the layer solvers this solves the whole cube in one step and
+//! usually needs no more than 22 face turns.
+
+mod coord;
+mod search;
+mod tables;
+
+use std::time::{Duration, Instant};
+
+use rubik_cube_core::cube::{cubie::CubieCube, notation::Move, Cube};
+
+use super::{SolveError, SolveTarget, Solver, SolverEnum};
+
+pub struct TwoPhaseSolver {
+    /// Stop as soon as a solution of at most this many moves is found
+    pub max_length: usize,
+    /// Once a solution is known, give up looking for a shorter one after this
+    pub time_budget: Duration,
+}
+
+impl Default for TwoPhaseSolver {
+    fn default() -> Self {
+        TwoPhaseSolver {
+            max_length: 22,
+            time_budget: Duration::from_secs(1),
+        }
+    }
+}
+
+impl TwoPhaseSolver {
+    pub fn new(max_length: usize, time_budget: Duration) -> Self {
+        TwoPhaseSolver {
+            max_length,
+            time_budget,
+        }
+    }
+
+    /// Builds the lookup tables ahead of the first solve.
+    pub fn warm_up() {
+        tables::tables();
+    }
+
+    pub fn solve(&self, cube: &CubieCube) -> Vec<Move> {
+        let tables = tables::tables();
+        let deadline = Instant::now() + self.time_budget;
+        search::Search::new(tables, *cube, self.max_length, deadline)
+            .run()
+            .into_iter()
+            .map(coord::to_move)
+            .collect()
+    }
+}
+
+impl Solver for TwoPhaseSolver {
+    fn target(&self) -> SolveTarget {
+        SolveTarget::TopEdge
+    }
+
+    fn solve_target(&mut self, cube: &mut Cube) -> Result<Vec<Move>, SolveError> {
+        let cubie_cube = CubieCube::try_from(&*cube)?;
+        let steps = self.solve(&cubie_cube);
+        for &mv in &steps {
+            cube.apply_move(mv);
+        }
+        Ok(steps)
+    }
+
+    fn is_target_solved(&self, cube: &Cube) -> bool {
+        cube.is_solved()
+    }
+
+    fn next_solver(&self) -> Option<SolverEnum> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rubik_cube_core::cube::notation::Algorithm;
+
+    use super::*;
+
+    #[test]
+    fn solves_scrambles() {
+        let scrambles = [
+            "R U R' U'",
+            "D2 F' L2 U B2 R' F D' L B2 U2 R2 F2 D L2 B R' U2 F'",
+            "U2 L' B2 D R F2 U' B L2 D2 R' F U B' D2 L F2 R U'",
+        ];
+        let solver = TwoPhaseSolver::default();
+        for scramble in scrambles {
+            let mut cube = Cube::new();
+            cube.apply_algorithm(&scramble.parse().unwrap());
+            let solution = solver.solve(&CubieCube::try_from(&cube).unwrap());
+            cube.apply_algorithm(&Algorithm::from(solution.clone()));
+            assert!(cube.is_solved(), "{}", scramble);
+            assert!(solution.len() <= 22, "{}", Algorithm::from(solution));
+        }
+    }
+
+    #[test]
+    fn solved_cube_needs_no_moves() {
+        assert!(TwoPhaseSolver::default()
+            .solve(&CubieCube::solved())
+            .is_empty());
+    }
+}
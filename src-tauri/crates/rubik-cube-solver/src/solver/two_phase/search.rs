@@ -0,0 +1,146 @@
+//! The two-phase search itself: IDA* into G1, then IDA* inside G1 for every
+//! phase 1 solution found, keeping the shortest combined solution.
+
+use std::time::Instant;
+
+use rubik_cube_core::cube::cubie::CubieCube;
+
+use super::coord::*;
+use super::tables::Tables;
+
+/// No cube needs more than 12 moves in phase 1 or 18 in phase 2.
+const MAX_PHASE1: usize = 12;
+const MAX_PHASE2: usize = 18;
+
+pub struct Search<'a> {
+    tables: &'a Tables,
+    cube: CubieCube,
+    max_length: usize,
+    deadline: Instant,
+    path: Vec<usize>,
+    best: Option<Vec<usize>>,
+}
+
+impl<'a> Search<'a> {
+    pub fn new(tables: &'a Tables, cube: CubieCube, max_length: usize, deadline: Instant) -> Self {
+        Search {
+            tables,
+            cube,
+            max_length,
+            deadline,
+            path: vec![],
+            best: None,
+        }
+    }
+
+    /// Runs until a solution of at most `max_length` moves is found, or until
+    /// the deadline once any solution is known.
+    pub fn run(mut self) -> Vec<usize> {
+        let (twist, flip, slice) = (twist(&self.cube), flip(&self.cube), slice(&self.cube));
+        for depth in 0..=MAX_PHASE1 {
+            if let Some(best) = &self.best {
+                if depth >= best.len() {
+                    break;
+                }
+            }
+            if self.phase1(twist, flip, slice, depth) {
+                break;
+            }
+        }
+        self.best
+            .expect("every valid cube has a two-phase solution")
+    }
+
+    fn should_stop(&self) -> bool {
+        match &self.best {
+            Some(best) => best.len() <= self.max_length || Instant::now() >= self.deadline,
+            None => false,
+        }
+    }
+
+    /// Returns true once the search should stop.
+    fn phase1(&mut self, twist: usize, flip: usize, slice: usize, depth: usize) -> bool {
+        if depth == 0 {
+            // 以 G1 内的转动结尾的解在更短的深度已经搜索过
+            let redundant = self.path.last().is_some_and(|m| PHASE2_MOVES.contains(m));
+            if twist == 0 && flip == 0 && slice == 0 && !redundant {
+                return self.start_phase2();
+            }
+            return false;
+        }
+
+        let prev = self.path.last().map(|&m| move_face(m));
+        for m in 0..N_MOVES {
+            if !may_follow(prev, move_face(m)) {
+                continue;
+            }
+            let t = self.tables.twist[twist][m] as usize;
+            let f = self.tables.flip[flip][m] as usize;
+            let s = self.tables.slice[slice][m] as usize;
+            if self.tables.phase1_distance(t, f, s) as usize >= depth {
+                continue;
+            }
+            self.path.push(m);
+            let stop = self.phase1(t, f, s, depth - 1);
+            self.path.pop();
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn start_phase2(&mut self) -> bool {
+        if self.should_stop() {
+            return true;
+        }
+        let mut cube = self.cube;
+        for &m in &self.path {
+            cube = cube.multiply(&move_cubies()[m]);
+        }
+        let (cp, ep, sp) = (corner_perm(&cube), ud_edge_perm(&cube), slice_perm(&cube));
+
+        let phase1_len = self.path.len();
+        let limit = match &self.best {
+            Some(best) => best.len() - 1 - phase1_len,
+            None => MAX_PHASE2,
+        }
+        .min(MAX_PHASE2);
+
+        let start = self.tables.phase2_distance(cp, ep, sp) as usize;
+        for depth in start..=limit {
+            if self.phase2(cp, ep, sp, depth) {
+                self.best = Some(self.path.clone());
+                self.path.truncate(phase1_len);
+                break;
+            }
+        }
+        self.should_stop()
+    }
+
+    /// Returns true when a solution was found; `path` then holds it.
+    fn phase2(&mut self, cp: usize, ep: usize, sp: usize, depth: usize) -> bool {
+        if depth == 0 {
+            return cp == 0 && ep == 0 && sp == 0;
+        }
+
+        let prev = self.path.last().map(|&m| move_face(m));
+        for m in PHASE2_MOVES {
+            if !may_follow(prev, move_face(m)) {
+                continue;
+            }
+            let c = self.tables.corner_perm[cp][m] as usize;
+            let e = self.tables.ud_edge_perm[ep][m] as usize;
+            let s = self.tables.slice_perm[sp][m] as usize;
+            if self.tables.phase2_distance(c, e, s) as usize >= depth {
+                continue;
+            }
+            self.path.push(m);
+            if self.phase2(c, e, s, depth - 1) {
+                return true;
+            }
+            self.path.pop();
+        }
+        false
+    }
+}
@@ -0,0 +1,140 @@
+//! Move and pruning tables of the two-phase algorithm
+//!
+//! Move tables map a coordinate and a face turn to the new coordinate. They
+//! are filled by a breadth-first walk from the solved state which keeps one
+//! representative cube per coordinate value, so no coordinate needs an
+//! inverse. Pruning tables hold the exact distance to solved of a pair of
+//! coordinates and serve as the IDA* heuristic.
+
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+
+use rubik_cube_core::cube::cubie::CubieCube;
+
+use super::coord::*;
+
+pub type MoveTable = Vec<[u16; N_MOVES]>;
+
+pub struct Tables {
+    pub twist: MoveTable,
+    pub flip: MoveTable,
+    pub slice: MoveTable,
+    pub corner_perm: MoveTable,
+    pub ud_edge_perm: MoveTable,
+    pub slice_perm: MoveTable,
+    pub twist_slice: Vec<u8>,
+    pub flip_slice: Vec<u8>,
+    pub corner_slice_perm: Vec<u8>,
+    pub edge_slice_perm: Vec<u8>,
+}
+
+const ALL_MOVES: [usize; N_MOVES] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17];
+
+/// Entries for moves outside `moves` are left at 0 and must not be read.
+fn build_move_table(size: usize, coord: fn(&CubieCube) -> usize, moves: &[usize]) -> MoveTable {
+    let mut table = vec![[0; N_MOVES]; size];
+    let mut representative: Vec<Option<CubieCube>> = vec![None; size];
+    let solved = CubieCube::solved();
+    representative[coord(&solved)] = Some(solved);
+    let mut queue = VecDeque::from([coord(&solved)]);
+
+    while let Some(c) = queue.pop_front() {
+        let cube = representative[c].unwrap();
+        for &m in moves {
+            let next = cube.multiply(&move_cubies()[m]);
+            let n = coord(&next);
+            table[c][m] = n as u16;
+            if representative[n].is_none() {
+                representative[n] = Some(next);
+                queue.push_back(n);
+            }
+        }
+    }
+    debug_assert!(representative.iter().all(Option::is_some));
+    table
+}
+
+/// Distance to solved of every `(a, b)` pair, indexed by `a * size_b + b`.
+fn build_pruning_table(a: &MoveTable, b: &MoveTable, moves: &[usize]) -> Vec<u8> {
+    let size_b = b.len();
+    let mut table = vec![u8::MAX; a.len() * size_b];
+    table[0] = 0;
+    let mut queue = VecDeque::from([(0usize, 0usize)]);
+
+    while let Some((ca, cb)) = queue.pop_front() {
+        let depth = table[ca * size_b + cb];
+        for &m in moves {
+            let (na, nb) = (a[ca][m] as usize, b[cb][m] as usize);
+            let index = na * size_b + nb;
+            if table[index] == u8::MAX {
+                table[index] = depth + 1;
+                queue.push_back((na, nb));
+            }
+        }
+    }
+    table
+}
+
+impl Tables {
+    fn build() -> Tables {
+        let twist = build_move_table(N_TWIST, twist, &ALL_MOVES);
+        let flip = build_move_table(N_FLIP, flip, &ALL_MOVES);
+        let slice = build_move_table(N_SLICE, slice, &ALL_MOVES);
+        let corner_perm = build_move_table(N_CORNER_PERM, corner_perm, &PHASE2_MOVES);
+        let ud_edge_perm = build_move_table(N_UD_EDGE_PERM, ud_edge_perm, &PHASE2_MOVES);
+        let slice_perm = build_move_table(N_SLICE_PERM, slice_perm, &PHASE2_MOVES);
+
+        let twist_slice = build_pruning_table(&twist, &slice, &ALL_MOVES);
+        let flip_slice = build_pruning_table(&flip, &slice, &ALL_MOVES);
+        let corner_slice_perm = build_pruning_table(&corner_perm, &slice_perm, &PHASE2_MOVES);
+        let edge_slice_perm = build_pruning_table(&ud_edge_perm, &slice_perm, &PHASE2_MOVES);
+
+        Tables {
+            twist,
+            flip,
+            slice,
+            corner_perm,
+            ud_edge_perm,
+            slice_perm,
+            twist_slice,
+            flip_slice,
+            corner_slice_perm,
+            edge_slice_perm,
+        }
+    }
+
+    pub fn phase1_distance(&self, twist: usize, flip: usize, slice: usize) -> u8 {
+        self.twist_slice[twist * N_SLICE + slice].max(self.flip_slice[flip * N_SLICE + slice])
+    }
+
+    pub fn phase2_distance(
+        &self,
+        corner_perm: usize,
+        ud_edge_perm: usize,
+        slice_perm: usize,
+    ) -> u8 {
+        self.corner_slice_perm[corner_perm * N_SLICE_PERM + slice_perm]
+            .max(self.edge_slice_perm[ud_edge_perm * N_SLICE_PERM + slice_perm])
+    }
+}
+
+/// Built on first use; takes a moment, so callers may want to warm it up.
+pub fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(Tables::build)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pruning_tables_cover_every_state() {
+        let tables = tables();
+        // 第一阶段最多 12 步，第二阶段最多 18 步
+        assert!(tables.twist_slice.iter().all(|&d| d <= 12));
+        assert!(tables.flip_slice.iter().all(|&d| d <= 12));
+        assert!(tables.corner_slice_perm.iter().all(|&d| d <= 18));
+        assert!(tables.edge_slice_perm.iter().all(|&d| d <= 18));
+    }
+}
@@ -1,4 +1,4 @@
-use rubik_cube_core::cube::{color::Color, face::Face, Cube};
+use rubik_cube_core::cube::{color::Color, error::CubeError, face::Face, Cube};
 
 pub fn color_state_to_u8(cube: &Cube) -> [[[u8; 3]; 3]; 6] {
     let color_state: [[[Color; 3]; 3]; 6] = cube.state;
@@ -26,10 +26,23 @@ pub fn u8_to_color_state(state: [[[u8; 3]; 3]; 6]) -> Cube {
     Cube::from_state(color_state)
 }
 
+/// Like `u8_to_color_state`, but rejects unknown colours and impossible cubes.
+pub fn try_u8_to_color_state(state: [[[u8; 3]; 3]; 6]) -> Result<Cube, CubeError> {
+    let mut color_state = [[[Color::White; 3]; 3]; 6];
+    for face in 0..6 {
+        for row in 0..3 {
+            for col in 0..3 {
+                color_state[face][row][col] = Color::try_from(state[face][row][col])?;
+            }
+        }
+    }
+    Cube::try_from_state(color_state)
+}
+
 pub fn print_cube(cube: &Cube) {
     // println!("魔方状态数组:");
     for face in 0..6 {
-        println!("{:?}:", Face::from(face as u8));
+        println!("{:?}:", Face::try_from(face as u8).unwrap());
         for row in 0..3 {
             println!("{:?}", cube.state[face][row]);
         }
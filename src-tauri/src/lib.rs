@@ -1,18 +1,44 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+mod player;
 mod rubiks;
+mod session;
+
+use std::sync::Mutex;
 
 use chrono::Local;
+use player::Player;
+use session::Session;
 use tauri::{command, PhysicalSize, Size, Window};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .manage(Mutex::new(Session::default()))
+        .manage(Mutex::new(Player::default()))
         .invoke_handler(tauri::generate_handler![
             rubiks::init_get_get_state,
+            rubiks::reset,
+            rubiks::set_size,
+            rubiks::set_color_scheme,
+            rubiks::init_from_colors,
+            rubiks::init_from_facelets,
+            rubiks::export_facelets,
             rubiks::shuffle,
             rubiks::turn,
+            rubiks::apply_algorithm,
             rubiks::solve,
+            rubiks::count_optimal_solutions,
+            rubiks::undo,
+            rubiks::redo,
+            rubiks::reset_to,
+            rubiks::history,
+            rubiks::playback_state,
+            rubiks::playback_seek,
+            rubiks::playback_step,
+            rubiks::playback_stage,
+            rubiks::playback_play,
+            rubiks::playback_pause,
             get_current_time,
             resize_window,
             get_window_size,
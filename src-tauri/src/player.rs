@@ -0,0 +1,146 @@
+//! 解法演示：最近一次求解的逐步回放，以及自动播放的状态
+
+use std::time::Duration;
+
+use rubik_cube_core::cube::notation::Move;
+use rubik_cube_solver::{solver::Playback, utils::color_state_to_u8};
+
+/// 自动播放默认每步间隔
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+pub struct Player {
+    playback: Option<Playback>,
+    playing: bool,
+    /// 每次开始播放加一，旧的计时线程发现不一致就退出
+    generation: u64,
+}
+
+/// 回放的当前位置：`position` 为已执行的步数，`stage` 为下一步所属的阶段，
+/// `last_move` 为刚执行的一步
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PlaybackFrame {
+    pub position: usize,
+    pub length: usize,
+    pub stage: usize,
+    pub stage_count: usize,
+    pub last_move: Option<Move>,
+    pub cube: [[[u8; 3]; 3]; 6],
+    pub playing: bool,
+}
+
+impl Player {
+    /// 载入新的解法并停止播放
+    pub fn load(&mut self, playback: Playback) {
+        self.playback = Some(playback);
+        self.pause();
+    }
+
+    /// 操作回放，没有载入解法时返回错误
+    pub fn update(&mut self, action: impl FnOnce(&mut Playback)) -> Result<PlaybackFrame, String> {
+        let playback = self
+            .playback
+            .as_mut()
+            .ok_or_else(|| "no solution loaded".to_string())?;
+        action(playback);
+        Ok(self.frame().unwrap())
+    }
+
+    /// 开始自动播放，返回计时线程要带上的编号；已经播完时从头开始
+    pub fn play(&mut self) -> Result<u64, String> {
+        self.update(|playback| {
+            if playback.is_finished() {
+                playback.seek(0);
+            }
+        })?;
+        self.playing = true;
+        self.generation += 1;
+        Ok(self.generation)
+    }
+
+    /// 清空回放，例如解法不是三阶的时候
+    pub fn unload(&mut self) {
+        self.playback = None;
+        self.pause();
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+        self.generation += 1;
+    }
+
+    /// 计时线程每隔一段时间调用一次，前进一步；播放已停止或被新的播放
+    /// 取代时返回 `None`
+    pub fn tick(&mut self, generation: u64) -> Option<PlaybackFrame> {
+        if !self.playing || generation != self.generation {
+            return None;
+        }
+        let playback = self.playback.as_mut()?;
+        playback.step_forward();
+        if playback.is_finished() {
+            self.playing = false;
+        }
+        self.frame()
+    }
+
+    pub fn frame(&self) -> Option<PlaybackFrame> {
+        let playback = self.playback.as_ref()?;
+        Some(PlaybackFrame {
+            position: playback.position(),
+            length: playback.len(),
+            stage: playback.stage(),
+            stage_count: playback.stage_count(),
+            last_move: playback.last_move(),
+            cube: color_state_to_u8(playback.state()),
+            playing: self.playing,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rubik_cube_core::cube::{notation::Algorithm, Cube};
+
+    use super::*;
+
+    fn loaded() -> Player {
+        let alg: Algorithm = "R U R'".parse().unwrap();
+        let mut player = Player::default();
+        player.load(Playback::new(Cube::new(), &alg));
+        player
+    }
+
+    #[test]
+    fn ticks_until_the_end() {
+        let mut player = loaded();
+        let generation = player.play().unwrap();
+        let positions: Vec<usize> = std::iter::from_fn(|| player.tick(generation))
+            .map(|frame| frame.position)
+            .collect();
+        assert_eq!(positions, vec![1, 2, 3]);
+        assert!(!player.frame().unwrap().playing);
+
+        // 播完后重新播放从头开始
+        let generation = player.play().unwrap();
+        assert_eq!(player.tick(generation).unwrap().position, 1);
+    }
+
+    #[test]
+    fn stale_timers_stop() {
+        let mut player = loaded();
+        let old = player.play().unwrap();
+        let new = player.play().unwrap();
+        assert!(player.tick(old).is_none());
+        assert!(player.tick(new).is_some());
+        player.pause();
+        assert!(player.tick(new).is_none());
+    }
+
+    #[test]
+    fn requires_a_solution() {
+        let mut player = Player::default();
+        assert!(player.frame().is_none());
+        assert!(player.play().is_err());
+        assert!(player.update(|playback| playback.seek(1)).is_err());
+    }
+}
@@ -1,67 +1,850 @@
 //! Rubik's Cube implementation module
+//!
+//! 魔方状态保存在 `Mutex<Session>` 里，命令直接修改它，并发出 `cube-changed`
+//! 事件，所有窗口据此同步显示；最近一次求解的回放在 `Mutex<Player>` 里，
+//! 位置变化时发出 `playback-tick` 事件
+
+use std::{
+    sync::{Mutex, MutexGuard, PoisonError},
+    thread,
+    time::Duration,
+};
 
 use rubik_cube_core::cube::{
+    color::Color,
+    error::CubeError,
     face::{Face, TwistDirection},
+    facelet::FaceletLetters,
+    input::{PartialState, Suggestion},
+    notation::{Algorithm, Metric, Move, MoveKind, ParseError},
+    nxn::{NxnCube, MAX_SIZE, MIN_SIZE},
+    scheme::ColorScheme,
     Cube,
 };
-use rubik_cube_shuffler::{CubeScrambler, CubeShuffler};
+use rubik_cube_shuffler::{scramble_length, CubeShuffler, NxnShuffler};
 use rubik_cube_solver::{
-    solver::{execute, SolveTarget},
-    utils::{color_state_to_u8, u8_to_color_state},
+    solver::{
+        execute_color_neutral, execute_reduction, execute_with_method, Playback, PocketCube,
+        PocketSolver, ReductionError, ReductionPhase, SolveError, SolveMethod, SolveTarget,
+    },
+    utils::color_state_to_u8,
+};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::{
+    player::{PlaybackFrame, Player, DEFAULT_INTERVAL},
+    session::{Action, HistoryEntry, Session},
 };
 
+/// 会话和回放在每次操作后都是完整的，锁中毒时照常使用
+fn lock<'a, T: Send>(state: &'a State<'_, Mutex<T>>) -> MutexGuard<'a, T> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// `cube-changed` 事件的内容：`size` 为阶数，`cube` 每个面 `size` 行 `size` 列；
+/// `position` 为已执行的历史记录数，`length` 为全部记录数
+#[derive(Clone, serde::Serialize)]
+pub struct CubeChanged {
+    size: usize,
+    cube: Vec<Vec<Vec<u8>>>,
+    position: usize,
+    length: usize,
+}
+
+impl From<&Session> for CubeChanged {
+    fn from(session: &Session) -> Self {
+        CubeChanged {
+            size: session.size(),
+            cube: nxn_state_to_u8(session.cube()),
+            position: session.position(),
+            length: session.entries().len(),
+        }
+    }
+}
+
+/// 任意阶数的状态，每个面 `size` 行 `size` 列
+fn nxn_state_to_u8(cube: &NxnCube) -> Vec<Vec<Vec<u8>>> {
+    cube.state()
+        .iter()
+        .map(|face| {
+            face.iter()
+                .map(|row| row.iter().map(|&color| color as u8).collect())
+                .collect()
+        })
+        .collect()
+}
+
+fn notify(app: &AppHandle, session: &Session) {
+    if let Err(e) = app.emit("cube-changed", CubeChanged::from(session)) {
+        eprintln!("failed to emit cube-changed: {}", e);
+    }
+}
+
+/// 当前的魔方状态，窗口打开时调用
 #[tauri::command]
-pub fn init_get_get_state() -> [[[u8; 3]; 3]; 6] {
-    // 将魔方状态转换为u8数组
-    color_state_to_u8(&Cube::new())
+pub fn init_get_get_state(session: State<'_, Mutex<Session>>) -> CubeChanged {
+    CubeChanged::from(&*lock(&session))
 }
 
+/// 恢复到还原状态，清空历史，阶数不变
 #[tauri::command]
-pub fn shuffle(state: [[[u8; 3]; 3]; 6], times: u32) -> [[[u8; 3]; 3]; 6] {
-    let mut cube = u8_to_color_state(state);
-    let mut shuffler = CubeShuffler::new(&mut cube);
-    shuffler.shuffle(times);
-    color_state_to_u8(&cube)
+pub fn reset(app: AppHandle, session: State<'_, Mutex<Session>>) {
+    let mut session = lock(&session);
+    *session = Session::new(NxnCube::with_scheme(session.size(), session.scheme()));
+    notify(&app, &session);
 }
 
+/// 换成 `size` 阶（2 到 7）的还原状态魔方，清空历史
 #[tauri::command]
-pub fn turn(state: [[[u8; 3]; 3]; 6], face: u8, direction: bool) -> [[[u8; 3]; 3]; 6] {
-    println!("turn param; face: {}, direction: {}", face, direction);
-    let mut cube = u8_to_color_state(state);
-    let mut shuffler = CubeScrambler::new(&mut cube);
-    shuffler.scramble(
-        Face::from(face),
-        if direction {
-            TwistDirection::Clockwise
-        } else {
-            TwistDirection::CounterClockwise
+pub fn set_size(
+    app: AppHandle,
+    session: State<'_, Mutex<Session>>,
+    size: usize,
+) -> Result<(), String> {
+    if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+        return Err(format!(
+            "cube size {} out of range {}..={}",
+            size, MIN_SIZE, MAX_SIZE
+        ));
+    }
+    let mut session = lock(&session);
+    *session = Session::new(NxnCube::with_scheme(size, session.scheme()));
+    notify(&app, &session);
+    Ok(())
+}
+
+/// 换一套配色，魔方回到还原状态，清空历史，阶数不变。
+/// `scheme`: 0 西式（默认），1 日式，其他值报错；给出 `colors` 时按 U D F B L R
+/// 的顺序自定义每个面的颜色，忽略 `scheme`
+#[tauri::command]
+pub fn set_color_scheme(
+    app: AppHandle,
+    session: State<'_, Mutex<Session>>,
+    scheme: Option<u8>,
+    colors: Option<[u8; 6]>,
+) -> Result<(), String> {
+    let scheme = color_scheme(scheme, colors)?;
+    let mut session = lock(&session);
+    *session = Session::new(NxnCube::with_scheme(session.size(), scheme));
+    notify(&app, &session);
+    Ok(())
+}
+
+fn color_scheme(scheme: Option<u8>, colors: Option<[u8; 6]>) -> Result<ColorScheme, String> {
+    let Some(colors) = colors else {
+        return ColorScheme::try_from(scheme.unwrap_or(0)).map_err(|e| e.to_string());
+    };
+    let mut parsed = [Color::White; 6];
+    for (slot, value) in parsed.iter_mut().zip(colors) {
+        *slot = Color::try_from(value).map_err(|e| e.to_string())?;
+    }
+    ColorScheme::new(parsed).map_err(|e| e.to_string())
+}
+
+/// 求解、导出等只支持三阶，其他阶数返回错误
+fn cube_3x3(session: &Session) -> Result<Cube, String> {
+    session.cube().to_cube().ok_or_else(|| {
+        format!(
+            "only supported on the 3x3 cube, the current cube is {0}x{0}",
+            session.size()
+        )
+    })
+}
+
+/// 按各面颜色设置三阶魔方并清空历史。`colors` 中 null 表示还没填的贴纸，
+/// 能唯一确定时自动补全；状态不可能时返回修改建议
+#[tauri::command]
+pub fn init_from_colors(
+    app: AppHandle,
+    session: State<'_, Mutex<Session>>,
+    colors: [[[Option<u8>; 3]; 3]; 6],
+) -> Result<(), InitFailure> {
+    let cube = cube_from_colors(colors)?;
+    let mut session = lock(&session);
+    *session = Session::new(cube);
+    notify(&app, &session);
+    Ok(())
+}
+
+fn cube_from_colors(colors: [[[Option<u8>; 3]; 3]; 6]) -> Result<Cube, InitFailure> {
+    let mut partial: PartialState = [[[None; 3]; 3]; 6];
+    for (face, rows) in colors.iter().enumerate() {
+        for (row, values) in rows.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                partial[face][row][col] = value
+                    .map(Color::try_from)
+                    .transpose()
+                    .map_err(|e: CubeError| InitFailure::from(vec![Suggestion::Invalid(e)]))?;
+            }
+        }
+    }
+    Cube::from_partial(&partial).map_err(InitFailure::from)
+}
+
+/// 按 Kociemba 格式的 54 字母字符串设置三阶魔方并清空历史，面字母（URFDLB）
+/// 和颜色字母（YWBGOR）都可以
+#[tauri::command]
+pub fn init_from_facelets(
+    app: AppHandle,
+    session: State<'_, Mutex<Session>>,
+    facelets: String,
+) -> Result<(), String> {
+    let mut session = lock(&session);
+    let cube = cube_from_facelets(&facelets, session.scheme())?;
+    *session = Session::new(cube);
+    notify(&app, &session);
+    Ok(())
+}
+
+/// 面字母按当前配色上色
+fn cube_from_facelets(facelets: &str, scheme: ColorScheme) -> Result<Cube, String> {
+    let cube = Cube::from_facelets_with_scheme(facelets, scheme).map_err(|e| e.to_string())?;
+    cube.validate().map_err(|e| e.to_string())?;
+    Ok(cube)
+}
+
+/// 以 Kociemba 格式导出当前的三阶状态，`colors` 为 true 时用颜色字母
+#[tauri::command]
+pub fn export_facelets(
+    session: State<'_, Mutex<Session>>,
+    colors: Option<bool>,
+) -> Result<String, String> {
+    let letters = if colors.unwrap_or(false) {
+        FaceletLetters::Colors
+    } else {
+        FaceletLetters::Faces
+    };
+    Ok(cube_3x3(&lock(&session))?.to_facelets(letters))
+}
+
+/// 初始化失败时的修改建议，任选一条照做即可；
+/// `facelets` 是建议涉及的贴纸，每项为 [面, 行, 列]
+#[derive(Debug, serde::Serialize)]
+pub struct InitFailure {
+    suggestions: Vec<InitSuggestion>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct InitSuggestion {
+    message: String,
+    facelets: Vec<[usize; 3]>,
+}
+
+impl From<Vec<Suggestion>> for InitFailure {
+    fn from(suggestions: Vec<Suggestion>) -> Self {
+        InitFailure {
+            suggestions: suggestions
+                .iter()
+                .map(|suggestion| InitSuggestion {
+                    message: suggestion.to_string(),
+                    facelets: suggestion
+                        .facelets()
+                        .into_iter()
+                        .map(|(face, row, col)| [face.ordinal(), row, col])
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 按比赛规则生成 `times` 步打乱（不连续转同一面，不连续三次转同一轴），
+/// 不传时按当前阶数取比赛的步数，二阶只转 R U F，更高阶还有宽层转动；
+/// `seed`: 传入上次返回的种子可以重现同一个打乱；
+/// `random_state`: 为 true 时均匀随机抽取一个状态，忽略 `times`，只支持三阶；
+/// `solved`: 专项练习，随机抽取一个该阶段及之前阶段都已还原的状态（如 F2L 之后只剩顶层），忽略 `times`，只支持三阶
+#[tauri::command]
+pub fn shuffle(
+    app: AppHandle,
+    session: State<'_, Mutex<Session>>,
+    times: Option<u32>,
+    seed: Option<u64>,
+    random_state: Option<bool>,
+    solved: Option<u8>,
+) -> Result<ShuffleResult, String> {
+    let mut session = lock(&session);
+    let result = shuffle_session(&mut session, times, seed, random_state, solved)?;
+    notify(&app, &session);
+    Ok(result)
+}
+
+fn shuffle_session(
+    session: &mut Session,
+    times: Option<u32>,
+    seed: Option<u64>,
+    random_state: Option<bool>,
+    solved: Option<u8>,
+) -> Result<ShuffleResult, String> {
+    let times = times.unwrap_or_else(|| scramble_length(session.size()));
+    if session.size() != 3 {
+        if solved.is_some() || random_state.unwrap_or(false) {
+            return Err("random-state scrambles are only supported on the 3x3 cube".to_string());
+        }
+        let mut cube = session.cube().clone();
+        let mut shuffler = match seed {
+            Some(seed) => NxnShuffler::with_seed(&mut cube, seed),
+            None => NxnShuffler::new(&mut cube),
+        };
+        let seed = shuffler.seed().unwrap();
+        let scramble = shuffler.scramble(times);
+        session.push(Action::Shuffle, scramble.clone());
+        return Ok(ShuffleResult { scramble, seed });
+    }
+
+    let solved = solved
+        .map(SolveTarget::try_from)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let mut cube = session.cube().to_cube().unwrap();
+    let mut shuffler = match seed {
+        Some(seed) => CubeShuffler::with_seed(&mut cube, seed),
+        None => CubeShuffler::new(&mut cube),
+    };
+    let seed = shuffler.seed().unwrap();
+    let scramble = if let Some(solved) = solved {
+        shuffler.subset_scramble(solved)
+    } else if random_state.unwrap_or(false) {
+        shuffler.random_state_scramble()
+    } else {
+        shuffler.scramble(times)
+    };
+    session.push(Action::Shuffle, scramble.clone());
+    Ok(ShuffleResult { scramble, seed })
+}
+
+#[derive(serde::Serialize)]
+pub struct ShuffleResult {
+    scramble: Algorithm,
+    seed: u64,
+}
+
+#[tauri::command]
+pub fn turn(
+    app: AppHandle,
+    session: State<'_, Mutex<Session>>,
+    face: u8,
+    direction: bool,
+) -> Result<(), String> {
+    let face = Face::try_from(face).map_err(|e| e.to_string())?;
+    let direction = if direction {
+        TwistDirection::Clockwise
+    } else {
+        TwistDirection::CounterClockwise
+    };
+    let mv = Move::new(MoveKind::Face(face), direction.into());
+    let mut session = lock(&session);
+    session.push(Action::Turn, Algorithm::from(vec![mv]));
+    notify(&app, &session);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn apply_algorithm(
+    app: AppHandle,
+    session: State<'_, Mutex<Session>>,
+    algorithm: String,
+) -> Result<(), String> {
+    let algorithm: Algorithm = algorithm.parse().map_err(|e: ParseError| e.to_string())?;
+    let mut session = lock(&session);
+    session
+        .try_push(Action::Algorithm, algorithm)
+        .map_err(|e| e.to_string())?;
+    notify(&app, &session);
+    Ok(())
+}
+
+/// 撤销上一次转动、公式、打乱或求解
+#[tauri::command]
+pub fn undo(app: AppHandle, session: State<'_, Mutex<Session>>) -> Result<(), String> {
+    let mut session = lock(&session);
+    if !session.undo() {
+        return Err("nothing to undo".to_string());
+    }
+    notify(&app, &session);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn redo(app: AppHandle, session: State<'_, Mutex<Session>>) -> Result<(), String> {
+    let mut session = lock(&session);
+    if !session.redo() {
+        return Err("nothing to redo".to_string());
+    }
+    notify(&app, &session);
+    Ok(())
+}
+
+/// 回到执行完前 `index` 条历史记录时的状态，0 为历史开始时
+#[tauri::command]
+pub fn reset_to(
+    app: AppHandle,
+    session: State<'_, Mutex<Session>>,
+    index: usize,
+) -> Result<(), String> {
+    let mut session = lock(&session);
+    session.reset_to(index)?;
+    notify(&app, &session);
+    Ok(())
+}
+
+/// `position`: 已执行的记录数，之后的记录可以重做
+#[tauri::command]
+pub fn history(session: State<'_, Mutex<Session>>) -> SessionHistory {
+    let session = lock(&session);
+    SessionHistory {
+        entries: session.entries().to_vec(),
+        position: session.position(),
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct SessionHistory {
+    entries: Vec<HistoryEntry>,
+    position: usize,
+}
+
+/// 求解当前状态并执行解法。三阶的解法同时载入回放；四阶、五阶用降阶法，
+/// 先还原中心、配对棱块，再按三阶还原；二阶查表给出最优解，忽略 `target`
+/// 和 `method`。非三阶的解法不载入回放。
+/// `method`: 0 层先法（默认），1 两阶段算法，2 CFOP；
+/// `max_length` 和 `time_budget_ms` 只对两阶段算法有效；
+/// `simplify`: 给出时消去多余的转动，0 HTM，1 QTM，2 STM（会合成中层转动），
+/// 只用于三阶；二阶按它选择最优解的计步方式，默认 HTM；
+/// `color_neutral`: 三阶六种底色都试一遍，取步数最少的，解法以整体转动开头
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub fn solve(
+    app: AppHandle,
+    session: State<'_, Mutex<Session>>,
+    player: State<'_, Mutex<Player>>,
+    target: u8,
+    method: Option<u8>,
+    max_length: Option<usize>,
+    time_budget_ms: Option<u64>,
+    simplify: Option<u8>,
+    color_neutral: Option<bool>,
+) -> Result<SolveSolution, SolveFailure> {
+    let mut session = lock(&session);
+    let mut player = lock(&player);
+    let solution = solve_session(
+        &mut session,
+        &mut player,
+        target,
+        method,
+        max_length,
+        time_budget_ms,
+        simplify,
+        color_neutral,
+    )?;
+    notify(&app, &session);
+    if let Some(frame) = player.frame() {
+        emit_frame(&app, &frame);
+    }
+    Ok(solution)
+}
+
+/// 求解并执行，三阶的解法载入回放
+#[allow(clippy::too_many_arguments)]
+fn solve_session(
+    session: &mut Session,
+    player: &mut Player,
+    target: u8,
+    method: Option<u8>,
+    max_length: Option<usize>,
+    time_budget_ms: Option<u64>,
+    simplify: Option<u8>,
+    color_neutral: Option<bool>,
+) -> Result<SolveSolution, SolveFailure> {
+    match session.size() {
+        2 => return solve_pocket(session, player, simplify),
+        4 | 5 => {
+            return solve_reduction(session, player, target, method, max_length, time_budget_ms)
+        }
+        _ => {}
+    }
+    let mut cube = cube_3x3(session).map_err(SolveFailure::unsupported_size)?;
+    let target = solve_target(target)?;
+    let method = solve_method(method, max_length, time_budget_ms)?;
+    let simplify = simplify.map(solve_metric).transpose()?;
+    let mut solution = if color_neutral.unwrap_or(false) {
+        execute_color_neutral(&mut cube, target, method)?
+    } else {
+        execute_with_method(&mut cube, target, method)?
+    };
+    let saved = simplify.map_or(0, |metric| solution.simplify(metric));
+    session.push(Action::Solve, solution.moves());
+    player.load(Playback::from_solution(&solution));
+    Ok(SolveSolution {
+        seq: solution.moves(),
+        saved,
+        htm: solution.htm(),
+        qtm: solution.qtm(),
+        stm: solution.stm(),
+        stages: solution
+            .stages
+            .into_iter()
+            .map(|stage| SolveStage {
+                phase: None,
+                target: Some(stage.target as u8),
+                label: stage.label,
+                seq: stage.moves,
+                state_before: nxn_state_to_u8(&NxnCube::from(&stage.state_before)),
+                state_after: nxn_state_to_u8(&NxnCube::from(&stage.state_after)),
+            })
+            .collect(),
+    })
+}
+
+fn solve_target(target: u8) -> Result<SolveTarget, SolveFailure> {
+    SolveTarget::try_from(target).map_err(|e| SolveFailure::invalid_argument(e.to_string()))
+}
+
+fn solve_method(
+    method: Option<u8>,
+    max_length: Option<usize>,
+    time_budget_ms: Option<u64>,
+) -> Result<SolveMethod, SolveFailure> {
+    let method = SolveMethod::try_from(method.unwrap_or(0))
+        .map_err(|e| SolveFailure::invalid_argument(e.to_string()))?;
+    Ok(match method {
+        SolveMethod::TwoPhase {
+            max_length: default_length,
+            time_budget: default_budget,
+        } => SolveMethod::TwoPhase {
+            max_length: max_length.unwrap_or(default_length),
+            time_budget: time_budget_ms
+                .map(Duration::from_millis)
+                .unwrap_or(default_budget),
         },
-    );
-    color_state_to_u8(&cube)
+        method => method,
+    })
+}
+
+fn solve_metric(metric: u8) -> Result<Metric, SolveFailure> {
+    Metric::try_from(metric).map_err(|e| SolveFailure::invalid_argument(e.to_string()))
+}
+
+fn pocket_cube(session: &Session) -> Result<PocketCube, SolveFailure> {
+    PocketCube::try_from(session.cube()).map_err(|error| match error {
+        CubeError::WrongSize { .. } => SolveFailure::unsupported_size(error.to_string()),
+        error => SolveFailure::from(SolveError::from(error)),
+    })
+}
+
+/// 二阶的最优解，只有一个步骤
+fn solve_pocket(
+    session: &mut Session,
+    player: &mut Player,
+    metric: Option<u8>,
+) -> Result<SolveSolution, SolveFailure> {
+    let cube = pocket_cube(session)?;
+    let solver = PocketSolver::new(solve_metric(metric.unwrap_or(0))?);
+    let seq = Algorithm::from(solver.solve(&cube));
+    let state_before = nxn_state_to_u8(session.cube());
+    session.push(Action::Solve, seq.clone());
+    player.unload();
+    Ok(SolveSolution {
+        htm: seq.length(Metric::Htm),
+        qtm: seq.length(Metric::Qtm),
+        stm: seq.length(Metric::Stm),
+        saved: 0,
+        stages: vec![SolveStage {
+            phase: Some("optimal"),
+            target: None,
+            label: None,
+            seq: seq.clone(),
+            state_before,
+            state_after: nxn_state_to_u8(session.cube()),
+        }],
+        seq,
+    })
+}
+
+/// `distance`: 最短解的步数；`solutions`: 这个步数的不同解法有多少种
+#[derive(Debug, serde::Serialize)]
+pub struct OptimalCount {
+    distance: usize,
+    solutions: u64,
+}
+
+/// 二阶当前状态有多少种最优解，`metric` 同 `solve` 的 `simplify`，默认 HTM
+#[tauri::command]
+pub fn count_optimal_solutions(
+    session: State<'_, Mutex<Session>>,
+    metric: Option<u8>,
+) -> Result<OptimalCount, SolveFailure> {
+    count_session(&lock(&session), metric)
+}
+
+fn count_session(session: &Session, metric: Option<u8>) -> Result<OptimalCount, SolveFailure> {
+    if session.size() != 2 {
+        return Err(SolveFailure::unsupported_size(format!(
+            "optimal solutions are only counted for the 2x2 cube, the current cube is {0}x{0}",
+            session.size()
+        )));
+    }
+    let cube = pocket_cube(session)?;
+    let solver = PocketSolver::new(solve_metric(metric.unwrap_or(0))?);
+    Ok(OptimalCount {
+        distance: solver.distance(&cube),
+        solutions: solver.count_solutions(&cube),
+    })
+}
+
+/// 四阶、五阶的降阶求解。回放只支持三阶，之前载入的回放清空
+fn solve_reduction(
+    session: &mut Session,
+    player: &mut Player,
+    target: u8,
+    method: Option<u8>,
+    max_length: Option<usize>,
+    time_budget_ms: Option<u64>,
+) -> Result<SolveSolution, SolveFailure> {
+    let mut cube = session.cube().clone();
+    let method = solve_method(method, max_length, time_budget_ms)?;
+    let solution = execute_reduction(&mut cube, solve_target(target)?, method)?;
+    let seq = solution.moves();
+    session.push(Action::Solve, seq.clone());
+    player.unload();
+    Ok(SolveSolution {
+        htm: seq.length(Metric::Htm),
+        qtm: seq.length(Metric::Qtm),
+        stm: seq.length(Metric::Stm),
+        seq,
+        saved: 0,
+        stages: solution
+            .stages
+            .into_iter()
+            .map(|stage| SolveStage {
+                phase: match stage.phase {
+                    ReductionPhase::Centers => Some("centers"),
+                    ReductionPhase::Edges => Some("edges"),
+                    ReductionPhase::OllParity => Some("oll_parity"),
+                    ReductionPhase::PllParity => Some("pll_parity"),
+                    ReductionPhase::Stage(_) => None,
+                },
+                target: match stage.phase {
+                    ReductionPhase::Stage(target) => Some(target as u8),
+                    _ => None,
+                },
+                label: stage.label,
+                seq: stage.moves,
+                state_before: nxn_state_to_u8(&stage.state_before),
+                state_after: nxn_state_to_u8(&stage.state_after),
+            })
+            .collect(),
+    })
+}
+
+fn emit_frame(app: &AppHandle, frame: &PlaybackFrame) {
+    if let Err(e) = app.emit("playback-tick", frame.clone()) {
+        eprintln!("failed to emit playback-tick: {}", e);
+    }
+}
+
+fn update_playback(
+    app: &AppHandle,
+    player: &State<'_, Mutex<Player>>,
+    action: impl FnOnce(&mut Playback),
+) -> Result<PlaybackFrame, String> {
+    let frame = lock(player).update(action)?;
+    emit_frame(app, &frame);
+    Ok(frame)
+}
+
+/// 最近一次求解的回放位置，还没有求解过时为 null
+#[tauri::command]
+pub fn playback_state(player: State<'_, Mutex<Player>>) -> Option<PlaybackFrame> {
+    lock(&player).frame()
+}
+
+/// 跳到执行完前 `position` 步时的状态
+#[tauri::command]
+pub fn playback_seek(
+    app: AppHandle,
+    player: State<'_, Mutex<Player>>,
+    position: usize,
+) -> Result<PlaybackFrame, String> {
+    update_playback(&app, &player, |playback| playback.seek(position))
 }
 
+/// 前进或后退一步
 #[tauri::command]
-pub fn solve(state: [[[u8; 3]; 3]; 6], target: u8) -> SolveSolution {
-    let mut cube = u8_to_color_state(state);
-    let target = SolveTarget::from_u8(target);
-    let result = execute(&mut cube, target);
-    println!("cube after solve: {:?}", cube);
-    SolveSolution {
-        seq: result.0,
-        cube: color_state_to_u8(&result.1),
+pub fn playback_step(
+    app: AppHandle,
+    player: State<'_, Mutex<Player>>,
+    forward: bool,
+) -> Result<PlaybackFrame, String> {
+    update_playback(&app, &player, |playback| {
+        if forward {
+            playback.step_forward();
+        } else {
+            playback.step_backward();
+        }
+    })
+}
+
+/// 跳到下一个阶段的开头，或者当前（已在开头时为上一个）阶段的开头
+#[tauri::command]
+pub fn playback_stage(
+    app: AppHandle,
+    player: State<'_, Mutex<Player>>,
+    forward: bool,
+) -> Result<PlaybackFrame, String> {
+    update_playback(&app, &player, |playback| {
+        if forward {
+            playback.next_stage();
+        } else {
+            playback.previous_stage();
+        }
+    })
+}
+
+/// 自动播放，每隔 `interval_ms`（默认 500）毫秒前进一步并发出 `playback-tick`；
+/// 播放中再次调用可以改变速度
+#[tauri::command]
+pub fn playback_play(
+    app: AppHandle,
+    player: State<'_, Mutex<Player>>,
+    interval_ms: Option<u64>,
+) -> Result<PlaybackFrame, String> {
+    let interval = interval_ms.map_or(DEFAULT_INTERVAL, Duration::from_millis);
+    let (generation, frame) = {
+        let mut player = lock(&player);
+        let generation = player.play()?;
+        (generation, player.frame().unwrap())
+    };
+    emit_frame(&app, &frame);
+
+    let app = app.clone();
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        let player = app.state::<Mutex<Player>>();
+        let Some(frame) = lock(&player).tick(generation) else {
+            break;
+        };
+        emit_frame(&app, &frame);
+        if !frame.playing {
+            break;
+        }
+    });
+    Ok(frame)
+}
+
+#[tauri::command]
+pub fn playback_pause(
+    app: AppHandle,
+    player: State<'_, Mutex<Player>>,
+) -> Result<PlaybackFrame, String> {
+    let frame = {
+        let mut player = lock(&player);
+        player.pause();
+        player
+            .frame()
+            .ok_or_else(|| "no solution loaded".to_string())?
+    };
+    emit_frame(&app, &frame);
+    Ok(frame)
+}
+
+/// 求解失败时交给前端的错误：`kind` 为 invalid_cube、stuck、
+/// previous_unsolved、unsupported_size（不是三阶，也不能降阶）或
+/// invalid_argument（参数超出范围），后几项是
+/// 出错的步骤、当时的三阶状态和已经执行的转动。降阶后的三阶出错时状态是
+/// 降阶得到的三阶
+#[derive(Debug, serde::Serialize)]
+pub struct SolveFailure {
+    kind: &'static str,
+    message: String,
+    stage: Option<u8>,
+    state: Option<[[[u8; 3]; 3]; 6]>,
+    moves: Option<Algorithm>,
+}
+
+impl SolveFailure {
+    fn invalid_argument(message: String) -> Self {
+        SolveFailure {
+            kind: "invalid_argument",
+            message,
+            stage: None,
+            state: None,
+            moves: None,
+        }
+    }
+
+    fn unsupported_size(message: String) -> Self {
+        SolveFailure {
+            kind: "unsupported_size",
+            message,
+            stage: None,
+            state: None,
+            moves: None,
+        }
+    }
+}
+
+impl From<ReductionError> for SolveFailure {
+    fn from(error: ReductionError) -> Self {
+        match error {
+            ReductionError::UnsupportedSize(_) => SolveFailure::unsupported_size(error.to_string()),
+            ReductionError::InvalidPieces(_) => SolveFailure {
+                kind: "invalid_cube",
+                message: error.to_string(),
+                stage: None,
+                state: None,
+                moves: None,
+            },
+            ReductionError::Solve(error) => SolveFailure::from(error),
+        }
     }
 }
 
+impl From<SolveError> for SolveFailure {
+    fn from(error: SolveError) -> Self {
+        SolveFailure {
+            kind: match error {
+                SolveError::InvalidCube(_) => "invalid_cube",
+                SolveError::Stuck { .. } => "stuck",
+                SolveError::PreviousUnsolved { .. } => "previous_unsolved",
+            },
+            message: error.to_string(),
+            stage: error.stage().map(|stage| stage as u8),
+            state: error.state().map(color_state_to_u8),
+            moves: error.moves().cloned(),
+        }
+    }
+}
+
+/// 一个步骤的公式，以及执行前后的魔方状态，前端用来逐步演示。三阶方法的
+/// 步骤给出 `target`；其他步骤给出 `phase`：降阶的 centers、edges、
+/// oll_parity、pll_parity，或二阶的 optimal
+#[derive(serde::Serialize)]
+pub struct SolveStage {
+    phase: Option<&'static str>,
+    target: Option<u8>,
+    label: Option<String>,
+    seq: Algorithm,
+    state_before: Vec<Vec<Vec<u8>>>,
+    state_after: Vec<Vec<Vec<u8>>>,
+}
+
+/// `htm`/`qtm`/`stm`: 整个解法按三种计步方式的步数；
+/// `saved`: 化简省下的步数，按化简时选的计步方式
 #[derive(serde::Serialize)]
 pub struct SolveSolution {
-    seq: Vec<char>,
-    cube: [[[u8; 3]; 3]; 6],
+    seq: Algorithm,
+    stages: Vec<SolveStage>,
+    saved: u32,
+    htm: u32,
+    qtm: u32,
+    stm: u32,
 }
 
 #[cfg(test)]
 mod tests {
     use rubik_cube_core::cube::Cube;
     use rubik_cube_shuffler::CubeShuffler;
+    use rubik_cube_solver::{solver::execute, utils::u8_to_color_state};
 
     use super::*;
 
@@ -71,7 +854,415 @@ mod tests {
         let mut shuffler = CubeShuffler::new(&mut cube);
         shuffler.shuffle(20);
         // print_cube(&cube);
-        let res = execute(&mut cube, SolveTarget::TopEdge);
-        assert!(res.1.is_solved());
+        let res = execute(&mut cube, SolveTarget::TopEdge).unwrap();
+        assert!(res.final_state().is_solved());
+    }
+
+    #[test]
+    fn test_solve_two_phase() {
+        let mut cube = Cube::new();
+        let mut shuffler = CubeShuffler::new(&mut cube);
+        shuffler.shuffle(20);
+        let res =
+            execute_with_method(&mut cube, SolveTarget::TopEdge, SolveMethod::two_phase()).unwrap();
+        assert!(res.final_state().is_solved());
+        assert!(res.htm() <= 22);
+    }
+
+    /// 会话从一个 20 步打乱开始
+    fn shuffled_session() -> Session {
+        let mut cube = Cube::new();
+        CubeShuffler::new(&mut cube).shuffle(20);
+        Session::new(cube)
+    }
+
+    #[test]
+    fn test_solve_simplified() {
+        let session = shuffled_session();
+        let plain = solve_session(
+            &mut Session::new(session.cube().clone()),
+            &mut Player::default(),
+            SolveTarget::TopEdge as u8,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let mut simplified_session = Session::new(session.cube().clone());
+        let simplified = solve_session(
+            &mut simplified_session,
+            &mut Player::default(),
+            SolveTarget::TopEdge as u8,
+            None,
+            None,
+            None,
+            Some(0),
+            None,
+        )
+        .unwrap();
+        assert_eq!(plain.saved, 0);
+        assert!(simplified.saved > 0);
+        assert_eq!(simplified.htm + simplified.saved, plain.htm);
+        assert!(simplified_session.cube().is_solved());
+    }
+
+    #[test]
+    fn test_shuffle_replays_seed() {
+        let mut first_session = Session::default();
+        let first = shuffle_session(&mut first_session, Some(20), None, None, None).unwrap();
+        let mut again_session = Session::default();
+        let again =
+            shuffle_session(&mut again_session, Some(20), Some(first.seed), None, None).unwrap();
+        assert_eq!(first_session.cube(), again_session.cube());
+        assert_eq!(first.scramble, again.scramble);
+        assert_eq!(first.scramble.len(), 20);
+
+        let mut session = Session::default();
+        let random =
+            shuffle_session(&mut session, None, Some(first.seed), Some(true), None).unwrap();
+        assert!(random.scramble.len() <= 22);
+        assert!(!session.cube().is_solved());
+
+        let mut session = Session::default();
+        shuffle_session(&mut session, None, None, None, Some(SolveTarget::F2l as u8)).unwrap();
+        assert!(SolveTarget::F2l.is_reached(&session.cube().to_cube().unwrap()));
+        assert_eq!(
+            shuffle_session(&mut session, None, None, None, Some(11)).err(),
+            Some("unknown stage 11".to_string())
+        );
+        assert_eq!(session.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_shuffle_other_sizes() {
+        let mut session = Session::new(NxnCube::new(4));
+        let result = shuffle_session(&mut session, None, Some(7), None, None).unwrap();
+        assert_eq!(result.scramble.len(), 40);
+        assert!(!session.cube().is_solved());
+        assert!(shuffle_session(&mut session, None, None, Some(true), None).is_err());
+
+        let changed = CubeChanged::from(&session);
+        assert_eq!(changed.size, 4);
+        assert!(changed.cube.iter().flatten().all(|row| row.len() == 4));
+
+        let mut session = Session::new(NxnCube::new(6));
+        shuffle_session(&mut session, None, Some(7), None, None).unwrap();
+        let failure = solve_session(
+            &mut session,
+            &mut Player::default(),
+            SolveTarget::TopEdge as u8,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(failure.kind, "unsupported_size");
+        assert_eq!(session.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_solve_color_neutral() {
+        // R2 不动橙色十字，转到底面就够了
+        let mut session = Session::default();
+        session.push(Action::Algorithm, "R2".parse().unwrap());
+        let solution = solve_session(
+            &mut session,
+            &mut Player::default(),
+            SolveTarget::BottomCross as u8,
+            None,
+            None,
+            None,
+            None,
+            Some(true),
+        )
+        .unwrap();
+        assert_eq!(solution.seq.to_string(), "z'");
+        assert_eq!(solution.htm, 0);
+        assert_eq!(session.entries().len(), 2);
+    }
+
+    #[test]
+    fn test_solve_2x2_optimally() {
+        let mut session = Session::new(NxnCube::new(2));
+        session.push(Action::Algorithm, "R U R' F2".parse().unwrap());
+        let count = count_session(&session, None).unwrap();
+        assert_eq!(count.distance, 4);
+        assert!(count.solutions >= 1);
+
+        let solution = solve_session(
+            &mut session,
+            &mut Player::default(),
+            0,
+            None,
+            None,
+            None,
+            Some(1),
+            None,
+        )
+        .unwrap();
+        assert!(session.cube().is_solved());
+        assert_eq!(solution.stages.len(), 1);
+        assert_eq!(solution.stages[0].phase, Some("optimal"));
+        assert_eq!(solution.qtm, 5);
+        assert_eq!(count_session(&session, None).unwrap().distance, 0);
+        assert_eq!(
+            count_session(&Session::default(), None).unwrap_err().kind,
+            "unsupported_size"
+        );
+        assert_eq!(
+            count_session(&session, Some(5)).unwrap_err().kind,
+            "invalid_argument"
+        );
+    }
+
+    #[test]
+    fn test_solve_by_reduction() {
+        for size in [4, 5] {
+            let mut session = Session::new(NxnCube::new(size));
+            shuffle_session(&mut session, None, Some(3), None, None).unwrap();
+            let mut player = Player::default();
+            player.load(Playback::from_solution(
+                &execute_with_method(&mut Cube::new(), SolveTarget::Pll, SolveMethod::Cfop)
+                    .unwrap(),
+            ));
+            let solution = solve_session(
+                &mut session,
+                &mut player,
+                SolveTarget::Pll as u8,
+                Some(2),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+            assert!(session.cube().is_solved());
+            assert_eq!(session.entries().len(), 2);
+            assert!(player.frame().is_none());
+            let phases: Vec<_> = solution.stages.iter().map(|stage| stage.phase).collect();
+            assert_eq!(&phases[..2], [Some("centers"), Some("edges")]);
+            assert_eq!(
+                solution.stages.last().unwrap().target,
+                Some(SolveTarget::Pll as u8)
+            );
+            assert!(solution.stages[0]
+                .state_before
+                .iter()
+                .flatten()
+                .all(|row| row.len() == size));
+        }
+    }
+
+    #[test]
+    fn test_session_records_commands() {
+        let mut session = Session::default();
+        session.push(Action::Turn, "R".parse().unwrap());
+        let turned = session.cube().clone();
+        shuffle_session(&mut session, Some(20), None, None, None).unwrap();
+        let shuffled = session.cube().clone();
+        solve_session(
+            &mut session,
+            &mut Player::default(),
+            SolveTarget::TopEdge as u8,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(session.cube().is_solved());
+        assert_eq!(session.entries().len(), 3);
+
+        session.undo();
+        assert_eq!(session.cube(), &shuffled);
+        session.reset_to(1).unwrap();
+        assert_eq!(session.cube(), &turned);
+
+        let changed = CubeChanged::from(&session);
+        let expected = color_state_to_u8(&turned.to_cube().unwrap());
+        assert!(changed.cube.iter().flatten().eq(expected.iter().flatten()));
+        assert_eq!((changed.position, changed.length), (1, 3));
+    }
+
+    #[test]
+    fn test_init_from_facelets() {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&"R U R' F2".parse().unwrap());
+        let western = ColorScheme::WESTERN;
+        for letters in [FaceletLetters::Faces, FaceletLetters::Colors] {
+            assert_eq!(
+                cube_from_facelets(&cube.to_facelets(letters), western).unwrap(),
+                cube
+            );
+        }
+        // 格式正确但 UF 棱块翻转了
+        let flipped = "UUUUUUUFURRRRRRRRRFUFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+        assert!(cube_from_facelets(flipped, western).is_err());
+        assert!(cube_from_facelets("UUU", western).is_err());
+
+        let japanese = Cube::with_scheme(ColorScheme::JAPANESE);
+        let letters = japanese.to_facelets(FaceletLetters::Faces);
+        assert_eq!(
+            cube_from_facelets(&letters, ColorScheme::JAPANESE).unwrap(),
+            japanese
+        );
+    }
+
+    #[test]
+    fn test_color_scheme() {
+        assert_eq!(color_scheme(None, None), Ok(ColorScheme::WESTERN));
+        assert_eq!(color_scheme(Some(1), None), Ok(ColorScheme::JAPANESE));
+        assert_eq!(
+            color_scheme(Some(1), Some([0, 1, 2, 3, 4, 5])),
+            Ok(ColorScheme::WESTERN)
+        );
+        assert!(color_scheme(None, Some([0, 0, 2, 3, 4, 5])).is_err());
+        assert!(color_scheme(None, Some([0, 1, 2, 3, 4, 9])).is_err());
+        assert_eq!(
+            color_scheme(Some(2), None),
+            Err("unknown colour scheme 2".to_string())
+        );
+
+        // 日式配色的会话照样能求解，重置后还是日式
+        let mut cube = Cube::with_scheme(ColorScheme::JAPANESE);
+        cube.apply_algorithm(&"R U F' L2 D B'".parse().unwrap());
+        let mut session = Session::new(cube);
+        solve_session(
+            &mut session,
+            &mut Player::default(),
+            SolveTarget::TopEdge as u8,
+            Some(2),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            session.cube(),
+            &NxnCube::from(&Cube::with_scheme(ColorScheme::JAPANESE))
+        );
+        assert_eq!(session.scheme(), ColorScheme::JAPANESE);
+    }
+
+    #[test]
+    fn test_init_from_colors() {
+        let mut cube = Cube::new();
+        cube.apply_algorithm(&"R U R' F2".parse().unwrap());
+        let mut colors = color_state_to_u8(&cube).map(|face| face.map(|row| row.map(Some)));
+        colors[2][0][0] = None;
+        colors[5][1][1] = None;
+        assert_eq!(cube_from_colors(colors).unwrap(), cube);
+
+        // 翻转 UF 棱块
+        let mut colors = color_state_to_u8(&Cube::new()).map(|face| face.map(|row| row.map(Some)));
+        colors[0][2][1] = Some(Color::Blue as u8);
+        colors[2][0][1] = Some(Color::Yellow as u8);
+        let failure = cube_from_colors(colors).err().unwrap();
+        assert_eq!(failure.suggestions.len(), 1);
+        assert_eq!(failure.suggestions[0].facelets, vec![[0, 2, 1], [2, 0, 1]]);
+
+        colors[0][0][0] = Some(9);
+        let failure = cube_from_colors(colors).err().unwrap();
+        assert_eq!(failure.suggestions[0].message, "invalid color value: 9");
+    }
+
+    #[test]
+    fn test_solve_invalid_input() {
+        let mut state = color_state_to_u8(&Cube::new());
+        state[0][2][1] = state[2][0][1];
+        state[2][0][1] = state[0][1][1];
+        let top_edge = SolveTarget::TopEdge as u8;
+        // (情形, 会话, 步骤, 方法, 化简的计数方式, 错误类型)
+        let cases = [
+            (
+                "invalid state",
+                Session::new(u8_to_color_state(state)),
+                top_edge,
+                None,
+                None,
+                "invalid_cube",
+            ),
+            (
+                "unknown target",
+                Session::default(),
+                11,
+                None,
+                None,
+                "invalid_argument",
+            ),
+            (
+                "unknown target on 4x4",
+                Session::new(NxnCube::new(4)),
+                11,
+                None,
+                None,
+                "invalid_argument",
+            ),
+            (
+                "unknown method",
+                shuffled_session(),
+                top_edge,
+                Some(3),
+                None,
+                "invalid_argument",
+            ),
+            (
+                "unknown metric",
+                shuffled_session(),
+                top_edge,
+                None,
+                Some(3),
+                "invalid_argument",
+            ),
+        ];
+        for (case, mut session, target, method, simplify, kind) in cases {
+            let failure = solve_session(
+                &mut session,
+                &mut Player::default(),
+                target,
+                method,
+                None,
+                None,
+                simplify,
+                None,
+            )
+            .err()
+            .unwrap();
+            assert_eq!(failure.kind, kind, "{}", case);
+            assert_eq!(failure.stage, None, "{}", case);
+            assert!(session.entries().is_empty(), "{}", case);
+        }
+    }
+
+    #[test]
+    fn test_solve_cfop_stages() {
+        let mut session = shuffled_session();
+        let state = nxn_state_to_u8(session.cube());
+        let solution = solve_session(
+            &mut session,
+            &mut Player::default(),
+            SolveTarget::Pll as u8,
+            Some(2),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(session.cube().is_solved());
+        assert!(solution.stages.iter().all(|stage| stage.target >= Some(7)));
+        assert_eq!(solution.stages.first().unwrap().state_before, state);
+        for pair in solution.stages.windows(2) {
+            assert_eq!(pair[0].state_after, pair[1].state_before);
+        }
+        assert!(solution.stm as usize <= solution.seq.len());
+        assert!(solution.htm >= solution.stm && solution.qtm >= solution.htm);
     }
 }
@@ -0,0 +1,222 @@
+//! 魔方会话：当前状态以及执行过的操作，支持撤销、重做和回到任意一步。
+//! 魔方可以是 2 到 7 阶，历史里的转动都适用于当前阶数
+
+use rubik_cube_core::cube::{
+    error::CubeError, notation::Algorithm, nxn::NxnCube, scheme::ColorScheme,
+};
+
+/// 产生一条历史记录的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Turn,
+    Algorithm,
+    Shuffle,
+    Solve,
+}
+
+/// 一次操作执行的全部转动，撤销时整体撤销
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HistoryEntry {
+    pub action: Action,
+    pub moves: Algorithm,
+}
+
+pub struct Session {
+    /// 历史开始时的状态
+    start: NxnCube,
+    /// 开始状态的配色，重置、换阶数时沿用
+    scheme: ColorScheme,
+    cube: NxnCube,
+    entries: Vec<HistoryEntry>,
+    /// 已执行的记录数，之后的记录可以重做
+    position: usize,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session::new(NxnCube::new(3))
+    }
+}
+
+impl Session {
+    pub fn new(cube: impl Into<NxnCube>) -> Self {
+        let cube = cube.into();
+        Session {
+            scheme: cube.scheme().unwrap_or_default(),
+            start: cube.clone(),
+            cube,
+            entries: vec![],
+            position: 0,
+        }
+    }
+
+    pub fn cube(&self) -> &NxnCube {
+        &self.cube
+    }
+
+    pub fn size(&self) -> usize {
+        self.cube.size()
+    }
+
+    pub fn scheme(&self) -> ColorScheme {
+        self.scheme
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// 执行并记录一次操作，丢弃可以重做的记录
+    pub fn push(&mut self, action: Action, moves: Algorithm) {
+        self.cube.apply_algorithm(&moves);
+        self.record(action, moves);
+    }
+
+    /// 和 push 一样，但有转动超出当前阶数时不执行也不记录，返回错误
+    pub fn try_push(&mut self, action: Action, moves: Algorithm) -> Result<(), CubeError> {
+        self.cube.try_apply_algorithm(&moves)?;
+        self.record(action, moves);
+        Ok(())
+    }
+
+    fn record(&mut self, action: Action, moves: Algorithm) {
+        self.entries.truncate(self.position);
+        self.entries.push(HistoryEntry { action, moves });
+        self.position += 1;
+    }
+
+    /// 撤销上一次操作，没有可撤销的操作时返回 false
+    pub fn undo(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.position -= 1;
+        let inverse = self.entries[self.position].moves.inverse();
+        self.cube.apply_algorithm(&inverse);
+        true
+    }
+
+    /// 重做上一次撤销的操作，没有可重做的操作时返回 false
+    pub fn redo(&mut self) -> bool {
+        if self.position == self.entries.len() {
+            return false;
+        }
+        self.cube
+            .apply_algorithm(&self.entries[self.position].moves);
+        self.position += 1;
+        true
+    }
+
+    /// 回到执行完前 `index` 条记录时的状态，之后的记录仍可重做
+    pub fn reset_to(&mut self, index: usize) -> Result<(), String> {
+        if index > self.entries.len() {
+            return Err(format!(
+                "history index {} out of range 0..={}",
+                index,
+                self.entries.len()
+            ));
+        }
+        self.cube = self.start.clone();
+        for entry in &self.entries[..index] {
+            self.cube.apply_algorithm(&entry.moves);
+        }
+        self.position = index;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rubik_cube_core::cube::Cube;
+
+    use super::*;
+
+    fn alg(moves: &str) -> Algorithm {
+        moves.parse().unwrap()
+    }
+
+    #[test]
+    fn undo_and_redo_restore_states() {
+        let mut session = Session::default();
+        session.push(Action::Turn, alg("R"));
+        let after_turn = session.cube().clone();
+        session.push(Action::Algorithm, alg("U F2 M' y"));
+        let after_algorithm = session.cube().clone();
+
+        assert!(session.undo());
+        assert_eq!(session.cube(), &after_turn);
+        assert!(session.undo());
+        assert!(session.cube().is_solved());
+        assert!(!session.undo());
+
+        assert!(session.redo());
+        assert!(session.redo());
+        assert_eq!(session.cube(), &after_algorithm);
+        assert!(!session.redo());
+    }
+
+    #[test]
+    fn new_actions_drop_the_redo_history() {
+        let mut session = Session::default();
+        session.push(Action::Turn, alg("R"));
+        session.push(Action::Turn, alg("U"));
+        session.undo();
+        session.push(Action::Turn, alg("F"));
+        assert_eq!(session.entries().len(), 2);
+        assert_eq!(session.entries()[1].moves, alg("F"));
+        assert!(!session.redo());
+    }
+
+    #[test]
+    fn resets_to_any_entry() {
+        let mut session = Session::default();
+        for moves in ["R", "U", "F"] {
+            session.push(Action::Turn, alg(moves));
+        }
+        session.reset_to(1).unwrap();
+        let mut expected = NxnCube::new(3);
+        expected.apply_algorithm(&alg("R"));
+        assert_eq!(session.cube(), &expected);
+        assert_eq!(session.position(), 1);
+
+        session.reset_to(3).unwrap();
+        expected.apply_algorithm(&alg("U F"));
+        assert_eq!(session.cube(), &expected);
+        assert!(session.reset_to(4).is_err());
+    }
+
+    #[test]
+    fn keeps_any_size() {
+        let mut session = Session::new(NxnCube::new(5));
+        session.push(Action::Algorithm, alg("2R 3Uw' F"));
+        assert_eq!(session.size(), 5);
+        session.undo();
+        assert!(session.cube().is_solved());
+
+        let session = Session::new(Cube::new());
+        assert_eq!(session.size(), 3);
+    }
+
+    #[test]
+    fn refuses_moves_for_bigger_cubes() {
+        let mut session = Session::default();
+        assert!(session.try_push(Action::Algorithm, alg("R 4Rw")).is_err());
+        assert!(session.cube().is_solved());
+        assert!(session.entries().is_empty());
+        assert!(session.try_push(Action::Algorithm, alg("R 2R")).is_ok());
+        assert_eq!(session.position(), 1);
+    }
+
+    #[test]
+    fn takes_the_scheme_from_the_start() {
+        let mut session = Session::new(NxnCube::with_scheme(4, ColorScheme::JAPANESE));
+        session.push(Action::Algorithm, alg("Rw U2 F'"));
+        assert_eq!(session.scheme(), ColorScheme::JAPANESE);
+        assert_eq!(Session::default().scheme(), ColorScheme::WESTERN);
+    }
+}
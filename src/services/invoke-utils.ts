@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
 
 interface Response<T> {
   success: boolean;
@@ -6,6 +7,82 @@ interface Response<T> {
   error?: string;
 }
 
+interface ShuffleResult {
+  scramble: string;
+  seed: number;
+}
+
+interface SolveStage {
+  phase: 'centers' | 'edges' | 'oll_parity' | 'pll_parity' | 'optimal' | null;
+  target: number | null;
+  label: string | null;
+  seq: string;
+  state_before: number[][][];
+  state_after: number[][][];
+}
+
+interface SolveFailure {
+  kind: 'invalid_cube' | 'stuck' | 'previous_unsolved' | 'unsupported_size' | 'invalid_argument';
+  message: string;
+  stage: number | null;
+  state: number[][][] | null;
+  moves: string | null;
+}
+
+interface SolveResponse extends Response<SolveSolution> {
+  failure?: SolveFailure;
+}
+
+interface SolveSolution {
+  seq: string;
+  stages: SolveStage[];
+  saved: number;
+  htm: number;
+  qtm: number;
+  stm: number;
+}
+
+interface OptimalCount {
+  distance: number;
+  solutions: number;
+}
+
+interface CubeChanged {
+  size: number;
+  cube: number[][][];
+  position: number;
+  length: number;
+}
+
+interface InitSuggestion {
+  message: string;
+  facelets: [number, number, number][];
+}
+
+interface InitResponse extends Response<void> {
+  suggestions?: InitSuggestion[];
+}
+
+interface PlaybackFrame {
+  position: number;
+  length: number;
+  stage: number;
+  stage_count: number;
+  last_move: string | null;
+  cube: number[][][];
+  playing: boolean;
+}
+
+interface HistoryEntry {
+  action: 'turn' | 'algorithm' | 'shuffle' | 'solve';
+  moves: string;
+}
+
+interface SessionHistory {
+  entries: HistoryEntry[];
+  position: number;
+}
+
 export default class TauriService {
   private static formatError(error: unknown, prefix: string): string {
     return `${prefix}: ${error instanceof Error ? error.message : String(error)}`;
@@ -29,15 +106,32 @@ export default class TauriService {
     }
   }
 
-  static async solveLayer(params: { state: number[][][], target: number }): Promise<Response<{seq: String[], cube: number[][][]}>> {
+  static async solveLayer(params: { target: number, method?: number, maxLength?: number, timeBudgetMs?: number, simplify?: number, colorNeutral?: boolean }): Promise<SolveResponse> {
     try {
-      const steps = await invoke<{seq: String[], cube: number[][][]}>('solve', params);
+      const steps = await invoke<SolveSolution>('solve', params);
       return this.createSuccessResponse(steps);
     } catch (error: unknown) {
+      if (typeof error === 'object' && error !== null && 'kind' in error) {
+        const failure = error as SolveFailure;
+        return { success: false, error: `${params.target}求解失败: ${failure.message}`, failure };
+      }
       return this.createErrorResponse(error, `${params.target}求解失败`);
     }
   }
 
+  // 二阶当前状态的最优解个数，metric: 0 HTM（默认），1 QTM
+  static async countOptimalSolutions(params: { metric?: number }): Promise<Response<OptimalCount>> {
+    try {
+      const count = await invoke<OptimalCount>('count_optimal_solutions', params);
+      return this.createSuccessResponse(count);
+    } catch (error: unknown) {
+      if (typeof error === 'object' && error !== null && 'message' in error) {
+        return { success: false, error: (error as SolveFailure).message };
+      }
+      return this.createErrorResponse(error, '统计最优解失败');
+    }
+  }
+
   static async getWindowSize(): Promise<Response<{ width: number; height: number }>> {
     try {
       const [width, height] = await invoke<number[]>('get_window_size');
@@ -47,31 +141,184 @@ export default class TauriService {
     }
   }
 
-  static async initCubeState(): Promise<Response<number[][][]>> {
+  static async initCubeState(): Promise<Response<CubeChanged>> {
     try {
-      const state = await invoke<number[][][]>('init_get_get_state');
+      const state = await invoke<CubeChanged>('init_get_get_state');
       return this.createSuccessResponse(state);
     } catch (error: unknown) {
       return this.createErrorResponse(error, '初始化魔方状态失败');
     }
   }
 
-  static async handleRotation(params: { state: number[][][], face: number, direction: boolean }): Promise<Response<number[][][]>> {
+  static async reset(): Promise<Response<void>> {
     try {
-      const result = await invoke<number[][][]>('turn', params);
+      await invoke('reset');
+      return this.createSuccessResponse(undefined);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '初始化魔方状态失败');
+    }
+  }
+
+  // 换阶数后魔方回到还原状态，历史清空
+  static async setSize(params: { size: number }): Promise<Response<void>> {
+    try {
+      await invoke('set_size', params);
+      return this.createSuccessResponse(undefined);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '切换阶数失败');
+    }
+  }
+
+  // scheme: 0 西式（默认），1 日式；colors 按 U D F B L R 顺序自定义，优先于 scheme
+  static async setColorScheme(params: { scheme?: number, colors?: number[] }): Promise<Response<void>> {
+    try {
+      await invoke('set_color_scheme', params);
+      return this.createSuccessResponse(undefined);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '切换配色失败');
+    }
+  }
+
+  static async initFromFacelets(params: { facelets: string }): Promise<Response<void>> {
+    try {
+      await invoke('init_from_facelets', params);
+      return this.createSuccessResponse(undefined);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '导入状态失败');
+    }
+  }
+
+  static async exportFacelets(params: { colors?: boolean }): Promise<Response<string>> {
+    try {
+      const result = await invoke<string>('export_facelets', params);
       return this.createSuccessResponse(result);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '导出状态失败');
+    }
+  }
+
+  // colors 中 null 表示还没填的贴纸
+  static async initFromColors(params: { colors: (number | null)[][][] }): Promise<InitResponse> {
+    try {
+      await invoke('init_from_colors', params);
+      return this.createSuccessResponse(undefined);
+    } catch (error: unknown) {
+      if (typeof error === 'object' && error !== null && 'suggestions' in error) {
+        const { suggestions } = error as { suggestions: InitSuggestion[] };
+        const messages = suggestions.map((suggestion) => suggestion.message).join('; ');
+        return { success: false, error: `颜色有误: ${messages}`, suggestions };
+      }
+      return this.createErrorResponse(error, '按颜色初始化失败');
+    }
+  }
+
+  static onCubeChanged(callback: (changed: CubeChanged) => void): Promise<UnlistenFn> {
+    return listen<CubeChanged>('cube-changed', (event) => callback(event.payload));
+  }
+
+  static async handleRotation(params: { face: number, direction: boolean }): Promise<Response<void>> {
+    try {
+      await invoke('turn', params);
+      return this.createSuccessResponse(undefined);
     } catch (error: unknown) {
       return this.createErrorResponse(error, '魔方旋转失败');
     }
   }
 
-    static async handleShuffle(params: { state: number[][][], times: number }): Promise<Response<number[][][]>> {
+  static async applyAlgorithm(params: { algorithm: string }): Promise<Response<void>> {
+    try {
+      await invoke('apply_algorithm', params);
+      return this.createSuccessResponse(undefined);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '执行公式失败');
+    }
+  }
+
+    static async handleShuffle(params: { times?: number, seed?: number, randomState?: boolean, solved?: number }): Promise<Response<ShuffleResult>> {
     try {
-      const result = await invoke<number[][][]>('shuffle', params);
+      const result = await invoke<ShuffleResult>('shuffle', params);
       return this.createSuccessResponse(result);
     } catch (error: unknown) {
       return this.createErrorResponse(error, '魔方打乱失败');
     }
   }
 
-}
\ No newline at end of file
+  static async undo(): Promise<Response<void>> {
+    try {
+      await invoke('undo');
+      return this.createSuccessResponse(undefined);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '撤销失败');
+    }
+  }
+
+  static async redo(): Promise<Response<void>> {
+    try {
+      await invoke('redo');
+      return this.createSuccessResponse(undefined);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '重做失败');
+    }
+  }
+
+  static async resetTo(params: { index: number }): Promise<Response<void>> {
+    try {
+      await invoke('reset_to', params);
+      return this.createSuccessResponse(undefined);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '回退失败');
+    }
+  }
+
+  static async history(): Promise<Response<SessionHistory>> {
+    try {
+      const result = await invoke<SessionHistory>('history');
+      return this.createSuccessResponse(result);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '获取历史失败');
+    }
+  }
+
+  static async playbackState(): Promise<Response<PlaybackFrame | null>> {
+    try {
+      const result = await invoke<PlaybackFrame | null>('playback_state');
+      return this.createSuccessResponse(result);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '获取回放状态失败');
+    }
+  }
+
+  static async playbackSeek(params: { position: number }): Promise<Response<PlaybackFrame>> {
+    return this.playbackCommand('playback_seek', params);
+  }
+
+  static async playbackStep(params: { forward: boolean }): Promise<Response<PlaybackFrame>> {
+    return this.playbackCommand('playback_step', params);
+  }
+
+  static async playbackStage(params: { forward: boolean }): Promise<Response<PlaybackFrame>> {
+    return this.playbackCommand('playback_stage', params);
+  }
+
+  static async playbackPlay(params: { intervalMs?: number }): Promise<Response<PlaybackFrame>> {
+    return this.playbackCommand('playback_play', params);
+  }
+
+  static async playbackPause(): Promise<Response<PlaybackFrame>> {
+    return this.playbackCommand('playback_pause', {});
+  }
+
+  private static async playbackCommand(command: string, params: Record<string, unknown>): Promise<Response<PlaybackFrame>> {
+    try {
+      const result = await invoke<PlaybackFrame>(command, params);
+      return this.createSuccessResponse(result);
+    } catch (error: unknown) {
+      return this.createErrorResponse(error, '回放失败');
+    }
+  }
+
+  static onPlaybackTick(callback: (frame: PlaybackFrame) => void): Promise<UnlistenFn> {
+    return listen<PlaybackFrame>('playback-tick', (event) => callback(event.payload));
+  }
+
+}
@@ -7,11 +7,19 @@ const spacing = cubeSize * 4;
 export const useCubeStore = defineStore('cube', {
   state: () => ({
     cubeState: undefined as number[][][] | undefined,
+    size: 3,
+    lastScramble: undefined as { scramble: string, seed: number } | undefined,
+    historyPosition: 0,
+    historyLength: 0,
+    // 回放时显示回放中的状态，而不是会话里的魔方
+    playbackFrame: undefined as { position: number, length: number, stage: number, stage_count: number, last_move: string | null, cube: number[][][], playing: boolean } | undefined,
+    showPlayback: false,
   }),
 
   getters: {
     cubes: (state) => {
-      if (!state.cubeState) return []
+      const cubeState = state.showPlayback && state.playbackFrame ? state.playbackFrame.cube : state.cubeState;
+      if (!cubeState) return []
 
       const cubeFaces = {
         top: 0,
@@ -28,7 +36,7 @@ export const useCubeStore = defineStore('cube', {
           size: cubeSize * 0.8,
           rotateX: -45,
           rotateY: 45,
-          cubeState: state.cubeState,
+          cubeState,
           coveredFaces: {
             ...cubeFaces,
             front: 3,
@@ -41,7 +49,7 @@ export const useCubeStore = defineStore('cube', {
           size: cubeSize * 0.85,
           rotateX: -45,
           rotateY: 45,
-          cubeState: state.cubeState,
+          cubeState,
           coveredFaces: {
             ...cubeFaces,
             left: 5,
@@ -54,7 +62,7 @@ export const useCubeStore = defineStore('cube', {
           size: cubeSize,
           rotateX: -45,
           rotateY: 45,
-          cubeState: state.cubeState,
+          cubeState,
           coveredFaces: { ...cubeFaces, }
         },
         {
@@ -63,7 +71,7 @@ export const useCubeStore = defineStore('cube', {
           size: cubeSize * 0.75,
           rotateX: -45,
           rotateY: 45,
-          cubeState: state.cubeState,
+          cubeState,
           coveredFaces: {
             ...cubeFaces,
             top: 1,
@@ -75,50 +83,97 @@ export const useCubeStore = defineStore('cube', {
   },
 
   actions: {
+    // 魔方状态由 Rust 端的会话持有，所有窗口通过 cube-changed 事件同步
+    async subscribe() {
+      const unlistenCube = await TauriService.onCubeChanged((changed) => this.applyChange(changed));
+      const unlistenPlayback = await TauriService.onPlaybackTick((frame) => {
+        this.playbackFrame = frame;
+      });
+      return () => {
+        unlistenCube();
+        unlistenPlayback();
+      };
+    },
+    applyChange(changed: { size: number, cube: number[][][], position: number, length: number }) {
+      this.size = changed.size;
+      this.cubeState = changed.cube;
+      this.historyPosition = changed.position;
+      this.historyLength = changed.length;
+    },
     async initCubeState() {
       const response = await TauriService.initCubeState();
-      if (response.success) {
-        this.cubeState = response.result;
+      if (response.success && response.result) {
+        this.applyChange(response.result);
       }
       return response;
     },
+    async reset() {
+      return TauriService.reset();
+    },
+    async setSize(size: number) {
+      return TauriService.setSize({ size });
+    },
+    async initFromColors(colors: (number | null)[][][]) {
+      return TauriService.initFromColors({ colors });
+    },
+    async initFromFacelets(facelets: string) {
+      return TauriService.initFromFacelets({ facelets });
+    },
+    async exportFacelets(colors = false) {
+      return TauriService.exportFacelets({ colors });
+    },
     async handleRotation({ face, direction }) {
-      const params = {
-        state: this.cubeState as number[][][],
-        face,
-        direction: direction === 0
-      }
-
-      const response = await TauriService.handleRotation(params);
-      if (response.success) {
-        this.cubeState = response.result;
-      }
-      return response;
+      return TauriService.handleRotation({ face, direction: direction === 0 });
     },
-    async handleShuffle(times: number) {
-      const params = {
-        state: this.cubeState as number[][][],
-        times
-      };
-      const response = await TauriService.handleShuffle(params);
-      if (response.success) {
-        this.cubeState = response.result;
+    async applyAlgorithm(algorithm: string) {
+      return TauriService.applyAlgorithm({ algorithm });
+    },
+    // times 不传时按阶数取比赛的打乱步数
+    async handleShuffle(times?: number, seed?: number) {
+      const response = await TauriService.handleShuffle({ times, seed });
+      if (response.success && response.result) {
+        this.lastScramble = { scramble: response.result.scramble, seed: response.result.seed };
       }
       return response;
     },
+    async undo() {
+      return TauriService.undo();
+    },
+    async redo() {
+      return TauriService.redo();
+    },
+    async resetTo(index: number) {
+      return TauriService.resetTo({ index });
+    },
 
     async solveLayer(target: number) {
-      const params = {
-        state: this.cubeState as number[][][],
-        target
-      };
-      const response = await TauriService.solveLayer(params);
-      if (response.success) {
-        if (response.result && 'cube' in response.result) {
-          this.cubeState = response.result.cube;
-        }
+      return TauriService.solveLayer({ target });
+    },
+
+    recordFrame<T extends { success: boolean, result?: any }>(response: T) {
+      if (response.success && response.result) {
+        this.playbackFrame = response.result;
       }
       return response;
     },
+    setShowPlayback(show: boolean) {
+      this.showPlayback = show;
+    },
+    async playbackSeek(position: number) {
+      return this.recordFrame(await TauriService.playbackSeek({ position }));
+    },
+    async playbackStep(forward: boolean) {
+      return this.recordFrame(await TauriService.playbackStep({ forward }));
+    },
+    async playbackStage(forward: boolean) {
+      return this.recordFrame(await TauriService.playbackStage({ forward }));
+    },
+    async playbackPlay(intervalMs?: number) {
+      this.showPlayback = true;
+      return this.recordFrame(await TauriService.playbackPlay({ intervalMs }));
+    },
+    async playbackPause() {
+      return this.recordFrame(await TauriService.playbackPause());
+    },
   },
-})
\ No newline at end of file
+})